@@ -0,0 +1,641 @@
+//! 音频格式工具：WAV 读写，以及识别前的解码、混缩、重采样（含分段流式转换）与峰值归一化
+
+use crate::tr;
+
+/// 16 位小端 PCM 的 WAV 文件头（44 字节）
+///
+/// `data_len` 为 None 时按流式输出处理，长度字段写为最大值，播放器会读到流结束为止。
+pub fn wav_header(sample_rate: u32, channels: u16, data_len: Option<u32>) -> Vec<u8> {
+    const BITS_PER_SAMPLE: u16 = 16;
+    let block_align = channels * BITS_PER_SAMPLE / 8;
+    let byte_rate = sample_rate * block_align as u32;
+    let (riff_len, data_len) = match data_len {
+        Some(len) => (len.saturating_add(36), len),
+        None => (u32::MAX, u32::MAX),
+    };
+
+    let mut header = Vec::with_capacity(44);
+    header.extend_from_slice(b"RIFF");
+    header.extend_from_slice(&riff_len.to_le_bytes());
+    header.extend_from_slice(b"WAVEfmt ");
+    header.extend_from_slice(&16u32.to_le_bytes());
+    header.extend_from_slice(&1u16.to_le_bytes());
+    header.extend_from_slice(&channels.to_le_bytes());
+    header.extend_from_slice(&sample_rate.to_le_bytes());
+    header.extend_from_slice(&byte_rate.to_le_bytes());
+    header.extend_from_slice(&block_align.to_le_bytes());
+    header.extend_from_slice(&BITS_PER_SAMPLE.to_le_bytes());
+    header.extend_from_slice(b"data");
+    header.extend_from_slice(&data_len.to_le_bytes());
+    header
+}
+
+/// WAV 格式码：整数 PCM、IEEE 浮点与 WAVE_FORMAT_EXTENSIBLE
+const WAVE_FORMAT_PCM: u16 = 1;
+const WAVE_FORMAT_IEEE_FLOAT: u16 = 3;
+const WAVE_FORMAT_EXTENSIBLE: u16 = 0xfffe;
+
+/// WAV 文件的 PCM 格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WavFormat {
+    pub sample_rate: u32,
+    pub channels: u16,
+    pub bits_per_sample: u16,
+    /// 采样为 IEEE 浮点数而非整数
+    pub float: bool,
+}
+
+impl WavFormat {
+    /// 16 位整数 PCM
+    pub const fn pcm16(sample_rate: u32, channels: u16) -> Self {
+        Self {
+            sample_rate,
+            channels,
+            bits_per_sample: 16,
+            float: false,
+        }
+    }
+}
+
+/// 解析 WAV 文件，返回格式与 data 块内容
+///
+/// 只支持未压缩的整数或浮点 PCM。流式输出的 WAV 常把长度写成 0 或最大值，此时 data 块取到文件末尾。
+pub fn parse_wav(bytes: &[u8]) -> Result<(WavFormat, &[u8]), String> {
+    if bytes.len() < 12 || &bytes[..4] != b"RIFF" || &bytes[8..12] != b"WAVE" {
+        return Err(tr!("audio.not_wav"));
+    }
+
+    let mut format = None;
+    let mut offset = 12;
+    while offset + 8 <= bytes.len() {
+        let id = &bytes[offset..offset + 4];
+        let len = u32::from_le_bytes(bytes[offset + 4..offset + 8].try_into().unwrap()) as usize;
+        let body = offset + 8;
+        match id {
+            b"fmt " if len >= 16 && body + 16 <= bytes.len() => {
+                let field =
+                    |at: usize| u16::from_le_bytes([bytes[body + at], bytes[body + at + 1]]);
+                // 扩展格式的实际编码在子格式 GUID 的前两个字节
+                let tag = match field(0) {
+                    WAVE_FORMAT_EXTENSIBLE if len >= 26 && body + 26 <= bytes.len() => field(24),
+                    tag => tag,
+                };
+                if tag != WAVE_FORMAT_PCM && tag != WAVE_FORMAT_IEEE_FLOAT {
+                    return Err(tr!("audio.unsupported_encoding", tag = tag));
+                }
+                format = Some(WavFormat {
+                    sample_rate: u32::from_le_bytes(bytes[body + 4..body + 8].try_into().unwrap()),
+                    channels: field(2),
+                    bits_per_sample: field(14),
+                    float: tag == WAVE_FORMAT_IEEE_FLOAT,
+                });
+            }
+            b"data" => {
+                let format = format.ok_or_else(|| tr!("audio.missing_fmt"))?;
+                let end = match len {
+                    0 | 0xffff_ffff => bytes.len(),
+                    len => bytes.len().min(body + len),
+                };
+                return Ok((format, &bytes[body..end]));
+            }
+            _ => {}
+        }
+        // 块长度为奇数时有一个填充字节
+        offset = body.saturating_add(len + len % 2);
+    }
+    Err(tr!("audio.missing_data"))
+}
+
+/// 16 位小端 PCM 转为采样，末尾不足一个采样的字节被忽略
+pub fn pcm16_samples(bytes: &[u8]) -> Vec<i16> {
+    bytes
+        .chunks_exact(2)
+        .map(|pair| i16::from_le_bytes([pair[0], pair[1]]))
+        .collect()
+}
+
+/// 重采样低通滤波器单侧的过零点数，越大过渡带越窄
+const RESAMPLE_ZERO_CROSSINGS: f64 = 16.0;
+
+/// 解码后的音频，采样为 [-1, 1] 的浮点数，多声道交错排列
+#[derive(Debug, Clone, PartialEq)]
+pub struct PcmAudio {
+    pub sample_rate: u32,
+    pub channels: u16,
+    pub samples: Vec<f32>,
+}
+
+impl PcmAudio {
+    /// 16 位小端裸 PCM
+    pub fn from_pcm16(bytes: &[u8], sample_rate: u32, channels: u16) -> Self {
+        let samples = pcm16_samples(bytes)
+            .into_iter()
+            .map(|sample| sample as f32 / 32768.0)
+            .collect();
+        Self {
+            sample_rate,
+            channels: channels.max(1),
+            samples,
+        }
+    }
+
+    /// 解码 WAV：8 / 16 / 24 / 32 位整数与 32 / 64 位浮点
+    pub fn from_wav(bytes: &[u8]) -> Result<Self, String> {
+        let (format, data) = parse_wav(bytes)?;
+        if format.channels == 0 || format.sample_rate == 0 {
+            return Err(tr!("audio.invalid_format"));
+        }
+        let width = (format.bits_per_sample as usize).div_ceil(8);
+        let decode: fn(&[u8]) -> f32 = match (format.float, width) {
+            (false, 1) => |b| (b[0] as f32 - 128.0) / 128.0,
+            (false, 2) => |b| i16::from_le_bytes([b[0], b[1]]) as f32 / 32768.0,
+            (false, 3) => |b| i32::from_le_bytes([0, b[0], b[1], b[2]]) as f32 / 2_147_483_648.0,
+            (false, 4) => |b| i32::from_le_bytes([b[0], b[1], b[2], b[3]]) as f32 / 2_147_483_648.0,
+            (true, 4) => |b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]),
+            (true, 8) => |b| f64::from_le_bytes(b.try_into().unwrap()) as f32,
+            _ => {
+                let key = if format.float {
+                    "audio.unsupported_float_bits"
+                } else {
+                    "audio.unsupported_bits"
+                };
+                return Err(tr!(key, bits = format.bits_per_sample));
+            }
+        };
+
+        // 丢弃末尾不完整的采样帧
+        let frame = width * format.channels as usize;
+        let data = &data[..data.len() - data.len() % frame];
+        Ok(Self {
+            sample_rate: format.sample_rate,
+            channels: format.channels,
+            samples: data.chunks_exact(width).map(decode).collect(),
+        })
+    }
+
+    /// 采样帧数（每帧包含各声道一个采样）
+    pub fn frames(&self) -> usize {
+        self.samples.len() / self.channels.max(1) as usize
+    }
+
+    pub fn duration_ms(&self) -> u64 {
+        self.frames() as u64 * 1000 / self.sample_rate.max(1) as u64
+    }
+
+    /// 转为指定声道数：多声道先取平均混成单声道，再按需复制到各声道
+    pub fn remix(self, channels: u16) -> Self {
+        let channels = channels.max(1);
+        if channels == self.channels {
+            return self;
+        }
+        let mono: Vec<f32> = if self.channels == 1 {
+            self.samples
+        } else {
+            self.samples
+                .chunks_exact(self.channels as usize)
+                .map(|frame| frame.iter().sum::<f32>() / frame.len() as f32)
+                .collect()
+        };
+        let samples = mono
+            .into_iter()
+            .flat_map(|sample| std::iter::repeat_n(sample, channels as usize))
+            .collect();
+        Self {
+            sample_rate: self.sample_rate,
+            channels,
+            samples,
+        }
+    }
+
+    /// 带限重采样（Hann 窗 sinc 插值），降采样时截止频率取目标采样率的一半以免混叠
+    pub fn resample(self, sample_rate: u32) -> Self {
+        if sample_rate == 0 || sample_rate == self.sample_rate {
+            return self;
+        }
+        if self.samples.is_empty() {
+            return Self {
+                sample_rate,
+                ..self
+            };
+        }
+
+        let channels = self.channels as usize;
+        let frames = self.frames();
+        let ratio = sample_rate as f64 / self.sample_rate as f64;
+        let out_frames = (frames as f64 * ratio).round() as usize;
+
+        let kernel = ResampleKernel::new(ratio, channels);
+        let mut samples = Vec::with_capacity(out_frames * channels);
+        let mut sums = vec![0.0f64; channels];
+        for index in 0..out_frames {
+            kernel.interpolate(&self.samples, 0, index as f64 / ratio, &mut sums);
+            samples.extend(sums.iter().map(|&sum| sum as f32));
+        }
+
+        Self {
+            sample_rate,
+            channels: self.channels,
+            samples,
+        }
+    }
+
+    /// 峰值归一化到 `peak_db`（dBFS），增益不超过 `max_gain_db`，避免把底噪放得过大
+    pub fn normalize(mut self, peak_db: f32, max_gain_db: f32) -> Self {
+        let peak = self
+            .samples
+            .iter()
+            .fold(0.0f32, |peak, sample| peak.max(sample.abs()));
+        if peak <= f32::EPSILON {
+            return self;
+        }
+        let target = 10f32.powf(peak_db / 20.0);
+        let gain = (target / peak).min(10f32.powf(max_gain_db / 20.0));
+        for sample in &mut self.samples {
+            *sample *= gain;
+        }
+        self
+    }
+
+    /// 编码为 16 位小端 PCM，超出范围的采样被截断
+    pub fn to_pcm16(&self) -> Vec<u8> {
+        self.samples
+            .iter()
+            .flat_map(|&sample| {
+                let value = (sample * 32768.0).round().clamp(-32768.0, 32767.0) as i16;
+                value.to_le_bytes()
+            })
+            .collect()
+    }
+
+    /// 编码为 16 位 WAV 文件
+    pub fn to_wav(&self) -> Vec<u8> {
+        let data = self.to_pcm16();
+        let mut wav = wav_header(self.sample_rate, self.channels, Some(data.len() as u32));
+        wav.extend_from_slice(&data);
+        wav
+    }
+}
+
+/// 带限插值的滤波器（Hann 窗 sinc），整段与流式重采样共用
+struct ResampleKernel {
+    /// 相对源采样率奈奎斯特频率的截止比例
+    cutoff: f64,
+    /// 以源采样为单位的滤波器半径
+    radius: f64,
+    channels: usize,
+}
+
+impl ResampleKernel {
+    fn new(ratio: f64, channels: usize) -> Self {
+        let cutoff = ratio.min(1.0);
+        Self {
+            cutoff,
+            radius: RESAMPLE_ZERO_CROSSINGS / cutoff,
+            channels,
+        }
+    }
+
+    /// 在源采样帧序号 `center` 处插值，各声道结果写入 `out`
+    ///
+    /// `samples` 为交错排列的源采样，首帧序号为 `offset`；超出 `samples` 的窗口部分按截断处理。
+    fn interpolate(&self, samples: &[f32], offset: usize, center: f64, out: &mut [f64]) {
+        let channels = self.channels;
+        let end = offset + samples.len() / channels;
+        let first = (center - self.radius).ceil().max(offset as f64) as usize;
+        let last = ((center + self.radius).floor() as usize).min(end - 1);
+
+        out.fill(0.0);
+        let mut weight_sum = 0.0;
+        for source in first..=last {
+            let distance = source as f64 - center;
+            let weight = sinc(self.cutoff * distance) * hann(distance / self.radius);
+            weight_sum += weight;
+            let index = source - offset;
+            let frame = &samples[index * channels..(index + 1) * channels];
+            for (sum, &sample) in out.iter_mut().zip(frame) {
+                *sum += sample as f64 * weight;
+            }
+        }
+        // 按权重和归一，直流增益为 1，首尾被截断的窗口也不会变暗
+        let scale = if weight_sum.abs() > f64::EPSILON {
+            1.0 / weight_sum
+        } else {
+            0.0
+        };
+        for sum in out.iter_mut() {
+            *sum *= scale;
+        }
+    }
+}
+
+/// 流式转换 16 位小端 PCM 的声道数与采样率，分段按到达顺序喂入
+///
+/// 与 [`PcmAudio::resample`] 使用同一滤波器，跨分段保留滤波窗口所需的采样，
+/// 因此输出比输入滞后约一个滤波器半径（几毫秒），结束时用 [`PcmConverter::finish`] 取出剩余部分。
+#[derive(Debug)]
+pub struct PcmConverter {
+    from_rate: u32,
+    from_channels: u16,
+    to_rate: u32,
+    to_channels: u16,
+    /// 不足一帧的剩余字节
+    pending: Vec<u8>,
+    /// 已混缩到目标声道、仍在滤波窗口内的源采样
+    samples: Vec<f32>,
+    /// `samples` 首帧在整个输入中的帧序号
+    offset: usize,
+    /// 下一个输出帧的序号
+    next_output: usize,
+}
+
+impl PcmConverter {
+    pub fn new(from_rate: u32, from_channels: u16, to_rate: u32, to_channels: u16) -> Self {
+        Self {
+            from_rate: from_rate.max(1),
+            from_channels: from_channels.max(1),
+            to_rate: to_rate.max(1),
+            to_channels: to_channels.max(1),
+            pending: Vec::new(),
+            samples: Vec::new(),
+            offset: 0,
+            next_output: 0,
+        }
+    }
+
+    /// 输入与输出格式相同，原样透传
+    pub fn is_passthrough(&self) -> bool {
+        self.from_rate == self.to_rate && self.from_channels == self.to_channels
+    }
+
+    /// 喂入一段输入，返回已可确定的输出
+    pub fn push(&mut self, bytes: &[u8]) -> Vec<u8> {
+        if self.is_passthrough() {
+            return bytes.to_vec();
+        }
+        self.pending.extend_from_slice(bytes);
+        let frame_bytes = 2 * self.from_channels as usize;
+        let whole = self.pending.len() - self.pending.len() % frame_bytes;
+        let audio =
+            PcmAudio::from_pcm16(&self.pending[..whole], self.from_rate, self.from_channels)
+                .remix(self.to_channels);
+        self.pending.drain(..whole);
+
+        if self.from_rate == self.to_rate {
+            return audio.to_pcm16();
+        }
+        self.samples.extend(audio.samples);
+        self.drain(false)
+    }
+
+    /// 输入结束：用截断的滤波窗口输出剩余部分
+    pub fn finish(&mut self) -> Vec<u8> {
+        if self.is_passthrough() || self.from_rate == self.to_rate {
+            return Vec::new();
+        }
+        self.drain(true)
+    }
+
+    fn drain(&mut self, flush: bool) -> Vec<u8> {
+        let channels = self.to_channels as usize;
+        let ratio = self.to_rate as f64 / self.from_rate as f64;
+        let kernel = ResampleKernel::new(ratio, channels);
+        let end = self.offset + self.samples.len() / channels;
+
+        let mut output = PcmAudio {
+            sample_rate: self.to_rate,
+            channels: self.to_channels,
+            samples: Vec::new(),
+        };
+        let mut sums = vec![0.0f64; channels];
+        loop {
+            let center = self.next_output as f64 / ratio;
+            // 整段重采样输出 round(帧数 × 比例) 帧，流式结束时保持一致
+            let complete = if flush {
+                self.next_output >= (end as f64 * ratio).round() as usize
+            } else {
+                center + kernel.radius >= end as f64
+            };
+            if complete || self.samples.is_empty() {
+                break;
+            }
+            kernel.interpolate(&self.samples, self.offset, center, &mut sums);
+            output.samples.extend(sums.iter().map(|&sum| sum as f32));
+            self.next_output += 1;
+        }
+
+        // 丢弃之后的滤波窗口不再用到的采样
+        let needed = (self.next_output as f64 / ratio - kernel.radius)
+            .ceil()
+            .max(0.0) as usize;
+        let drop = needed.saturating_sub(self.offset).min(end - self.offset);
+        self.samples.drain(..drop * channels);
+        self.offset += drop;
+
+        output.to_pcm16()
+    }
+}
+
+fn sinc(x: f64) -> f64 {
+    if x.abs() < 1e-9 {
+        return 1.0;
+    }
+    let x = x * std::f64::consts::PI;
+    x.sin() / x
+}
+
+/// Hann 窗，`t` 的取值范围为 [-1, 1]
+fn hann(t: f64) -> f64 {
+    if t.abs() >= 1.0 {
+        return 0.0;
+    }
+    0.5 * (1.0 + (t * std::f64::consts::PI).cos())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wav_header_layout() {
+        let header = wav_header(24000, 1, Some(4800));
+        assert_eq!(header.len(), 44);
+        assert_eq!(&header[..4], b"RIFF");
+        assert_eq!(u32::from_le_bytes(header[4..8].try_into().unwrap()), 4836);
+        assert_eq!(
+            u32::from_le_bytes(header[28..32].try_into().unwrap()),
+            48000
+        );
+        assert_eq!(u32::from_le_bytes(header[40..44].try_into().unwrap()), 4800);
+
+        let streaming = wav_header(16000, 1, None);
+        assert_eq!(&streaming[40..44], &[0xff; 4]);
+    }
+
+    #[test]
+    fn parse_wav_skips_extra_chunks() {
+        let mut wav = wav_header(22050, 1, Some(4));
+        // 在 fmt 与 data 之间插入一个 LIST 块
+        let list = [b"LIST".as_slice(), &3u32.to_le_bytes(), b"abc\0"].concat();
+        wav.splice(36..36, list);
+        wav.extend_from_slice(&[1, 2, 3, 4, 5]);
+
+        let (format, data) = parse_wav(&wav).unwrap();
+        assert_eq!(format, WavFormat::pcm16(22050, 1));
+        assert_eq!(data, &[1, 2, 3, 4]);
+
+        let streaming = [wav_header(16000, 1, None), vec![9; 6]].concat();
+        assert_eq!(parse_wav(&streaming).unwrap().1, &[9; 6]);
+        assert!(parse_wav(b"ID3 not a wav").is_err());
+    }
+
+    /// 手工拼装任意编码的 WAV
+    fn wav(tag: u16, channels: u16, sample_rate: u32, bits: u16, data: &[u8]) -> Vec<u8> {
+        let block_align = channels * bits / 8;
+        [
+            b"RIFF".as_slice(),
+            &(36 + data.len() as u32).to_le_bytes(),
+            b"WAVEfmt ",
+            &16u32.to_le_bytes(),
+            &tag.to_le_bytes(),
+            &channels.to_le_bytes(),
+            &sample_rate.to_le_bytes(),
+            &(sample_rate * block_align as u32).to_le_bytes(),
+            &block_align.to_le_bytes(),
+            &bits.to_le_bytes(),
+            b"data",
+            &(data.len() as u32).to_le_bytes(),
+            data,
+        ]
+        .concat()
+    }
+
+    #[test]
+    fn decodes_integer_and_float_wav() {
+        // 24 位立体声：满幅负值与一半正值
+        let data = [0x00, 0x00, 0x80, 0x00, 0x00, 0x40];
+        let audio = PcmAudio::from_wav(&wav(1, 2, 44100, 24, &data)).unwrap();
+        assert_eq!((audio.sample_rate, audio.channels), (44100, 2));
+        assert_eq!(audio.samples, vec![-1.0, 0.5]);
+
+        let data = [0.25f32.to_le_bytes(), (-0.75f32).to_le_bytes()].concat();
+        let audio = PcmAudio::from_wav(&wav(3, 1, 48000, 32, &data)).unwrap();
+        assert_eq!(audio.samples, vec![0.25, -0.75]);
+
+        let audio = PcmAudio::from_wav(&wav(1, 1, 8000, 8, &[0, 128, 255])).unwrap();
+        assert_eq!(audio.samples, vec![-1.0, 0.0, 127.0 / 128.0]);
+
+        assert!(PcmAudio::from_wav(&wav(1, 1, 8000, 64, &[0; 8])).is_err());
+        assert!(PcmAudio::from_wav(&wav(2, 1, 8000, 4, &[0, 0])).is_err());
+    }
+
+    #[test]
+    fn remix_and_resample_keep_the_signal() {
+        let sine = |rate: u32, frame: usize| {
+            (frame as f64 * 2.0 * std::f64::consts::PI * 440.0 / rate as f64).sin() as f32 * 0.5
+        };
+        // 48kHz 立体声，右声道反相的高频噪声在混缩后抵消
+        let samples = (0..4800)
+            .flat_map(|frame| {
+                let noise = if frame % 2 == 0 { 0.2 } else { -0.2 };
+                [sine(48000, frame) + noise, sine(48000, frame) - noise]
+            })
+            .collect();
+        let audio = PcmAudio {
+            sample_rate: 48000,
+            channels: 2,
+            samples,
+        }
+        .remix(1)
+        .resample(16000);
+
+        assert_eq!((audio.sample_rate, audio.channels), (16000, 1));
+        assert_eq!(audio.frames(), 1600);
+        assert_eq!(audio.duration_ms(), 100);
+        // 避开首尾被截断的滤波窗口
+        for frame in 100..1500 {
+            assert!((audio.samples[frame] - sine(16000, frame)).abs() < 0.01);
+        }
+    }
+
+    #[test]
+    fn downsampling_filters_out_aliases() {
+        // 7kHz 高于 8kHz 采样率的奈奎斯特频率，应被滤掉而不是折叠成 1kHz
+        let samples = (0..16000)
+            .map(|frame| {
+                (frame as f64 * 2.0 * std::f64::consts::PI * 7000.0 / 16000.0).sin() as f32
+            })
+            .collect();
+        let audio = PcmAudio {
+            sample_rate: 16000,
+            channels: 1,
+            samples,
+        }
+        .resample(8000);
+        let peak = audio.samples[200..7800]
+            .iter()
+            .fold(0.0f32, |peak, sample| peak.max(sample.abs()));
+        assert!(peak < 0.05, "alias peak {}", peak);
+    }
+
+    #[test]
+    fn streaming_conversion_matches_whole_clip() {
+        let samples: Vec<f32> = (0..4801)
+            .map(|frame| (frame as f64 * 0.013).sin() as f32 * 0.6)
+            .collect();
+        let bytes = PcmAudio {
+            sample_rate: 16000,
+            channels: 1,
+            samples,
+        }
+        .to_pcm16();
+
+        for (to_rate, to_channels) in [(24000, 1), (8000, 2), (16000, 2)] {
+            let whole = PcmAudio::from_pcm16(&bytes, 16000, 1)
+                .remix(to_channels)
+                .resample(to_rate)
+                .to_pcm16();
+
+            // 奇数字节的分段：半个采样留到下一段
+            let mut converter = PcmConverter::new(16000, 1, to_rate, to_channels);
+            let mut streamed = Vec::new();
+            for chunk in bytes.chunks(1001) {
+                streamed.extend(converter.push(chunk));
+            }
+            streamed.extend(converter.finish());
+            assert_eq!(streamed, whole, "{} Hz x{}", to_rate, to_channels);
+        }
+
+        let mut passthrough = PcmConverter::new(16000, 1, 16000, 1);
+        assert!(passthrough.is_passthrough());
+        assert_eq!(passthrough.push(&bytes[..3]), &bytes[..3]);
+        assert!(passthrough.finish().is_empty());
+    }
+
+    #[test]
+    fn normalize_and_encode() {
+        let audio = PcmAudio {
+            sample_rate: 16000,
+            channels: 1,
+            samples: vec![0.25, -0.5, 0.1],
+        }
+        .normalize(-6.0206, 20.0);
+        assert!((audio.samples[1] + 0.5).abs() < 1e-4);
+        assert!((audio.samples[0] - 0.25).abs() < 1e-4);
+
+        // 增益受上限约束：-60 dBFS 的信号只放大 20 dB
+        let quiet = PcmAudio {
+            sample_rate: 16000,
+            channels: 1,
+            samples: vec![0.001, -1.0 / 1000.0],
+        }
+        .normalize(-1.0, 20.0);
+        assert!((quiet.samples[0] - 0.01).abs() < 1e-5);
+
+        let loud = PcmAudio::from_pcm16(&[0x00, 0x80, 0xff, 0x7f], 16000, 1);
+        assert_eq!(loud.to_pcm16(), vec![0x00, 0x80, 0xff, 0x7f]);
+        let wav = loud.to_wav();
+        let (format, data) = parse_wav(&wav).unwrap();
+        assert_eq!(format, WavFormat::pcm16(16000, 1));
+        assert_eq!(data.len(), 4);
+    }
+}
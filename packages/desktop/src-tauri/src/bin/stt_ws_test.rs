@@ -1,4 +1,6 @@
 use futures_util::{SinkExt, StreamExt};
+use reason_desktop_lib::volcengine;
+use reason_desktop_lib::volcengine::protocol::{Compression, Frame, Serialization};
 use serde::{Deserialize, Serialize};
 use std::{env, fs, path::Path};
 use tokio::time::{timeout_at, Duration, Instant};
@@ -22,22 +24,32 @@ struct VolcengineConfig {
     access_token: String,
     #[serde(rename = "resourceId", default)]
     legacy_resource_id: Option<String>,
+    #[serde(rename = "enableGzip", default)]
+    enable_gzip: bool,
     #[serde(default)]
     stt: SttConfig,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Default, Deserialize)]
 struct SttConfig {
     #[serde(rename = "resourceId", default)]
     resource_id: String,
-}
-
-impl Default for SttConfig {
-    fn default() -> Self {
-        Self {
-            resource_id: String::new(),
-        }
-    }
+    #[serde(rename = "modelName", default)]
+    model_name: Option<String>,
+    #[serde(rename = "enableItn", default)]
+    enable_itn: Option<bool>,
+    #[serde(rename = "enablePunc", default)]
+    enable_punc: Option<bool>,
+    #[serde(rename = "enableDdc", default)]
+    enable_ddc: Option<bool>,
+    #[serde(default)]
+    language: Option<String>,
+    #[serde(rename = "sampleRate", default)]
+    sample_rate: Option<i32>,
+    #[serde(default)]
+    channel: Option<i32>,
+    #[serde(rename = "endWindowSize", default)]
+    end_window_size: Option<u32>,
 }
 
 #[derive(Debug, Serialize)]
@@ -59,6 +71,8 @@ struct SttAudioConfig {
     rate: i32,
     bits: i32,
     channel: i32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    language: Option<String>,
 }
 
 #[derive(Debug, Serialize)]
@@ -70,6 +84,10 @@ struct SttRequestConfig {
     enable_itn: Option<bool>,
     #[serde(skip_serializing_if = "Option::is_none")]
     enable_punc: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    enable_ddc: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    end_window_size: Option<u32>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -113,75 +131,42 @@ fn read_volcengine_config() -> Result<(VolcengineConfig, String), String> {
     Ok((volcengine, resource_id))
 }
 
-fn build_full_client_request(config: &SttRequest) -> Vec<u8> {
-    let payload = serde_json::to_vec(config).unwrap();
-    let payload_size = (payload.len() as u32).to_be_bytes();
-
-    let mut message = Vec::with_capacity(8 + payload.len());
-    message.push(0x11);
-    message.push(0x10);
-    message.push(0x10);
-    message.push(0x00);
-    message.extend_from_slice(&payload_size);
-    message.extend_from_slice(&payload);
-    message
+fn build_full_client_request(
+    config: &SttRequest,
+    compression: Compression,
+) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+    let payload = serde_json::to_vec(config)?;
+    Ok(Frame::full_client_request(payload)
+        .compress(compression)?
+        .encode()?)
 }
 
-fn build_audio_only_request(audio_data: &[u8], is_last: bool) -> Vec<u8> {
-    let payload_size = (audio_data.len() as u32).to_be_bytes();
-    let flags = if is_last { 0x02 } else { 0x00 };
-
-    let mut message = Vec::with_capacity(8 + audio_data.len());
-    message.push(0x11);
-    message.push(0x20 | flags);
-    message.push(0x00);
-    message.push(0x00);
-    message.extend_from_slice(&payload_size);
-    message.extend_from_slice(audio_data);
-    message
+fn build_audio_only_request(
+    audio_data: &[u8],
+    is_last: bool,
+    compression: Compression,
+) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+    Ok(Frame::audio_only_request(audio_data.to_vec(), is_last)
+        .compress(compression)?
+        .encode()?)
 }
 
 fn parse_server_payload(data: &[u8]) -> Result<Option<String>, String> {
-    if data.len() < 12 {
-        return Ok(None);
-    }
-    let header_size = ((data[0] & 0x0f) as usize) * 4;
-    if data.len() < header_size + 8 {
-        return Ok(None);
-    }
-
-    let message_type = data[1] >> 4;
-    let serialization = data[2] >> 4;
-    let compression = data[2] & 0x0f;
-    if compression != 0 {
-        return Err("Unsupported compression in server response".to_string());
-    }
-    if serialization != 0x01 && serialization != 0x00 {
+    let frame = Frame::decode(data)
+        .and_then(Frame::decompress)
+        .map_err(|e| format!("Invalid server frame: {}", e))?;
+    if frame.serialization != Serialization::Json && frame.serialization != Serialization::None {
         return Err("Unsupported serialization in server response".to_string());
     }
 
-    let payload_size_offset = header_size + 4;
-    if data.len() < payload_size_offset + 4 {
-        return Ok(None);
-    }
-
-    let payload_size = u32::from_be_bytes([
-        data[payload_size_offset],
-        data[payload_size_offset + 1],
-        data[payload_size_offset + 2],
-        data[payload_size_offset + 3],
-    ]) as usize;
+    let payload_text = String::from_utf8_lossy(&frame.payload).to_string();
 
-    let payload_offset = header_size + 8;
-    if data.len() < payload_offset + payload_size {
-        return Ok(None);
-    }
-    let payload = &data[payload_offset..payload_offset + payload_size];
-    let payload_text = String::from_utf8_lossy(payload).to_string();
-
-    if message_type == 0b1111 {
+    if frame.is_error() {
         return Err(format!("Server error response: {}", payload_text));
     }
+    if payload_text.is_empty() {
+        return Ok(None);
+    }
 
     Ok(Some(payload_text))
 }
@@ -222,7 +207,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         Some(path) => path,
         None => {
             eprintln!(
-                "Usage: stt_ws_test <audio_path>\nEnv: VOLC_WS_URL, VOLC_WS_PROTOCOL, VOLC_WS_AUTH"
+                "Usage: stt_ws_test <audio_path>\nEnv: VOLC_WS_URL, VOLC_WS_PROTOCOL, VOLC_WS_AUTH, VOLC_MOCK_ADDR"
             );
             return Ok(());
         }
@@ -245,20 +230,27 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         audio: SttAudioConfig {
             format,
             codec,
-            rate: 16000,
+            rate: config.stt.sample_rate.unwrap_or(16000),
             bits: 16,
-            channel: 1,
+            channel: config.stt.channel.unwrap_or(1),
+            language: config.stt.language.clone(),
         },
         request: SttRequestConfig {
-            model_name: "bigmodel".to_string(),
+            model_name: config
+                .stt
+                .model_name
+                .clone()
+                .unwrap_or_else(|| "bigmodel".to_string()),
             show_utterances: true,
-            enable_itn: None,
-            enable_punc: None,
+            enable_itn: config.stt.enable_itn,
+            enable_punc: config.stt.enable_punc,
+            enable_ddc: config.stt.enable_ddc,
+            end_window_size: config.stt.end_window_size,
         },
     };
 
     let default_url = "wss://openspeech.bytedance.com/api/v3/sauc/bigmodel_async".to_string();
-    let url = env::var("VOLC_WS_URL").unwrap_or(default_url);
+    let url = env::var("VOLC_WS_URL").unwrap_or_else(|_| volcengine::endpoint(&default_url));
 
     let mut ws_request = url
         .into_client_request()
@@ -311,12 +303,17 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("WebSocket connected: {}", response.status());
 
     let (mut write, mut read) = ws_stream.split();
-    let message = build_full_client_request(&request);
+    let compression = if config.enable_gzip {
+        Compression::Gzip
+    } else {
+        Compression::None
+    };
+    let message = build_full_client_request(&request, compression)?;
     write
         .send(Message::Binary(message))
         .await
         .map_err(|e| format!("Failed to send request: {}", e))?;
-    let audio_message = build_audio_only_request(&audio_bytes, true);
+    let audio_message = build_audio_only_request(&audio_bytes, true, compression)?;
     write
         .send(Message::Binary(audio_message))
         .await
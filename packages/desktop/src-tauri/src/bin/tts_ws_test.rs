@@ -1,4 +1,9 @@
 use futures_util::{stream::Stream, SinkExt, StreamExt};
+use reason_desktop_lib::subtitles::{self, SubtitleTrack};
+use reason_desktop_lib::volcengine;
+use reason_desktop_lib::volcengine::protocol::{
+    Compression, Event, Frame, MessageType, Serialization,
+};
 use serde::Deserialize;
 use serde_json::json;
 use std::{env, fs, path::PathBuf};
@@ -10,31 +15,6 @@ use uuid::Uuid;
 
 const WS_ENDPOINT: &str = "wss://openspeech.bytedance.com/api/v3/tts/bidirection";
 
-const PROTOCOL_VERSION: u8 = 0x1;
-const HEADER_SIZE: u8 = 0x1;
-
-const MSG_TYPE_FULL_CLIENT: u8 = 0x1;
-const MSG_TYPE_AUDIO_ONLY_REQUEST: u8 = 0x2;
-const MSG_TYPE_AUDIO_ONLY_RESPONSE: u8 = 0xb;
-const MSG_TYPE_ERROR: u8 = 0xf;
-
-const FLAG_WITH_EVENT: u8 = 0x4;
-const SERIALIZATION_JSON: u8 = 0x1;
-const COMPRESSION_NONE: u8 = 0x0;
-
-const EVENT_START_CONNECTION: i32 = 1;
-const EVENT_FINISH_CONNECTION: i32 = 2;
-const EVENT_CONNECTION_STARTED: i32 = 50;
-const EVENT_CONNECTION_FINISHED: i32 = 52;
-const EVENT_START_SESSION: i32 = 100;
-const EVENT_FINISH_SESSION: i32 = 102;
-const EVENT_SESSION_STARTED: i32 = 150;
-const EVENT_SESSION_FINISHED: i32 = 152;
-const EVENT_TASK_REQUEST: i32 = 200;
-const EVENT_TTS_SENTENCE_START: i32 = 350;
-const EVENT_TTS_SENTENCE_END: i32 = 351;
-const EVENT_TTS_RESPONSE: i32 = 352;
-
 #[derive(Debug, Deserialize)]
 struct ReasonConfig {
     #[serde(default)]
@@ -49,6 +29,8 @@ struct VolcengineConfig {
     access_token: String,
     #[serde(rename = "resourceId", default)]
     legacy_resource_id: Option<String>,
+    #[serde(rename = "enableGzip", default)]
+    enable_gzip: bool,
     #[serde(default)]
     tts: TtsConfig,
 }
@@ -78,16 +60,6 @@ struct Args {
     output: PathBuf,
 }
 
-#[derive(Debug)]
-struct ParsedFrame {
-    message_type: u8,
-    flags: u8,
-    serialization: u8,
-    event: Option<i32>,
-    id: Option<String>,
-    payload: Vec<u8>,
-}
-
 fn parse_args() -> Result<Args, String> {
     let mut args = env::args().skip(1);
     let mut text = None;
@@ -147,115 +119,20 @@ fn read_volcengine_config() -> Result<(VolcengineConfig, String), String> {
     Ok((volcengine, resource_id))
 }
 
-fn build_full_client_frame(event: i32, session_id: Option<&str>, payload: &[u8]) -> Vec<u8> {
-    let mut frame = Vec::new();
-    frame.push((PROTOCOL_VERSION << 4) | HEADER_SIZE);
-    frame.push((MSG_TYPE_FULL_CLIENT << 4) | FLAG_WITH_EVENT);
-    frame.push((SERIALIZATION_JSON << 4) | COMPRESSION_NONE);
-    frame.push(0x00);
-    frame.extend_from_slice(&event.to_be_bytes());
-
-    if let Some(session_id) = session_id {
-        let id_bytes = session_id.as_bytes();
-        frame.extend_from_slice(&(id_bytes.len() as u32).to_be_bytes());
-        frame.extend_from_slice(id_bytes);
-    }
-
-    frame.extend_from_slice(&(payload.len() as u32).to_be_bytes());
-    frame.extend_from_slice(payload);
-    frame
-}
-
-fn parse_frame(data: &[u8]) -> Result<ParsedFrame, String> {
-    if data.len() < 4 {
-        return Err("Frame too short".to_string());
-    }
-
-    let header_size = (data[0] & 0x0f) as usize * 4;
-    if header_size > data.len() {
-        return Err("Invalid header size".to_string());
-    }
-
-    let message_type = data[1] >> 4;
-    let flags = data[1] & 0x0f;
-    let serialization = data[2] >> 4;
-
-    let mut offset = header_size;
-    let mut event = None;
-
-    if (flags & FLAG_WITH_EVENT) != 0 {
-        if offset + 4 > data.len() {
-            return Err("Missing event field".to_string());
-        }
-        event = Some(i32::from_be_bytes([
-            data[offset],
-            data[offset + 1],
-            data[offset + 2],
-            data[offset + 3],
-        ]));
-        offset += 4;
-    }
-
-    if message_type == MSG_TYPE_ERROR {
-        if offset + 4 > data.len() {
-            return Err("Missing error code".to_string());
-        }
-        let payload = data[offset + 4..].to_vec();
-        return Ok(ParsedFrame {
-            message_type,
-            flags,
-            serialization,
-            event,
-            id: None,
-            payload,
-        });
-    }
-
-    let mut id = None;
-    if offset + 4 <= data.len() {
-        let id_len = u32::from_be_bytes([
-            data[offset],
-            data[offset + 1],
-            data[offset + 2],
-            data[offset + 3],
-        ]) as usize;
-        offset += 4;
-
-        if offset + id_len <= data.len() && id_len > 0 {
-            id = Some(String::from_utf8_lossy(&data[offset..offset + id_len]).to_string());
-            offset += id_len;
-        }
-    }
-
-    let mut payload = Vec::new();
-    if offset + 4 <= data.len() {
-        let payload_len = u32::from_be_bytes([
-            data[offset],
-            data[offset + 1],
-            data[offset + 2],
-            data[offset + 3],
-        ]) as usize;
-        offset += 4;
-        if offset + payload_len <= data.len() {
-            payload = data[offset..offset + payload_len].to_vec();
-        } else if offset < data.len() {
-            payload = data[offset..].to_vec();
-        }
-    }
-
-    Ok(ParsedFrame {
-        message_type,
-        flags,
-        serialization,
-        event,
-        id,
-        payload,
-    })
+fn build_event_frame(
+    event: Event,
+    session_id: Option<&str>,
+    payload: &[u8],
+    compression: Compression,
+) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+    Ok(Frame::client_event(event, session_id, payload.to_vec())
+        .compress(compression)?
+        .encode()?)
 }
 
 async fn wait_for_event<S>(
     ws_read: &mut S,
-    target_event: i32,
+    target_event: Event,
 ) -> Result<(), Box<dyn std::error::Error>>
 where
     S: Stream<Item = Result<Message, tokio_tungstenite::tungstenite::Error>> + Unpin,
@@ -264,11 +141,9 @@ where
         let message = message?;
         match message {
             Message::Binary(data) => {
-                let frame = parse_frame(&data)?;
-                if let Some(event) = frame.event {
-                    if event == target_event {
-                        return Ok(());
-                    }
+                let frame = Frame::decode(&data)?.decompress()?;
+                if frame.event == Some(target_event) {
+                    return Ok(());
                 }
             }
             Message::Text(text) => {
@@ -288,8 +163,13 @@ async fn run() -> Result<(), Box<dyn std::error::Error>> {
 
     let connect_id = Uuid::new_v4().to_string();
     let session_id = Uuid::new_v4().to_string();
+    let compression = if config.enable_gzip {
+        Compression::Gzip
+    } else {
+        Compression::None
+    };
 
-    let mut request = WS_ENDPOINT.into_client_request()?;
+    let mut request = volcengine::endpoint(WS_ENDPOINT).into_client_request()?;
     let headers = request.headers_mut();
     headers.insert("X-Api-App-Key", config.app_id.parse()?);
     headers.insert("X-Api-Access-Key", config.access_token.parse()?);
@@ -299,14 +179,14 @@ async fn run() -> Result<(), Box<dyn std::error::Error>> {
     let (ws_stream, _) = connect_async(request).await?;
     let (mut ws_write, mut ws_read) = ws_stream.split();
 
-    let start_conn = build_full_client_frame(EVENT_START_CONNECTION, None, b"{}");
+    let start_conn = build_event_frame(Event::StartConnection, None, b"{}", compression)?;
     ws_write.send(Message::Binary(start_conn)).await?;
-    wait_for_event(&mut ws_read, EVENT_CONNECTION_STARTED).await?;
+    wait_for_event(&mut ws_read, Event::ConnectionStarted).await?;
     println!("Connection started");
 
     let session_payload = json!({
         "user": { "uid": "reason-desktop" },
-        "event": EVENT_START_SESSION,
+        "event": Event::StartSession.code(),
         "namespace": "BidirectionalTTS",
         "req_params": {
             "speaker": config.tts.voice_type,
@@ -317,69 +197,87 @@ async fn run() -> Result<(), Box<dyn std::error::Error>> {
         }
     });
     let session_payload_bytes = serde_json::to_vec(&session_payload)?;
-    let start_session =
-        build_full_client_frame(EVENT_START_SESSION, Some(&session_id), &session_payload_bytes);
+    let start_session = build_event_frame(
+        Event::StartSession,
+        Some(&session_id),
+        &session_payload_bytes,
+        compression,
+    )?;
     ws_write.send(Message::Binary(start_session)).await?;
-    wait_for_event(&mut ws_read, EVENT_SESSION_STARTED).await?;
+    wait_for_event(&mut ws_read, Event::SessionStarted).await?;
     println!("Session started");
 
     let task_payload = json!({
-        "event": EVENT_TASK_REQUEST,
+        "event": Event::TaskRequest.code(),
         "namespace": "BidirectionalTTS",
         "req_params": {
             "text": args.text
         }
     });
     let task_payload_bytes = serde_json::to_vec(&task_payload)?;
-    let task_request =
-        build_full_client_frame(EVENT_TASK_REQUEST, Some(&session_id), &task_payload_bytes);
+    let task_request = build_event_frame(
+        Event::TaskRequest,
+        Some(&session_id),
+        &task_payload_bytes,
+        compression,
+    )?;
     ws_write.send(Message::Binary(task_request)).await?;
 
     let finish_session =
-        build_full_client_frame(EVENT_FINISH_SESSION, Some(&session_id), b"{}");
+        build_event_frame(Event::FinishSession, Some(&session_id), b"{}", compression)?;
     ws_write.send(Message::Binary(finish_session)).await?;
 
     let mut output_file = tokio::fs::File::create(&args.output).await?;
     let mut audio_bytes = 0usize;
+    let mut track = SubtitleTrack::new();
 
     while let Some(message) = ws_read.next().await {
         let message = message?;
         match message {
             Message::Binary(data) => {
-                let frame = parse_frame(&data)?;
+                let frame = Frame::decode(&data)?.decompress()?;
                 if let Some(event) = frame.event {
                     match event {
-                        EVENT_TTS_SENTENCE_START => {
-                            println!("Sentence start");
+                        Event::TtsSentenceStart => {
+                            let cue = track.start_sentence(&frame.payload);
+                            println!("Sentence start at {}ms: {}", cue.start_ms, cue.text);
                         }
-                        EVENT_TTS_SENTENCE_END => {
-                            println!("Sentence end");
+                        Event::TtsSentenceEnd => {
+                            if let Some(cue) = track.end_sentence(&frame.payload) {
+                                println!(
+                                    "Sentence end at {}ms ({} words)",
+                                    cue.end_ms,
+                                    cue.words.len()
+                                );
+                            }
                         }
-                        EVENT_TTS_RESPONSE => {
+                        Event::TtsResponse => {
                             if !frame.payload.is_empty() {
                                 output_file.write_all(&frame.payload).await?;
                                 audio_bytes += frame.payload.len();
+                                track.push_audio(&frame.payload);
                             }
                         }
-                        EVENT_SESSION_FINISHED => {
+                        Event::SessionFinished => {
                             println!("Session finished");
                             break;
                         }
                         _ => {
-                            if frame.serialization == SERIALIZATION_JSON && !frame.payload.is_empty()
+                            if frame.serialization == Serialization::Json
+                                && !frame.payload.is_empty()
                             {
                                 let payload_text = String::from_utf8_lossy(&frame.payload);
-                                println!("Event {} payload: {}", event, payload_text);
+                                println!("Event {} payload: {}", event.code(), payload_text);
                             }
                         }
                     }
-                } else if frame.message_type == MSG_TYPE_AUDIO_ONLY_RESPONSE
-                    || frame.message_type == MSG_TYPE_AUDIO_ONLY_REQUEST
+                } else if (frame.message_type == MessageType::AudioOnlyResponse
+                    || frame.message_type == MessageType::AudioOnlyRequest)
+                    && !frame.payload.is_empty()
                 {
-                    if !frame.payload.is_empty() {
-                        output_file.write_all(&frame.payload).await?;
-                        audio_bytes += frame.payload.len();
-                    }
+                    output_file.write_all(&frame.payload).await?;
+                    audio_bytes += frame.payload.len();
+                    track.push_audio(&frame.payload);
                 }
             }
             Message::Text(text) => {
@@ -390,9 +288,9 @@ async fn run() -> Result<(), Box<dyn std::error::Error>> {
         }
     }
 
-    let finish_conn = build_full_client_frame(EVENT_FINISH_CONNECTION, None, b"{}");
+    let finish_conn = build_event_frame(Event::FinishConnection, None, b"{}", compression)?;
     ws_write.send(Message::Binary(finish_conn)).await?;
-    let _ = wait_for_event(&mut ws_read, EVENT_CONNECTION_FINISHED).await;
+    let _ = wait_for_event(&mut ws_read, Event::ConnectionFinished).await;
 
     output_file.flush().await?;
     println!(
@@ -401,6 +299,13 @@ async fn run() -> Result<(), Box<dyn std::error::Error>> {
         audio_bytes
     );
 
+    let cues = track.finish();
+    if !cues.is_empty() {
+        let subtitle_path = args.output.with_extension("srt");
+        fs::write(&subtitle_path, subtitles::to_srt(&cues))?;
+        println!("Saved subtitles to {}", subtitle_path.display());
+    }
+
     Ok(())
 }
 
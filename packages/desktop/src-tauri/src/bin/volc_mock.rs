@@ -0,0 +1,69 @@
+use reason_desktop_lib::volcengine::mock::{MockScenario, MockServer};
+use reason_desktop_lib::volcengine::MOCK_ADDR_ENV;
+use std::{env, fs};
+
+const DEFAULT_ADDR: &str = "127.0.0.1:18080";
+
+struct Args {
+    addr: String,
+    scenario: MockScenario,
+}
+
+fn parse_args() -> Result<Args, String> {
+    let mut args = env::args().skip(1);
+    let mut addr = DEFAULT_ADDR.to_string();
+    let mut scenario = MockScenario::default();
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-h" | "--help" => {
+                print_usage();
+                std::process::exit(0);
+            }
+            "--addr" => {
+                addr = args.next().ok_or("--addr requires a value")?;
+            }
+            "--scenario" => {
+                let path = args.next().ok_or("--scenario requires a path")?;
+                let content = fs::read_to_string(&path)
+                    .map_err(|e| format!("Failed to read scenario {}: {}", path, e))?;
+                scenario = serde_json::from_str(&content)
+                    .map_err(|e| format!("Failed to parse scenario {}: {}", path, e))?;
+            }
+            other => return Err(format!("Unexpected argument: {}", other)),
+        }
+    }
+
+    Ok(Args { addr, scenario })
+}
+
+fn print_usage() {
+    println!("Usage: cargo run --features mock --bin volc_mock -- [--addr HOST:PORT] [--scenario SCENARIO.json]");
+    println!(
+        "Example scenario: {{\"chunkDelayMs\": 200, \"fault\": {{\"kind\": \"closeMidStream\", \"afterFrames\": 2}}}}"
+    );
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let args = parse_args()?;
+    println!("Scenario: {}", serde_json::to_string(&args.scenario)?);
+
+    let server = MockServer::start(&args.addr, args.scenario).await?;
+    println!("Mock Volcengine listening on {}", server.addr());
+    println!(
+        "Point clients at it with {}={}",
+        MOCK_ADDR_ENV,
+        server.addr()
+    );
+
+    tokio::signal::ctrl_c().await?;
+    for request in server.requests() {
+        println!(
+            "{} event={:?} {}",
+            request.path, request.event, request.payload
+        );
+    }
+
+    Ok(())
+}
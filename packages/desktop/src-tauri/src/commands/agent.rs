@@ -1,5 +1,10 @@
+use crate::commands::{tts, voice_metrics};
+use reason_desktop_lib::error::AppError;
+use reason_desktop_lib::metrics::{Phase, Stage};
+use reason_desktop_lib::tr;
 use serde::Serialize;
 use std::process::Stdio;
+use std::time::Instant;
 use tauri::{Emitter, Manager};
 use tokio::io::{AsyncBufReadExt, BufReader};
 use tokio::process::Command;
@@ -17,15 +22,19 @@ pub struct AgentFinishedPayload {
     pub full_text: String,
 }
 
-/// Agent 错误事件
-#[derive(Clone, Serialize)]
-pub struct AgentErrorPayload {
-    pub message: String,
-}
+/// Agent 错误事件，与命令返回的错误结构相同
+pub type AgentErrorPayload = AppError;
 
 /// 调用 reason CLI
 #[tauri::command]
-pub async fn agent_run(app: tauri::AppHandle, prompt: String) -> Result<String, String> {
+#[tracing::instrument(skip_all, fields(prompt_len = prompt.chars().count()))]
+pub async fn agent_run(app: tauri::AppHandle, prompt: String) -> Result<String, AppError> {
+    let started = Instant::now();
+    voice_metrics::begin(&app, Phase::Agent);
+
+    // Agent 输出通常随即进入播报，提前建好语音合成连接
+    tts::prewarm(&app);
+
     // 启动 reason CLI 进程
     let mut child = Command::new("reason")
         .arg("-m")
@@ -35,21 +44,24 @@ pub async fn agent_run(app: tauri::AppHandle, prompt: String) -> Result<String,
         .stdout(Stdio::piped())
         .stderr(Stdio::piped())
         .spawn()
-        .map_err(|e| format!("启动 reason CLI 失败: {}", e))?;
+        .map_err(|e| AppError::process(tr!("agent.start_failed"), e.to_string()))?;
+    let spawned = Instant::now();
+    voice_metrics::record(&app, Stage::AgentSpawn, started.elapsed());
+    tracing::info!(pid = child.id(), "agent spawned");
 
     let stdout = child
         .stdout
         .take()
-        .ok_or("无法获取 stdout")?;
+        .ok_or_else(|| AppError::Internal(tr!("agent.pipe_unavailable", stream = "stdout")))?;
 
     let stderr = child
         .stderr
         .take()
-        .ok_or("无法获取 stderr")?;
+        .ok_or_else(|| AppError::Internal(tr!("agent.pipe_unavailable", stream = "stderr")))?;
 
     let window = app
         .get_webview_window("main")
-        .ok_or("Window not found")?;
+        .ok_or_else(AppError::window_not_found)?;
 
     let mut full_output = String::new();
 
@@ -59,6 +71,9 @@ pub async fn agent_run(app: tauri::AppHandle, prompt: String) -> Result<String,
 
     // 流式读取输出
     while let Ok(Some(line)) = lines.next_line().await {
+        if full_output.is_empty() {
+            voice_metrics::record(&app, Stage::AgentFirstOutput, spawned.elapsed());
+        }
         let chunk = format!("{}\n", line);
         full_output.push_str(&chunk);
 
@@ -75,7 +90,8 @@ pub async fn agent_run(app: tauri::AppHandle, prompt: String) -> Result<String,
     let status = child
         .wait()
         .await
-        .map_err(|e| format!("等待进程失败: {}", e))?;
+        .map_err(|e| AppError::process(tr!("agent.wait_failed"), e.to_string()))?;
+    voice_metrics::record(&app, Stage::AgentExit, spawned.elapsed());
 
     if !status.success() {
         // 读取 stderr
@@ -88,23 +104,20 @@ pub async fn agent_run(app: tauri::AppHandle, prompt: String) -> Result<String,
             error_output.push('\n');
         }
 
-        let error_message = if error_output.is_empty() {
-            format!("进程退出码: {}", status.code().unwrap_or(-1))
-        } else {
-            error_output
-        };
+        tracing::warn!(code = status.code(), stderr = %error_output.trim_end(), "agent failed");
+        let error = AppError::process(
+            tr!("agent.exited", code = status.code().unwrap_or(-1)),
+            error_output,
+        );
 
         // 发送错误事件
-        let _ = window.emit(
-            "agent-error",
-            AgentErrorPayload {
-                message: error_message.clone(),
-            },
-        );
+        let _ = window.emit::<AgentErrorPayload>("agent-error", error.clone());
 
-        return Err(error_message);
+        return Err(error);
     }
 
+    tracing::info!(output_len = full_output.len(), "agent finished");
+
     // 发送完成事件
     let _ = window.emit(
         "agent-finished",
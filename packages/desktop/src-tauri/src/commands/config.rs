@@ -1,38 +1,484 @@
+use reason_desktop_lib::error::AppError;
+use reason_desktop_lib::i18n::{self, Locale};
+use reason_desktop_lib::segmenter::SegmenterConfig;
+use reason_desktop_lib::speech_text::NormalizeLevel;
+use reason_desktop_lib::tr;
+use reason_desktop_lib::vad::VadConfig;
+use reason_desktop_lib::volcengine::protocol::Compression;
 use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::PathBuf;
+use std::sync::Mutex;
 
-/// 火山引擎 TTS 配置
+/// 前端上报的系统语言（WebView 的 `navigator.language`），优先于环境变量
+static SYSTEM_LOCALE: Mutex<Option<Locale>> = Mutex::new(None);
+
+/// 语音合成提供方
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TtsProviderKind {
+    #[default]
+    Volcengine,
+    /// OpenAI 兼容的 `/v1/audio/speech` 接口
+    Openai,
+    /// 本地命令，如 piper、espeak-ng
+    Command,
+}
+
+/// TTS 配置；`provider` 之外的火山引擎字段仍放在顶层，兼容旧配置文件
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TtsConfig {
+    #[serde(default)]
+    pub provider: TtsProviderKind,
     #[serde(rename = "resourceId", default)]
     pub resource_id: String,
     #[serde(rename = "voiceType")]
     pub voice_type: String,
     pub cluster: String,
+    #[serde(default)]
+    pub audio: TtsAudioParams,
+    /// 朗读前的文本规范化级别：off / basic / full
+    #[serde(default)]
+    pub normalize: NormalizeLevel,
+    /// 流式合成的分段长度
+    #[serde(default)]
+    pub segmenter: SegmenterConfig,
+    #[serde(default)]
+    pub cache: TtsCacheConfig,
+    #[serde(default)]
+    pub openai: OpenAiTtsConfig,
+    #[serde(default)]
+    pub command: CommandTtsConfig,
 }
 
 impl Default for TtsConfig {
     fn default() -> Self {
         Self {
+            provider: TtsProviderKind::default(),
             resource_id: String::new(),
             voice_type: "zh_female_tianmeixiaoyuan_moon_bigtts".to_string(),
             cluster: "volcano_tts".to_string(),
+            audio: TtsAudioParams::default(),
+            normalize: NormalizeLevel::default(),
+            segmenter: SegmenterConfig::default(),
+            cache: TtsCacheConfig::default(),
+            openai: OpenAiTtsConfig::default(),
+            command: CommandTtsConfig::default(),
         }
     }
 }
 
-/// 火山引擎 STT 配置
+/// OpenAI 兼容语音合成配置，自建服务（如 openedai-speech、kokoro）修改 baseUrl 即可
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OpenAiTtsConfig {
+    #[serde(rename = "baseUrl", default = "default_openai_tts_base_url")]
+    pub base_url: String,
+    /// 为空时不发送 Authorization 头
+    #[serde(rename = "apiKey", default)]
+    pub api_key: String,
+    #[serde(default = "default_openai_tts_model")]
+    pub model: String,
+    #[serde(default = "default_openai_tts_voice")]
+    pub voice: String,
+}
+
+fn default_openai_tts_base_url() -> String {
+    "https://api.openai.com/v1".to_string()
+}
+
+fn default_openai_tts_model() -> String {
+    "tts-1".to_string()
+}
+
+fn default_openai_tts_voice() -> String {
+    "alloy".to_string()
+}
+
+impl Default for OpenAiTtsConfig {
+    fn default() -> Self {
+        Self {
+            base_url: default_openai_tts_base_url(),
+            api_key: String::new(),
+            model: default_openai_tts_model(),
+            voice: default_openai_tts_voice(),
+        }
+    }
+}
+
+/// 本地命令语音合成配置，命令需把 16 位 PCM 的 WAV 写到标准输出
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CommandTtsConfig {
+    #[serde(default)]
+    pub program: String,
+    /// 参数中的 `{text}`、`{voice}` 会被替换；没有 `{text}` 时文本从标准输入传入
+    #[serde(default)]
+    pub args: Vec<String>,
+    #[serde(default)]
+    pub voice: String,
+    /// 命令输出的采样率，piper 与 espeak-ng 默认均为 22050
+    #[serde(rename = "sampleRate", default = "default_command_tts_sample_rate")]
+    pub sample_rate: u32,
+    #[serde(rename = "timeoutSecs", default = "default_command_tts_timeout_secs")]
+    pub timeout_secs: u64,
+}
+
+fn default_command_tts_sample_rate() -> u32 {
+    22050
+}
+
+fn default_command_tts_timeout_secs() -> u64 {
+    30
+}
+
+impl Default for CommandTtsConfig {
+    fn default() -> Self {
+        Self {
+            program: String::new(),
+            args: Vec::new(),
+            voice: String::new(),
+            sample_rate: default_command_tts_sample_rate(),
+            timeout_secs: default_command_tts_timeout_secs(),
+        }
+    }
+}
+
+/// 语音合成支持的输出格式
+pub const TTS_AUDIO_FORMATS: [&str; 4] = ["mp3", "ogg_opus", "pcm", "wav"];
+/// 语音合成支持的采样率
+pub const TTS_SAMPLE_RATES: [u32; 6] = [8000, 16000, 24000, 32000, 44100, 48000];
+/// OpenAI 兼容接口支持的输出格式
+const OPENAI_TTS_FORMATS: [&str; 3] = ["mp3", "pcm", "wav"];
+/// OpenAI 兼容接口的 pcm / wav 输出固定为 24kHz
+const OPENAI_TTS_PCM_SAMPLE_RATE: u32 = 24000;
+
+/// TTS 音频参数，HTTP 与 WebSocket 两条合成路径共用
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TtsAudioParams {
+    /// 语速倍率，0.5–2.0
+    #[serde(rename = "speedRatio", default = "default_tts_ratio")]
+    pub speed_ratio: f32,
+    /// 音量倍率，0.5–2.0
+    #[serde(rename = "volumeRatio", default = "default_tts_ratio")]
+    pub volume_ratio: f32,
+    /// 音调倍率，0.5–2.0
+    #[serde(rename = "pitchRatio", default = "default_tts_ratio")]
+    pub pitch_ratio: f32,
+    /// 输出格式：mp3 / ogg_opus / pcm / wav（pcm、wav 为 16 位单声道）
+    #[serde(default = "default_tts_format")]
+    pub format: String,
+    #[serde(rename = "sampleRate", default = "default_tts_sample_rate")]
+    pub sample_rate: u32,
+    /// 情感 / 风格（如 happy、sad），仅部分音色支持
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub emotion: Option<String>,
+}
+
+fn default_tts_ratio() -> f32 {
+    1.0
+}
+
+fn default_tts_format() -> String {
+    "mp3".to_string()
+}
+
+fn default_tts_sample_rate() -> u32 {
+    24000
+}
+
+impl Default for TtsAudioParams {
+    fn default() -> Self {
+        Self {
+            speed_ratio: default_tts_ratio(),
+            volume_ratio: default_tts_ratio(),
+            pitch_ratio: default_tts_ratio(),
+            format: default_tts_format(),
+            sample_rate: default_tts_sample_rate(),
+            emotion: None,
+        }
+    }
+}
+
+/// 单次调用对音频参数的覆盖，未设置的字段沿用配置
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TtsAudioOverrides {
+    pub speed_ratio: Option<f32>,
+    pub volume_ratio: Option<f32>,
+    pub pitch_ratio: Option<f32>,
+    pub format: Option<String>,
+    pub sample_rate: Option<u32>,
+    pub emotion: Option<String>,
+}
+
+impl TtsAudioParams {
+    /// 合并单次调用的覆盖，并按所选提供方校验取值
+    pub fn resolve(
+        &self,
+        provider: TtsProviderKind,
+        overrides: Option<TtsAudioOverrides>,
+    ) -> Result<Self, AppError> {
+        let mut params = self.clone();
+        if let Some(overrides) = overrides {
+            params.speed_ratio = overrides.speed_ratio.unwrap_or(params.speed_ratio);
+            params.volume_ratio = overrides.volume_ratio.unwrap_or(params.volume_ratio);
+            params.pitch_ratio = overrides.pitch_ratio.unwrap_or(params.pitch_ratio);
+            params.format = overrides.format.unwrap_or(params.format);
+            params.sample_rate = overrides.sample_rate.unwrap_or(params.sample_rate);
+            params.emotion = overrides.emotion.or(params.emotion);
+        }
+        params.emotion = params.emotion.filter(|emotion| !emotion.trim().is_empty());
+        params.validate(provider)?;
+        Ok(params)
+    }
+
+    fn validate(&self, provider: TtsProviderKind) -> Result<(), AppError> {
+        for (name, ratio) in [
+            ("config.param.speed", self.speed_ratio),
+            ("config.param.volume", self.volume_ratio),
+            ("config.param.pitch", self.pitch_ratio),
+        ] {
+            if !(0.5..=2.0).contains(&ratio) {
+                return Err(AppError::Input(tr!(
+                    "config.ratio_out_of_range",
+                    name = tr!(name),
+                    value = ratio
+                )));
+            }
+        }
+        if !TTS_AUDIO_FORMATS.contains(&self.format.as_str()) {
+            return Err(AppError::Input(tr!(
+                "audio.unsupported_format",
+                format = self.format
+            )));
+        }
+        if !TTS_SAMPLE_RATES.contains(&self.sample_rate) {
+            return Err(AppError::Input(tr!(
+                "config.unsupported_sample_rate",
+                rate = self.sample_rate
+            )));
+        }
+        if provider == TtsProviderKind::Openai {
+            if !OPENAI_TTS_FORMATS.contains(&self.format.as_str()) {
+                return Err(AppError::Input(tr!(
+                    "tts.openai_unsupported_format",
+                    format = self.format
+                )));
+            }
+            if self.format != "mp3" && self.sample_rate != OPENAI_TTS_PCM_SAMPLE_RATE {
+                return Err(AppError::Input(tr!(
+                    "tts.openai_unsupported_sample_rate",
+                    format = self.format,
+                    rate = self.sample_rate,
+                    expected = OPENAI_TTS_PCM_SAMPLE_RATE
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// TTS 磁盘缓存配置，缓存目录为 `~/.reason-code/tts_cache`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TtsCacheConfig {
+    #[serde(default = "default_tts_cache_enabled")]
+    pub enabled: bool,
+    /// 缓存总容量上限（MB）
+    #[serde(rename = "maxSizeMb", default = "default_tts_cache_max_size_mb")]
+    pub max_size_mb: u64,
+    /// 条目自最近一次使用起的保留天数
+    #[serde(rename = "maxAgeDays", default = "default_tts_cache_max_age_days")]
+    pub max_age_days: u64,
+}
+
+fn default_tts_cache_enabled() -> bool {
+    true
+}
+
+fn default_tts_cache_max_size_mb() -> u64 {
+    200
+}
+
+fn default_tts_cache_max_age_days() -> u64 {
+    30
+}
+
+impl Default for TtsCacheConfig {
+    fn default() -> Self {
+        Self {
+            enabled: default_tts_cache_enabled(),
+            max_size_mb: default_tts_cache_max_size_mb(),
+            max_age_days: default_tts_cache_max_age_days(),
+        }
+    }
+}
+
+/// 语音识别提供方
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SttProviderKind {
+    #[default]
+    Volcengine,
+    /// OpenAI / Whisper 兼容的 `/v1/audio/transcriptions` 接口
+    Openai,
+    /// 本地命令，如 whisper.cpp
+    Command,
+}
+
+/// STT 配置，识别选项为空时使用服务端默认值；`provider` 之外的火山引擎字段仍放在顶层
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SttConfig {
+    #[serde(default)]
+    pub provider: SttProviderKind,
     #[serde(rename = "resourceId", default)]
     pub resource_id: String,
+    #[serde(rename = "modelName", default = "default_stt_model_name")]
+    pub model_name: String,
+    /// 文本规范化（数字、日期等转为阿拉伯数字形式）
+    #[serde(rename = "enableItn", default, skip_serializing_if = "Option::is_none")]
+    pub enable_itn: Option<bool>,
+    /// 自动添加标点
+    #[serde(rename = "enablePunc", default, skip_serializing_if = "Option::is_none")]
+    pub enable_punc: Option<bool>,
+    /// 顺滑（去除语气词、重复词）
+    #[serde(rename = "enableDdc", default, skip_serializing_if = "Option::is_none")]
+    pub enable_ddc: Option<bool>,
+    /// 识别语种，如 zh-CN、en-US
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
+    /// 覆盖请求声明的采样率，默认 16000
+    #[serde(rename = "sampleRate", default, skip_serializing_if = "Option::is_none")]
+    pub sample_rate: Option<i32>,
+    /// 覆盖请求声明的声道数，默认 1
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub channel: Option<i32>,
+    /// 判停静音窗口（毫秒）
+    #[serde(rename = "endWindowSize", default, skip_serializing_if = "Option::is_none")]
+    pub end_window_size: Option<u32>,
+    /// 识别前是否附带热词（用户词表 + 项目标识符）
+    #[serde(rename = "enableHotwords", default = "default_enable_hotwords")]
+    pub enable_hotwords: bool,
+    /// 提取热词的项目目录，为空时使用当前工作目录（需是项目根目录）
+    #[serde(rename = "projectDir", default, skip_serializing_if = "Option::is_none")]
+    pub project_dir: Option<String>,
+    /// 语音活动检测：上传前裁剪首尾静音，流式输入时判定一句话结束
+    #[serde(default)]
+    pub vad: VadConfig,
+    #[serde(default)]
+    pub openai: OpenAiSttConfig,
+    #[serde(default)]
+    pub command: CommandSttConfig,
+}
+
+fn default_stt_model_name() -> String {
+    "bigmodel".to_string()
+}
+
+fn default_enable_hotwords() -> bool {
+    true
 }
 
 impl Default for SttConfig {
     fn default() -> Self {
         Self {
+            provider: SttProviderKind::default(),
             resource_id: String::new(),
+            model_name: default_stt_model_name(),
+            enable_itn: None,
+            enable_punc: None,
+            enable_ddc: None,
+            language: None,
+            sample_rate: None,
+            channel: None,
+            end_window_size: None,
+            enable_hotwords: default_enable_hotwords(),
+            project_dir: None,
+            vad: VadConfig::default(),
+            openai: OpenAiSttConfig::default(),
+            command: CommandSttConfig::default(),
+        }
+    }
+}
+
+/// OpenAI / Whisper 兼容语音识别配置，自建服务（如 faster-whisper-server）修改 baseUrl 即可
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OpenAiSttConfig {
+    #[serde(rename = "baseUrl", default = "default_openai_stt_base_url")]
+    pub base_url: String,
+    /// 为空时不发送 Authorization 头
+    #[serde(rename = "apiKey", default)]
+    pub api_key: String,
+    #[serde(default = "default_openai_stt_model")]
+    pub model: String,
+    /// 请求分句时间戳（verbose_json），gpt-4o-transcribe 等不支持的模型需关闭
+    #[serde(default = "default_openai_stt_timestamps")]
+    pub timestamps: bool,
+}
+
+fn default_openai_stt_base_url() -> String {
+    "https://api.openai.com/v1".to_string()
+}
+
+fn default_openai_stt_model() -> String {
+    "whisper-1".to_string()
+}
+
+fn default_openai_stt_timestamps() -> bool {
+    true
+}
+
+impl Default for OpenAiSttConfig {
+    fn default() -> Self {
+        Self {
+            base_url: default_openai_stt_base_url(),
+            api_key: String::new(),
+            model: default_openai_stt_model(),
+            timestamps: default_openai_stt_timestamps(),
+        }
+    }
+}
+
+/// 本地命令语音识别配置，命令把识别结果写到标准输出（whisper.cpp 的带时间戳输出会解析为分句）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CommandSttConfig {
+    /// 如 whisper.cpp 的 `whisper-cli`
+    #[serde(default)]
+    pub program: String,
+    /// `{input}` 替换为 16kHz 单声道 WAV 路径，`{model}`、`{language}`、`{prompt}`
+    /// 分别替换为模型路径、语种（未配置时为 auto）与热词提示
+    #[serde(default = "default_command_stt_args")]
+    pub args: Vec<String>,
+    /// 模型文件路径，如 `ggml-base.bin`
+    #[serde(default)]
+    pub model: String,
+    /// 非 16kHz WAV 的录音先用 ffmpeg 转码
+    #[serde(default = "default_command_stt_ffmpeg")]
+    pub ffmpeg: String,
+    #[serde(rename = "timeoutSecs", default = "default_command_stt_timeout_secs")]
+    pub timeout_secs: u64,
+}
+
+fn default_command_stt_args() -> Vec<String> {
+    ["-m", "{model}", "-f", "{input}", "-l", "{language}", "--prompt", "{prompt}"]
+        .map(String::from)
+        .to_vec()
+}
+
+fn default_command_stt_ffmpeg() -> String {
+    "ffmpeg".to_string()
+}
+
+fn default_command_stt_timeout_secs() -> u64 {
+    120
+}
+
+impl Default for CommandSttConfig {
+    fn default() -> Self {
+        Self {
+            program: String::new(),
+            args: default_command_stt_args(),
+            model: String::new(),
+            ffmpeg: default_command_stt_ffmpeg(),
+            timeout_secs: default_command_stt_timeout_secs(),
         }
     }
 }
@@ -46,6 +492,9 @@ pub struct VolcengineConfig {
     pub access_token: String,
     #[serde(rename = "resourceId", default, skip_serializing_if = "Option::is_none")]
     pub legacy_resource_id: Option<String>,
+    /// 是否对上行的请求 / 音频帧启用 gzip 压缩（下行压缩帧总会自动解压）
+    #[serde(rename = "enableGzip", default)]
+    pub enable_gzip: bool,
     #[serde(default)]
     pub stt: SttConfig,
     pub tts: TtsConfig,
@@ -57,14 +506,26 @@ impl Default for VolcengineConfig {
             app_id: String::new(),
             access_token: String::new(),
             legacy_resource_id: None,
+            enable_gzip: false,
             stt: SttConfig::default(),
             tts: TtsConfig::default(),
         }
     }
 }
 
+impl VolcengineConfig {
+    /// 上行帧使用的 payload 压缩方式
+    pub fn payload_compression(&self) -> Compression {
+        if self.enable_gzip {
+            Compression::Gzip
+        } else {
+            Compression::None
+        }
+    }
+}
+
 /// 完整配置文件结构
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct ReasonConfig {
     #[serde(default)]
     pub model: serde_json::Value, //表示任意JSON的值
@@ -81,63 +542,287 @@ pub struct ReasonConfig {
 }
 
 /// 获取配置文件路径
-fn get_config_path() -> PathBuf {
+pub fn get_config_path() -> PathBuf {
     dirs::home_dir()
         .expect("Cannot find home directory")
         .join(".reason-code")
         .join("config.json")
 }
 
+/// 用户热词表路径，每行一个词
+pub fn get_hotwords_path() -> PathBuf {
+    dirs::home_dir()
+        .expect("Cannot find home directory")
+        .join(".reason-code")
+        .join("hotwords.txt")
+}
+
+/// TTS 音频缓存目录
+pub fn get_tts_cache_dir() -> PathBuf {
+    dirs::home_dir()
+        .expect("Cannot find home directory")
+        .join(".reason-code")
+        .join("tts_cache")
+}
+
+/// 日志目录
+pub fn get_logs_dir() -> PathBuf {
+    dirs::home_dir()
+        .expect("Cannot find home directory")
+        .join(".reason-code")
+        .join("logs")
+}
+
+/// 语音交互耗时记录文件
+pub fn get_voice_metrics_path() -> PathBuf {
+    dirs::home_dir()
+        .expect("Cannot find home directory")
+        .join(".reason-code")
+        .join("voice_metrics.jsonl")
+}
+
+/// 诊断包输出目录
+pub fn get_diagnostics_dir() -> PathBuf {
+    dirs::home_dir()
+        .expect("Cannot find home directory")
+        .join(".reason-code")
+        .join("diagnostics")
+}
+
 /// 读取配置文件
-fn read_config() -> Result<ReasonConfig, String> {
+fn read_config() -> Result<ReasonConfig, AppError> {
     let path = get_config_path();
 
     if !path.exists() {
-        return Err("Config file not found".to_string());
+        return Err(AppError::ConfigMissing);
     }
 
-    let content = fs::read_to_string(&path).map_err(|e| format!("Failed to read config: {}", e))?;
+    let content = fs::read_to_string(&path)
+        .map_err(|e| AppError::Io(tr!("config.read_failed", error = e)))?;
 
-    serde_json::from_str(&content).map_err(|e| format!("Failed to parse config: {}", e))
+    serde_json::from_str(&content)
+        .map_err(|e| AppError::Config(tr!("config.parse_failed", error = e)))
 }
 
 /// 写入配置文件
-fn write_config(config: &ReasonConfig) -> Result<(), String> {
+fn write_config(config: &ReasonConfig) -> Result<(), AppError> {
     let path = get_config_path();
 
     // 确保目录存在 - 如果path.parent()存在的话，就执行{}中的代码
     if let Some(parent) = path.parent() {
         //执行创建目录的操作，如果出现错误就执行map_err中的代码，并且?是直接返回函数
-        fs::create_dir_all(parent).map_err(|e| format!("Failed to create config dir: {}", e))?;
+        fs::create_dir_all(parent)
+            .map_err(|e| AppError::Io(tr!("config.create_dir_failed", error = e)))?;
     }
 
     let content = serde_json::to_string_pretty(config)
-        .map_err(|e| format!("Failed to serialize config: {}", e))?;
+        .map_err(|e| AppError::Internal(tr!("config.serialize_failed", error = e)))?;
 
-    fs::write(&path, content).map_err(|e| format!("Failed to write config: {}", e))
+    fs::write(&path, content).map_err(|e| AppError::Io(tr!("config.write_failed", error = e)))
 }
 
 /// 获取火山引擎配置
 #[tauri::command]
-pub async fn get_volcengine_config() -> Result<VolcengineConfig, String> {
+pub async fn get_volcengine_config() -> Result<VolcengineConfig, AppError> {
     let config = read_config()?;
     Ok(config.volcengine.unwrap_or_default())
 }
 
 /// 保存火山引擎配置
 #[tauri::command]
-pub async fn save_volcengine_config(config: VolcengineConfig) -> Result<(), String> {
+pub async fn save_volcengine_config(config: VolcengineConfig) -> Result<(), AppError> {
     //读取配置文件，如果read_config失败，则返回一个默认配置
-    let mut full_config = read_config().unwrap_or_else(|_| ReasonConfig {
-        model: serde_json::Value::Null,
-        providers: serde_json::Value::Null,
-        volcengine: None,
-        agent: serde_json::Value::Null,
-        ui: serde_json::Value::Null,
-        session: serde_json::Value::Null,
-    });
+    let mut full_config = read_config().unwrap_or_default();
 
     full_config.volcengine = Some(config);
 
     write_config(&full_config)
 }
+
+/// 配置中的界面语言 `ui.language`，未设置或为 "system" 时返回 None
+fn configured_locale(config: &ReasonConfig) -> Option<Locale> {
+    config.ui.get("language")?.as_str().and_then(Locale::parse)
+}
+
+/// 按配置或系统语言切换后端消息的语言，返回生效的语言
+fn apply_locale(config: Option<&ReasonConfig>) -> Locale {
+    let system = *SYSTEM_LOCALE.lock().unwrap_or_else(|e| e.into_inner());
+    let locale = config
+        .and_then(configured_locale)
+        .or(system)
+        .or_else(Locale::from_env)
+        .unwrap_or_default();
+    i18n::set_locale(locale);
+    locale
+}
+
+/// 启动时按配置文件初始化消息语言
+pub fn init_locale() {
+    apply_locale(read_config().ok().as_ref());
+}
+
+/// 前端启动时上报系统语言，返回生效的界面语言（如 "zh-CN"）
+#[tauri::command]
+pub async fn sync_ui_language(system_language: Option<String>) -> String {
+    if let Some(locale) = system_language.as_deref().and_then(Locale::parse) {
+        *SYSTEM_LOCALE.lock().unwrap_or_else(|e| e.into_inner()) = Some(locale);
+    }
+    apply_locale(read_config().ok().as_ref()).tag().to_string()
+}
+
+/// 保存界面语言，`language` 为空或 "system" 表示跟随系统；返回生效的界面语言
+#[tauri::command]
+pub async fn save_ui_language(language: Option<String>) -> Result<String, AppError> {
+    let language = language
+        .map(|language| language.trim().to_string())
+        .filter(|language| !language.is_empty() && language != "system");
+    let locale = match &language {
+        Some(language) => Some(Locale::parse(language).ok_or_else(|| {
+            AppError::Input(tr!("config.unsupported_language", language = language))
+        })?),
+        None => None,
+    };
+
+    // 配置文件损坏时不覆盖，避免丢失其他设置
+    let mut full_config = match read_config() {
+        Err(AppError::ConfigMissing) => ReasonConfig::default(),
+        result => result?,
+    };
+    if !full_config.ui.is_object() {
+        full_config.ui = serde_json::json!({});
+    }
+    full_config.ui["language"] = match locale {
+        Some(locale) => locale.tag().into(),
+        None => "system".into(),
+    };
+    write_config(&full_config)?;
+
+    Ok(apply_locale(Some(&full_config)).tag().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resolves_audio_overrides() {
+        let base = TtsAudioParams {
+            emotion: Some("happy".to_string()),
+            ..TtsAudioParams::default()
+        };
+        assert_eq!(
+            base.resolve(TtsProviderKind::Volcengine, None).unwrap(),
+            base
+        );
+
+        let resolved = base
+            .resolve(
+                TtsProviderKind::Volcengine,
+                Some(TtsAudioOverrides {
+                    speed_ratio: Some(1.5),
+                    sample_rate: Some(16000),
+                    format: Some("pcm".to_string()),
+                    // 空白的情感覆盖等同于不使用情感
+                    emotion: Some("  ".to_string()),
+                    ..TtsAudioOverrides::default()
+                }),
+            )
+            .unwrap();
+        assert_eq!(
+            resolved,
+            TtsAudioParams {
+                speed_ratio: 1.5,
+                format: "pcm".to_string(),
+                sample_rate: 16000,
+                emotion: None,
+                ..TtsAudioParams::default()
+            }
+        );
+    }
+
+    #[test]
+    fn rejects_out_of_range_audio_params() {
+        let base = TtsAudioParams::default();
+        let resolve = |overrides: TtsAudioOverrides| {
+            base.resolve(TtsProviderKind::Volcengine, Some(overrides))
+        };
+
+        for ratio in [0.5, 2.0] {
+            assert!(resolve(TtsAudioOverrides {
+                pitch_ratio: Some(ratio),
+                ..TtsAudioOverrides::default()
+            })
+            .is_ok());
+        }
+        for overrides in [
+            TtsAudioOverrides {
+                speed_ratio: Some(0.49),
+                ..TtsAudioOverrides::default()
+            },
+            TtsAudioOverrides {
+                volume_ratio: Some(2.1),
+                ..TtsAudioOverrides::default()
+            },
+            TtsAudioOverrides {
+                pitch_ratio: Some(f32::NAN),
+                ..TtsAudioOverrides::default()
+            },
+            TtsAudioOverrides {
+                sample_rate: Some(22050),
+                ..TtsAudioOverrides::default()
+            },
+            TtsAudioOverrides {
+                sample_rate: Some(0),
+                ..TtsAudioOverrides::default()
+            },
+            TtsAudioOverrides {
+                format: Some("flac".to_string()),
+                ..TtsAudioOverrides::default()
+            },
+        ] {
+            assert!(matches!(resolve(overrides), Err(AppError::Input(_))));
+        }
+    }
+
+    #[test]
+    fn rejects_audio_params_unsupported_by_openai() {
+        let base = TtsAudioParams::default();
+        let resolve = |format: &str, sample_rate: u32| {
+            base.resolve(
+                TtsProviderKind::Openai,
+                Some(TtsAudioOverrides {
+                    format: Some(format.to_string()),
+                    sample_rate: Some(sample_rate),
+                    ..TtsAudioOverrides::default()
+                }),
+            )
+        };
+
+        // mp3 不受采样率限制，pcm / wav 只能输出 24kHz
+        assert!(resolve("mp3", 16000).is_ok());
+        assert!(resolve("pcm", 24000).is_ok());
+        assert!(resolve("wav", 24000).is_ok());
+
+        for (format, sample_rate, named) in [
+            ("ogg_opus", 24000, "ogg_opus"),
+            ("wav", 16000, "16000"),
+            ("pcm", 48000, "48000"),
+        ] {
+            match resolve(format, sample_rate) {
+                Err(AppError::Input(message)) => assert!(message.contains(named), "{message}"),
+                other => panic!("{format} {sample_rate}: {other:?}"),
+            }
+        }
+        // 火山引擎支持同样的取值
+        assert!(base
+            .resolve(
+                TtsProviderKind::Volcengine,
+                Some(TtsAudioOverrides {
+                    format: Some("ogg_opus".to_string()),
+                    sample_rate: Some(16000),
+                    ..TtsAudioOverrides::default()
+                }),
+            )
+            .is_ok());
+    }
+}
@@ -0,0 +1,49 @@
+use crate::commands::config::{get_config_path, get_diagnostics_dir, get_logs_dir};
+use reason_desktop_lib::diagnostics::{self, MAX_LOG_BYTES};
+use reason_desktop_lib::error::AppError;
+use reason_desktop_lib::i18n;
+use reason_desktop_lib::tr;
+use serde_json::json;
+use std::fs::{self, File};
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// 导出诊断包（最近日志、脱敏配置与版本信息），返回 zip 文件路径
+#[tauri::command]
+#[tracing::instrument(skip_all)]
+pub async fn diagnostics_export(app: tauri::AppHandle) -> Result<String, AppError> {
+    let version = app.package_info().version.to_string();
+    let path = tokio::task::spawn_blocking(move || export(&version))
+        .await
+        .map_err(|e| AppError::Internal(tr!("diagnostics.task_failed", error = e)))?
+        .map_err(|e| AppError::Io(tr!("diagnostics.export_failed", error = e)))?;
+
+    tracing::info!(path = %path.display(), "diagnostics exported");
+    Ok(path.to_string_lossy().into_owned())
+}
+
+fn export(version: &str) -> Result<PathBuf, String> {
+    let ts = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default();
+    let info = json!({
+        "version": version,
+        "os": std::env::consts::OS,
+        "arch": std::env::consts::ARCH,
+        "debug": cfg!(debug_assertions),
+        "locale": i18n::locale().tag(),
+        "exportedAt": ts,
+    });
+    let config = fs::read_to_string(get_config_path())
+        .ok()
+        .map(|content| diagnostics::sanitize_config_text(&content));
+    let logs = diagnostics::recent_logs(&get_logs_dir(), MAX_LOG_BYTES);
+
+    let dir = get_diagnostics_dir();
+    fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    let path = dir.join(format!("reason-diagnostics-{}.zip", ts));
+    let file = File::create(&path).map_err(|e| e.to_string())?;
+    diagnostics::write_bundle(file, &info, config.as_deref(), &logs)?;
+    Ok(path)
+}
@@ -1,6 +1,8 @@
 pub mod agent;
 pub mod config;
+pub mod diagnostics;
 pub mod stt;
 pub mod tts;
+pub mod voice_metrics;
 pub mod voice_session;
 pub mod window;
@@ -0,0 +1,291 @@
+//! 本地命令语音识别，如 whisper.cpp 的 `whisper-cli`；适合没有云端账号或离线的机器
+
+use super::provider::{audio_file, hotword_prompt, language_code, SttProvider};
+use super::{SttTranscript, SttUtterance};
+use crate::commands::config::SttConfig;
+use futures_util::future::BoxFuture;
+use futures_util::FutureExt;
+use reason_desktop_lib::audio::{parse_wav, WavFormat};
+use reason_desktop_lib::error::AppError;
+use reason_desktop_lib::tr;
+use regex::Regex;
+use std::path::PathBuf;
+use std::process::Stdio;
+use std::sync::LazyLock;
+use tokio::process::Command;
+use tokio::time::{timeout, Duration};
+use uuid::Uuid;
+
+/// whisper.cpp 要求的输入格式
+const WHISPER_INPUT_FORMAT: WavFormat = WavFormat::pcm16(16000, 1);
+
+/// whisper.cpp 的分句输出：`[00:00:00.000 --> 00:00:02.480]  文本`
+static SEGMENT_LINE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"^\[(\d+):(\d{2}):(\d{2})[.,](\d{3}) --> (\d+):(\d{2}):(\d{2})[.,](\d{3})\]\s*(.*)$",
+    )
+    .unwrap()
+});
+
+/// 识别结束后删除的临时文件
+#[derive(Default)]
+struct TempFiles(Vec<PathBuf>);
+
+impl TempFiles {
+    fn add(&mut self, path: PathBuf) -> PathBuf {
+        self.0.push(path.clone());
+        path
+    }
+}
+
+impl Drop for TempFiles {
+    fn drop(&mut self) {
+        for path in &self.0 {
+            let _ = std::fs::remove_file(path);
+        }
+    }
+}
+
+pub struct CommandStt {
+    config: SttConfig,
+}
+
+impl CommandStt {
+    pub fn new(config: SttConfig) -> Self {
+        Self { config }
+    }
+
+    async fn recognize(
+        &self,
+        audio: &[u8],
+        mime_type: &str,
+        hotwords: &[String],
+    ) -> Result<SttTranscript, AppError> {
+        let command = &self.config.command;
+        let program = command.program.trim();
+        if program.is_empty() {
+            return Err(AppError::Config(tr!("stt.command_missing")));
+        }
+
+        let (file, ext) = audio_file(audio, mime_type, &self.config)?;
+        let id = Uuid::new_v4();
+        let mut temp_files = TempFiles::default();
+        let source =
+            temp_files.add(std::env::temp_dir().join(format!("reason-stt-{}.{}", id, ext)));
+        tokio::fs::write(&source, &file)
+            .await
+            .map_err(|e| AppError::Io(tr!("stt.temp_write_failed", error = e)))?;
+
+        let is_whisper_input =
+            parse_wav(&file).is_ok_and(|(format, _)| format == WHISPER_INPUT_FORMAT);
+        let input = if is_whisper_input {
+            source
+        } else {
+            let converted =
+                temp_files.add(std::env::temp_dir().join(format!("reason-stt-{}-16k.wav", id)));
+            let args = [
+                "-nostdin",
+                "-loglevel",
+                "error",
+                "-y",
+                "-i",
+                &source.to_string_lossy(),
+                "-ar",
+                "16000",
+                "-ac",
+                "1",
+                "-c:a",
+                "pcm_s16le",
+                &converted.to_string_lossy(),
+            ]
+            .map(String::from);
+            run(&command.ffmpeg, &args, command.timeout_secs)
+                .await
+                .map_err(|error| {
+                    AppError::process(tr!("stt.transcode_failed"), error.to_string())
+                })?;
+            converted
+        };
+
+        let input = input.to_string_lossy();
+        let language = language_code(&self.config).unwrap_or_else(|| "auto".to_string());
+        let prompt = hotword_prompt(hotwords);
+        let args: Vec<String> = command
+            .args
+            .iter()
+            .map(|arg| {
+                arg.replace("{input}", &input)
+                    .replace("{model}", &command.model)
+                    .replace("{language}", &language)
+                    .replace("{prompt}", &prompt)
+            })
+            .collect();
+        let stdout = run(program, &args, command.timeout_secs).await?;
+
+        Ok(parse_output(&String::from_utf8_lossy(&stdout)))
+    }
+}
+
+impl SttProvider for CommandStt {
+    fn transcribe<'a>(
+        &'a self,
+        audio: &'a [u8],
+        mime_type: &'a str,
+        hotwords: &'a [String],
+    ) -> BoxFuture<'a, Result<SttTranscript, AppError>> {
+        self.recognize(audio, mime_type, hotwords).boxed()
+    }
+}
+
+/// 运行命令并返回标准输出，超时后结束进程
+async fn run(program: &str, args: &[String], timeout_secs: u64) -> Result<Vec<u8>, AppError> {
+    let child = Command::new(program)
+        .args(args)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()
+        .map_err(|e| {
+            AppError::process(
+                tr!("process.start_failed", program = program),
+                e.to_string(),
+            )
+        })?;
+
+    let output = timeout(Duration::from_secs(timeout_secs), child.wait_with_output())
+        .await
+        .map_err(|_| {
+            AppError::process(
+                tr!("process.timeout", program = program, secs = timeout_secs),
+                "",
+            )
+        })?
+        .map_err(|e| {
+            AppError::process(tr!("process.wait_failed", program = program), e.to_string())
+        })?;
+
+    if !output.status.success() {
+        return Err(AppError::process(
+            tr!("process.failed", program = program, status = output.status),
+            String::from_utf8_lossy(&output.stderr),
+        ));
+    }
+    Ok(output.stdout)
+}
+
+fn timestamp_ms(captures: &regex::Captures, first: usize) -> i64 {
+    let field = |index: usize| captures[first + index].parse::<i64>().unwrap_or(0);
+    ((field(0) * 60 + field(1)) * 60 + field(2)) * 1000 + field(3)
+}
+
+/// 解析命令输出：带时间戳的行作为分句，其余非空行只计入文本
+fn parse_output(stdout: &str) -> SttTranscript {
+    let mut texts = Vec::new();
+    let mut utterances = Vec::new();
+    for line in stdout
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+    {
+        match SEGMENT_LINE.captures(line) {
+            Some(captures) => {
+                let text = captures[9].trim().to_string();
+                if text.is_empty() {
+                    continue;
+                }
+                texts.push(text.clone());
+                utterances.push(SttUtterance {
+                    text,
+                    start_time: timestamp_ms(&captures, 1),
+                    end_time: timestamp_ms(&captures, 5),
+                    definite: true,
+                    words: Vec::new(),
+                });
+            }
+            None => texts.push(line.to_string()),
+        }
+    }
+
+    SttTranscript {
+        text: join_texts(&texts),
+        duration_ms: utterances.last().map(|last| last.end_time as u64),
+        utterances,
+    }
+}
+
+/// 拼接分句文本，中文之间不加空格
+fn join_texts(texts: &[String]) -> String {
+    let mut joined = String::new();
+    for text in texts {
+        let is_wide = |ch: char| ch > '\u{2e80}';
+        let needs_space = joined.chars().last().is_some_and(|last| !is_wide(last))
+            && text.chars().next().is_some_and(|first| !is_wide(first));
+        if needs_space {
+            joined.push(' ');
+        }
+        joined.push_str(text);
+    }
+    joined
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn spans(transcript: &SttTranscript) -> Vec<(&str, i64, i64)> {
+        transcript
+            .utterances
+            .iter()
+            .map(|utterance| {
+                (
+                    utterance.text.as_str(),
+                    utterance.start_time,
+                    utterance.end_time,
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn parses_whisper_timestamp_lines() {
+        let stdout = "\n[00:00:00.000 --> 00:00:02.480]   你好，\n\
+                      [00:00:02.480 --> 00:00:04.000]  世界。\n\
+                      [00:00:04.000 --> 00:00:05.000]\n\
+                      [01:02:03,004 --> 01:02:04,500]  Hello\n";
+        let transcript = parse_output(stdout);
+        // 没有文本的分句被跳过，逗号分隔的毫秒与小时位同样解析
+        assert_eq!(
+            spans(&transcript),
+            [
+                ("你好，", 0, 2480),
+                ("世界。", 2480, 4000),
+                ("Hello", 3_723_004, 3_724_500)
+            ]
+        );
+        assert_eq!(transcript.text, "你好，世界。Hello");
+        assert_eq!(transcript.duration_ms, Some(3_724_500));
+    }
+
+    #[test]
+    fn plain_output_only_contributes_text() {
+        let transcript = parse_output("  Hello there \n\nworld\n");
+        assert_eq!(transcript.text, "Hello there world");
+        assert!(transcript.utterances.is_empty());
+        assert_eq!(transcript.duration_ms, None);
+    }
+
+    #[test]
+    fn joins_texts_without_spaces_between_wide_characters() {
+        let texts = |items: &[&str]| {
+            items
+                .iter()
+                .map(|item| item.to_string())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(join_texts(&texts(&["Hello", "world"])), "Hello world");
+        assert_eq!(join_texts(&texts(&["你好", "世界", "ok"])), "你好世界ok");
+        assert_eq!(join_texts(&texts(&["版本", "2.0", "发布"])), "版本2.0发布");
+        assert_eq!(join_texts(&[]), "");
+    }
+}
@@ -0,0 +1,1261 @@
+mod command;
+mod openai;
+mod provider;
+
+use crate::commands::config::{
+    get_hotwords_path, get_volcengine_config, SttConfig, SttProviderKind, VolcengineConfig,
+};
+use crate::commands::tts::TtsSpeakingState;
+use crate::commands::voice_metrics;
+use futures_util::future::BoxFuture;
+use futures_util::stream::{SplitSink, SplitStream, Stream};
+use futures_util::{FutureExt, SinkExt, StreamExt};
+use reason_desktop_lib::audio::{pcm16_samples, PcmAudio, PcmConverter};
+use reason_desktop_lib::error::AppError;
+use reason_desktop_lib::hotwords;
+use reason_desktop_lib::metrics::{Phase, Stage};
+use reason_desktop_lib::net::{self, RetryPolicy, VoiceError};
+use reason_desktop_lib::tr;
+use reason_desktop_lib::vad::{self, Vad, VadEvent};
+use reason_desktop_lib::volcengine;
+use reason_desktop_lib::volcengine::protocol::{Compression, Frame, Serialization};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::path::PathBuf;
+use std::time::Instant;
+use tauri::{Emitter, Manager, State, WebviewWindow};
+use tokio::net::TcpStream;
+use tokio::sync::{mpsc, Mutex};
+use tokio::task::JoinHandle;
+use tokio::time::{timeout, Duration};
+use tokio_tungstenite::tungstenite::client::IntoClientRequest;
+use tokio_tungstenite::tungstenite::error::Error as WsError;
+use tokio_tungstenite::tungstenite::http::header::HeaderValue;
+use tokio_tungstenite::{connect_async, tungstenite::Message, MaybeTlsStream, WebSocketStream};
+use tracing::{info, warn, Instrument};
+use uuid::Uuid;
+
+use provider::{stt_provider, SttProvider};
+
+const STT_WS_ENDPOINT: &str = "wss://openspeech.bytedance.com/api/v3/sauc/bigmodel_async";
+
+type SttSocket = WebSocketStream<MaybeTlsStream<TcpStream>>;
+type SttWriter = SplitSink<SttSocket, Message>;
+type SttReader = SplitStream<SttSocket>;
+
+/// 未压缩音频（pcm / wav）每个分段的时长（按请求声明的采样参数换算成字节数）
+const STT_SEGMENT_MS: usize = 200;
+/// 未压缩音频的分段发送间隔，约 4 倍实时速度上传，避免长录音瞬间灌满服务端
+const STT_SEGMENT_INTERVAL_MS: u64 = 50;
+/// 压缩音频无法按时长换算字节数，按固定字节数分段
+const STT_COMPRESSED_SEGMENT_BYTES: usize = 4096;
+/// 压缩音频的分段发送间隔，128 kbps 的 MP3 约 5 倍实时速度，码率更低的 Opus 更快
+const STT_COMPRESSED_SEGMENT_INTERVAL_MS: u64 = 50;
+/// 等待服务端确认建连首包与最终结果的超时时间
+const STT_ACK_TIMEOUT_SECS: u64 = 10;
+/// 录音结束后整段识别的超时时间（不支持流式识别的提供方）
+const STT_BUFFERED_TIMEOUT_SECS: u64 = 180;
+/// 识别前峰值归一化的目标电平与最大增益（dB）
+const NORMALIZE_PEAK_DB: f32 = -1.0;
+const NORMALIZE_MAX_GAIN_DB: f32 = 20.0;
+/// 项目热词缓存有效期
+const PROJECT_HOTWORDS_TTL_SECS: u64 = 300;
+
+/// 最近一次扫描的项目目录、扫描时间与热词
+static PROJECT_HOTWORDS: std::sync::Mutex<Option<(PathBuf, Instant, Vec<String>)>> =
+    std::sync::Mutex::new(None);
+
+/// STT 请求参数
+#[derive(Debug, Serialize)]
+struct SttRequest {
+    user: SttUserConfig,
+    audio: SttAudioConfig,
+    request: SttRequestConfig,
+}
+
+#[derive(Debug, Serialize)]
+struct SttUserConfig {
+    uid: String,
+}
+
+#[derive(Debug, Serialize)]
+struct SttAudioConfig {
+    format: String,
+    codec: String,
+    rate: i32,
+    bits: i32,
+    channel: i32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    language: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct SttRequestConfig {
+    model_name: String,
+    #[serde(rename = "show_utterances")]
+    show_utterances: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    enable_itn: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    enable_punc: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    enable_ddc: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    end_window_size: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    corpus: Option<SttCorpus>,
+}
+
+/// 热词等识别上下文，`context` 为 JSON 字符串
+#[derive(Debug, Serialize)]
+struct SttCorpus {
+    context: String,
+}
+
+/// STT 响应
+#[derive(Debug, Deserialize)]
+struct SttResponse {
+    #[serde(default)]
+    result: Option<SttResult>,
+    #[serde(default)]
+    audio_info: Option<SttAudioInfo>,
+    #[serde(default)]
+    code: i32,
+    #[serde(default)]
+    message: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct SttResult {
+    #[serde(default)]
+    text: String,
+    #[serde(default)]
+    utterances: Vec<SttUtterance>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SttAudioInfo {
+    #[serde(default)]
+    duration: Option<u64>,
+}
+
+/// 带时间信息的识别结果
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct SttTranscript {
+    pub text: String,
+    pub utterances: Vec<SttUtterance>,
+    /// 服务端已处理的音频时长（毫秒）
+    #[serde(rename = "durationMs")]
+    pub duration_ms: Option<u64>,
+}
+
+/// 分句结果，时间单位为毫秒
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all(serialize = "camelCase"))]
+pub struct SttUtterance {
+    #[serde(default)]
+    pub text: String,
+    #[serde(default)]
+    pub start_time: i64,
+    #[serde(default)]
+    pub end_time: i64,
+    /// 是否为确定结果（后续不再改写）
+    #[serde(default)]
+    pub definite: bool,
+    #[serde(default)]
+    pub words: Vec<SttWord>,
+}
+
+/// 字词级时间戳，时间单位为毫秒
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all(serialize = "camelCase"))]
+pub struct SttWord {
+    #[serde(default)]
+    pub text: String,
+    #[serde(default)]
+    pub start_time: i64,
+    #[serde(default)]
+    pub end_time: i64,
+    #[serde(default)]
+    pub blank_duration: i64,
+}
+
+#[derive(Clone, Serialize)]
+pub struct SttPartialPayload {
+    pub text: String,
+}
+
+#[derive(Clone, Serialize)]
+pub struct SttFinalPayload {
+    pub text: String,
+}
+
+/// 流式识别错误事件，与命令返回的错误结构相同
+pub type SttStreamErrorPayload = AppError;
+
+/// 语音起止事件，`atMs` 为相对录音开头的毫秒数
+#[derive(Clone, Serialize)]
+pub struct SttVadPayload {
+    #[serde(rename = "atMs")]
+    pub at_ms: u64,
+}
+
+/// 进行中的流式识别
+struct SttStreamHandle {
+    audio_tx: mpsc::UnboundedSender<Vec<u8>>,
+    task: JoinHandle<Result<String, AppError>>,
+    /// 录音结束后等待最终结果的时间
+    finish_timeout: Duration,
+    /// 输入为 PCM 时检测语音起止，一句话说完通知前端自动停止录音
+    vad: Option<Vad>,
+    /// VAD 是否检测到过语音起点，没有则结束时按无语音处理
+    heard_speech: bool,
+    /// 采集格式与请求声明的格式不同时，把推送的 PCM 转为声明的格式
+    converter: Option<PcmConverter>,
+    window: WebviewWindow,
+}
+
+/// 流式识别状态，由 Tauri 托管
+#[derive(Default)]
+pub struct SttStreamState {
+    active: Mutex<Option<SttStreamHandle>>,
+}
+
+/// 服务端对单个请求包的确认
+struct SttAck {
+    result: Option<SttTranscript>,
+    is_last: bool,
+}
+
+/// WebSocket 消息构建
+fn build_full_client_request(
+    config: &SttRequest,
+    sequence: i32,
+    compression: Compression,
+) -> Result<Vec<u8>, VoiceError> {
+    let payload = serde_json::to_vec(config)
+        .map_err(|e| VoiceError::input(tr!("protocol.serialize_failed", error = e)))?;
+    Frame::full_client_request(payload)
+        .with_sequence(sequence)
+        .compress(compression)
+        .and_then(|frame| frame.encode())
+        .map_err(|e| VoiceError::input(tr!("protocol.build_frame_failed", error = e)))
+}
+
+fn build_audio_only_request(
+    audio_data: &[u8],
+    sequence: i32,
+    compression: Compression,
+) -> Result<Vec<u8>, VoiceError> {
+    Frame::audio_only_request(audio_data.to_vec(), sequence < 0)
+        .with_sequence(sequence)
+        .compress(compression)
+        .and_then(|frame| frame.encode())
+        .map_err(|e| VoiceError::input(tr!("protocol.build_audio_frame_failed", error = e)))
+}
+
+fn parse_server_payload(data: &[u8]) -> Result<Frame, VoiceError> {
+    let frame = Frame::decode(data)
+        .and_then(Frame::decompress)
+        .map_err(|e| VoiceError::protocol(tr!("protocol.parse_frame_failed", error = e)))?;
+    if frame.serialization != Serialization::Json && frame.serialization != Serialization::None {
+        return Err(VoiceError::protocol(tr!(
+            "protocol.unsupported_serialization"
+        )));
+    }
+
+    if frame.is_error() {
+        return Err(volcengine::frame_error(
+            &tr!("protocol.server_error"),
+            &frame,
+        ));
+    }
+
+    Ok(frame)
+}
+
+fn parse_response(payload: &[u8]) -> Result<Option<SttTranscript>, VoiceError> {
+    match serde_json::from_slice::<SttResponse>(payload) {
+        Ok(response) if response.code != 0 => Err(VoiceError::from_service(
+            &tr!("stt.failed"),
+            Some(response.code as i64),
+            &response.message,
+        )),
+        Ok(response) => Ok(response.result.map(|result| SttTranscript {
+            text: result.text,
+            utterances: result.utterances,
+            duration_ms: response.audio_info.and_then(|info| info.duration),
+        })),
+        Err(_) => Ok(None),
+    }
+}
+
+/// 整段上传时单个音频分段的字节数与发送间隔
+///
+/// 未压缩的 pcm / wav 按声明的采样参数换算为固定时长；压缩格式的码率未知，按固定字节数分段。
+fn segment_pacing(audio: &SttAudioConfig) -> (usize, Duration) {
+    if !matches!(audio.format.as_str(), "pcm" | "wav") {
+        return (
+            STT_COMPRESSED_SEGMENT_BYTES,
+            Duration::from_millis(STT_COMPRESSED_SEGMENT_INTERVAL_MS),
+        );
+    }
+    let bytes_per_second = audio.rate * (audio.bits / 8) * audio.channel;
+    (
+        (bytes_per_second.max(0) as usize * STT_SEGMENT_MS / 1000).max(1),
+        Duration::from_millis(STT_SEGMENT_INTERVAL_MS),
+    )
+}
+
+/// 读取服务端对 `sent_sequence` 及之前分段的确认，超时视为失败
+async fn read_ack<S>(read: &mut S, sent_sequence: i32) -> Result<SttAck, VoiceError>
+where
+    S: Stream<Item = Result<Message, WsError>> + Unpin,
+{
+    net::with_timeout(
+        Duration::from_secs(STT_ACK_TIMEOUT_SECS),
+        &tr!("stt.ack_wait", sequence = sent_sequence.abs()),
+        next_ack(read, sent_sequence),
+    )
+    .await
+}
+
+async fn next_ack<S>(read: &mut S, sent_sequence: i32) -> Result<SttAck, VoiceError>
+where
+    S: Stream<Item = Result<Message, WsError>> + Unpin,
+{
+    while let Some(message) = read.next().await {
+        match message {
+            Ok(Message::Binary(data)) => {
+                let frame = parse_server_payload(&data)?;
+                if let Some(sequence) = frame.sequence {
+                    if sequence.abs() > sent_sequence.abs() {
+                        return Err(VoiceError::protocol(tr!(
+                            "stt.ack_sequence_mismatch",
+                            sent = sent_sequence,
+                            received = sequence
+                        )));
+                    }
+                }
+                return Ok(SttAck {
+                    result: parse_response(&frame.payload)?,
+                    is_last: frame.is_last(),
+                });
+            }
+            Ok(Message::Text(text)) => {
+                return Ok(SttAck {
+                    result: parse_response(text.as_bytes())?,
+                    is_last: false,
+                });
+            }
+            Ok(Message::Close(_)) => break,
+            Err(e) => return Err(VoiceError::from_ws(&tr!("ws.receive_failed"), &e)),
+            _ => {}
+        }
+    }
+
+    Ok(SttAck {
+        result: None,
+        is_last: true,
+    })
+}
+
+fn parse_audio_format(mime_type: &str) -> Result<(String, String), AppError> {
+    let mime = mime_type.to_lowercase();
+    // 浏览器 MediaRecorder 的 webm / ogg 录音均为 Opus 编码，mp4 为 AAC
+    if mime.contains("webm") {
+        return Ok(("webm".to_string(), "opus".to_string()));
+    }
+    if mime.contains("ogg") {
+        return Ok(("ogg".to_string(), "opus".to_string()));
+    }
+    if mime.contains("mp4") || mime.contains("m4a") || mime.contains("aac") {
+        return Ok(("mp4".to_string(), "aac".to_string()));
+    }
+    if mime.contains("mpeg") || mime.contains("mp3") {
+        return Ok(("mp3".to_string(), "raw".to_string()));
+    }
+    if mime.contains("wav") {
+        return Ok(("wav".to_string(), "raw".to_string()));
+    }
+    if mime.contains("pcm") {
+        return Ok(("pcm".to_string(), "raw".to_string()));
+    }
+    if mime.is_empty() {
+        return Ok(("webm".to_string(), "opus".to_string()));
+    }
+
+    Err(AppError::Input(tr!(
+        "audio.unsupported_format",
+        format = mime_type
+    )))
+}
+
+/// 请求声明的采样率与声道数
+fn declared_format(stt_config: &SttConfig) -> Result<(u32, u16), AppError> {
+    let rate = stt_config.sample_rate.unwrap_or(16000);
+    if rate <= 0 {
+        return Err(AppError::Config(tr!(
+            "stt.invalid_sample_rate",
+            rate = rate
+        )));
+    }
+    let channel = stt_config.channel.unwrap_or(1);
+    if !(1..=2).contains(&channel) {
+        return Err(AppError::Config(tr!(
+            "stt.invalid_channels",
+            channels = channel
+        )));
+    }
+    Ok((rate as u32, channel as u16))
+}
+
+/// 裸 PCM 的实际采样率与声道数，取自 mime 参数（如 `audio/pcm;rate=16000;channels=1`），
+/// 未给出时视为已是请求声明的格式
+fn pcm_format(mime_type: &str, declared: (u32, u16)) -> Result<(u32, u16), AppError> {
+    let (mut rate, mut channels) = declared;
+    for param in mime_type.split(';').skip(1) {
+        let Some((name, value)) = param.split_once('=') else {
+            continue;
+        };
+        let invalid = || AppError::Input(tr!("stt.invalid_pcm_param", param = param.trim()));
+        let value = value.trim();
+        match name.trim().to_lowercase().as_str() {
+            "rate" => {
+                rate = value
+                    .parse()
+                    .ok()
+                    .filter(|&rate| rate > 0)
+                    .ok_or_else(invalid)?
+            }
+            "channels" => {
+                channels = value
+                    .parse()
+                    .ok()
+                    .filter(|channels| (1..=2).contains(channels))
+                    .ok_or_else(invalid)?
+            }
+            _ => {}
+        }
+    }
+    Ok((rate, channels))
+}
+
+/// 识别前的音频准备，保证请求声明的格式与实际上传的字节一致
+///
+/// WAV 与 PCM 解码后按声明的声道数与采样率混缩、重采样；PCM 的实际格式见 [`pcm_format`]。
+/// 随后裁剪首尾静音（整段没有语音时直接报错，省去一次空识别）并做峰值归一化。
+/// 压缩格式（webm / ogg / mp4 / mp3）由服务端解码，原样上传。
+fn prepare_audio(
+    audio: Vec<u8>,
+    mime_type: &str,
+    stt_config: &SttConfig,
+) -> Result<Vec<u8>, AppError> {
+    let (format, _) = parse_audio_format(mime_type)?;
+    let (rate, channels) = declared_format(stt_config)?;
+    let decoded = match format.as_str() {
+        "wav" => PcmAudio::from_wav(&audio).map_err(AppError::Input)?,
+        "pcm" => {
+            let (pcm_rate, pcm_channels) = pcm_format(mime_type, (rate, channels))?;
+            PcmAudio::from_pcm16(&audio, pcm_rate, pcm_channels)
+        }
+        _ => return Ok(audio),
+    };
+    let mut prepared = decoded.remix(channels).resample(rate);
+
+    if stt_config.vad.enabled {
+        let mono = pcm16_samples(&prepared.clone().remix(1).to_pcm16());
+        let frames = vad::speech_range(&mono, rate, &stt_config.vad)
+            .ok_or_else(|| AppError::Input(tr!("stt.no_speech")))?;
+        let channels = channels as usize;
+        prepared.samples =
+            prepared.samples[frames.start * channels..frames.end * channels].to_vec();
+    }
+
+    let prepared = prepared.normalize(NORMALIZE_PEAK_DB, NORMALIZE_MAX_GAIN_DB);
+    Ok(if format == "wav" {
+        prepared.to_wav()
+    } else {
+        prepared.to_pcm16()
+    })
+}
+
+/// 在阻塞线程中准备音频，长录音的重采样不占用异步运行时
+async fn prepare_audio_blocking(
+    audio: Vec<u8>,
+    mime_type: &str,
+    stt_config: &SttConfig,
+) -> Result<Vec<u8>, AppError> {
+    let mime_type = mime_type.to_string();
+    let stt_config = stt_config.clone();
+    tokio::task::spawn_blocking(move || prepare_audio(audio, &mime_type, &stt_config))
+        .await
+        .map_err(|e| AppError::Internal(tr!("stt.preprocess_failed", error = e)))?
+}
+
+/// 校验鉴权配置，返回语音识别资源 ID
+fn resolve_resource_id(volcengine_config: &VolcengineConfig) -> Result<String, AppError> {
+    if volcengine_config.app_id.is_empty() || volcengine_config.access_token.is_empty() {
+        return Err(AppError::Config(tr!("config.volcengine_missing")));
+    }
+    let resource_id = if !volcengine_config.stt.resource_id.is_empty() {
+        volcengine_config.stt.resource_id.clone()
+    } else {
+        volcengine_config
+            .legacy_resource_id
+            .clone()
+            .unwrap_or_default()
+    };
+    if resource_id.is_empty() {
+        return Err(AppError::Config(tr!("stt.resource_id_missing")));
+    }
+    Ok(resource_id)
+}
+
+/// 热词来源目录：优先使用配置，其次是项目根目录形式的当前工作目录
+fn hotword_project_dir(stt_config: &SttConfig) -> Option<PathBuf> {
+    if let Some(dir) = &stt_config.project_dir {
+        return Some(PathBuf::from(dir));
+    }
+    std::env::current_dir()
+        .ok()
+        .filter(|dir| hotwords::is_project_root(dir))
+}
+
+fn project_hotwords(root: PathBuf) -> Vec<String> {
+    let mut cache = PROJECT_HOTWORDS.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some((cached_root, scanned_at, words)) = cache.as_ref() {
+        if *cached_root == root && scanned_at.elapsed().as_secs() < PROJECT_HOTWORDS_TTL_SECS {
+            return words.clone();
+        }
+    }
+
+    let words = hotwords::harvest_project(&root);
+    *cache = Some((root, Instant::now(), words.clone()));
+    words
+}
+
+/// 汇总本次识别使用的热词
+async fn collect_hotwords(stt_config: &SttConfig) -> Vec<String> {
+    if !stt_config.enable_hotwords {
+        return Vec::new();
+    }
+    let project_dir = hotword_project_dir(stt_config);
+
+    tokio::task::spawn_blocking(move || {
+        let vocabulary = hotwords::load_vocabulary(&get_hotwords_path());
+        let project = project_dir.map(project_hotwords).unwrap_or_default();
+        hotwords::build_hotwords(vocabulary, project, hotwords::MAX_HOTWORDS)
+    })
+    .await
+    .unwrap_or_default()
+}
+
+fn build_request(
+    mime_type: &str,
+    stt_config: &SttConfig,
+    hotwords: &[String],
+) -> Result<SttRequest, AppError> {
+    let (format, codec) = parse_audio_format(mime_type)?;
+    let (rate, channel) = declared_format(stt_config)?;
+    let model_name = if stt_config.model_name.is_empty() {
+        "bigmodel".to_string()
+    } else {
+        stt_config.model_name.clone()
+    };
+    let corpus = if hotwords.is_empty() {
+        None
+    } else {
+        let words: Vec<_> = hotwords
+            .iter()
+            .map(|word| json!({ "word": word }))
+            .collect();
+        Some(SttCorpus {
+            context: json!({ "hotwords": words }).to_string(),
+        })
+    };
+
+    Ok(SttRequest {
+        user: SttUserConfig {
+            uid: "reason-desktop".to_string(),
+        },
+        audio: SttAudioConfig {
+            format,
+            codec,
+            rate: rate as i32,
+            bits: 16,
+            channel: channel as i32,
+            language: stt_config.language.clone(),
+        },
+        request: SttRequestConfig {
+            model_name,
+            show_utterances: true,
+            enable_itn: stt_config.enable_itn,
+            enable_punc: stt_config.enable_punc,
+            enable_ddc: stt_config.enable_ddc,
+            end_window_size: stt_config.end_window_size,
+            corpus,
+        },
+    })
+}
+
+/// 建立 SAUC WebSocket 连接，网络错误时退避重试
+async fn open_session(
+    volcengine_config: &VolcengineConfig,
+    resource_id: &str,
+    request: &SttRequest,
+) -> Result<(SttWriter, SttReader), VoiceError> {
+    net::retry(RetryPolicy::default(), &tr!("stt.connect"), || {
+        open_session_once(volcengine_config, resource_id, request)
+    })
+    .await
+}
+
+/// 建立一次 SAUC WebSocket 连接，发送首包（序号 1）并等待确认
+async fn open_session_once(
+    volcengine_config: &VolcengineConfig,
+    resource_id: &str,
+    request: &SttRequest,
+) -> Result<(SttWriter, SttReader), VoiceError> {
+    let mut ws_request = volcengine::endpoint(STT_WS_ENDPOINT)
+        .into_client_request()
+        .map_err(|e| VoiceError::input(tr!("ws.build_request_failed", error = e)))?;
+    ws_request.headers_mut().insert(
+        "X-Api-App-Key",
+        HeaderValue::from_str(&volcengine_config.app_id).map_err(|e| {
+            VoiceError::input(tr!(
+                "ws.invalid_header",
+                header = "X-Api-App-Key",
+                error = e
+            ))
+        })?,
+    );
+    ws_request.headers_mut().insert(
+        "X-Api-Access-Key",
+        HeaderValue::from_str(&volcengine_config.access_token).map_err(|e| {
+            VoiceError::input(tr!(
+                "ws.invalid_header",
+                header = "X-Api-Access-Key",
+                error = e
+            ))
+        })?,
+    );
+    ws_request.headers_mut().insert(
+        "X-Api-Resource-Id",
+        HeaderValue::from_str(resource_id).map_err(|e| {
+            VoiceError::input(tr!(
+                "ws.invalid_header",
+                header = "X-Api-Resource-Id",
+                error = e
+            ))
+        })?,
+    );
+    ws_request.headers_mut().insert(
+        "X-Api-Connect-Id",
+        HeaderValue::from_str(&Uuid::new_v4().to_string()).map_err(|e| {
+            VoiceError::input(tr!(
+                "ws.invalid_header",
+                header = "X-Api-Connect-Id",
+                error = e
+            ))
+        })?,
+    );
+
+    let (ws_stream, _) = net::with_timeout(net::CONNECT_TIMEOUT, &tr!("ws.connect"), async {
+        connect_async(ws_request)
+            .await
+            .map_err(|e| VoiceError::from_ws(&tr!("ws.connect_failed"), &e))
+    })
+    .await?;
+
+    let (mut write, mut read) = ws_stream.split();
+
+    let message = build_full_client_request(request, 1, volcengine_config.payload_compression())?;
+    write
+        .send(Message::Binary(message))
+        .await
+        .map_err(|e| VoiceError::from_ws(&tr!("stt.send_request_failed"), &e))?;
+    let ack = read_ack(&mut read, 1).await?;
+    if ack.is_last {
+        return Err(VoiceError::network(tr!("stt.closed_early")));
+    }
+
+    Ok((write, read))
+}
+
+/// 火山引擎大模型流式语音识别（SAUC）
+struct VolcengineStt {
+    config: VolcengineConfig,
+}
+
+impl VolcengineStt {
+    fn new(config: VolcengineConfig) -> Self {
+        Self { config }
+    }
+
+    async fn recognize(
+        &self,
+        audio_bytes: &[u8],
+        mime_type: &str,
+        hotwords: &[String],
+    ) -> Result<SttTranscript, AppError> {
+        let volcengine_config = &self.config;
+        let resource_id = resolve_resource_id(volcengine_config)?;
+
+        // 构建请求
+        let request = build_request(mime_type, &volcengine_config.stt, hotwords)?;
+        let (segment_bytes, interval) = segment_pacing(&request.audio);
+        let segments: Vec<&[u8]> = audio_bytes.chunks(segment_bytes).collect();
+        if segments.is_empty() {
+            return Err(AppError::Input(tr!("stt.empty_audio")));
+        }
+
+        // 整段录音在本地，连接中断时可以从头重新识别
+        Ok(
+            net::retry(RetryPolicy::default(), &tr!("stt.recognize"), || {
+                self.recognize_once(&resource_id, &request, &segments, interval)
+            })
+            .await?,
+        )
+    }
+
+    async fn recognize_once(
+        &self,
+        resource_id: &str,
+        request: &SttRequest,
+        segments: &[&[u8]],
+        interval: Duration,
+    ) -> Result<SttTranscript, VoiceError> {
+        let volcengine_config = &self.config;
+
+        // 连接 WebSocket 并发送首包
+        let (mut write, mut read) =
+            open_session_once(volcengine_config, resource_id, request).await?;
+        let compression = volcengine_config.payload_compression();
+
+        let mut transcript = SttTranscript::default();
+        let mut sequence = 1;
+        let mut sent = 0;
+        let mut ticker = tokio::time::interval(interval);
+
+        // 按固定节奏发送分段（最后一段使用负序号），同时接收中间结果。
+        // 服务端只在结果变化时回复，发送期间不等待确认，长时间静音也不会超时
+        while sent < segments.len() {
+            tokio::select! {
+                _ = ticker.tick() => {
+                    sequence += 1;
+                    sent += 1;
+                    let sent_sequence = if sent == segments.len() { -sequence } else { sequence };
+                    let audio_message =
+                        build_audio_only_request(segments[sent - 1], sent_sequence, compression)?;
+                    write
+                        .send(Message::Binary(audio_message))
+                        .await
+                        .map_err(|e| VoiceError::from_ws(&tr!("stt.send_audio_failed"), &e))?;
+                }
+                ack = next_ack(&mut read, sequence) => {
+                    let ack = ack?;
+                    if let Some(result) = ack.result {
+                        transcript = result;
+                    }
+                    if ack.is_last {
+                        return Err(VoiceError::network(tr!("stt.closed_early")));
+                    }
+                }
+            }
+        }
+
+        // 最后一段发出后等待最终识别结果，每次回复都需在时限内到达
+        loop {
+            let ack = read_ack(&mut read, -sequence).await?;
+            if let Some(result) = ack.result {
+                transcript = result;
+            }
+            if ack.is_last {
+                break;
+            }
+        }
+
+        Ok(transcript)
+    }
+}
+
+impl SttProvider for VolcengineStt {
+    fn transcribe<'a>(
+        &'a self,
+        audio: &'a [u8],
+        mime_type: &'a str,
+        hotwords: &'a [String],
+    ) -> BoxFuture<'a, Result<SttTranscript, AppError>> {
+        self.recognize(audio, mime_type, hotwords).boxed()
+    }
+}
+
+/// 识别一段完整录音，返回带分句与字词时间的结果
+async fn transcribe(
+    app: &tauri::AppHandle,
+    audio_bytes: Vec<u8>,
+    mime_type: &str,
+) -> Result<SttTranscript, AppError> {
+    let started = Instant::now();
+    voice_metrics::begin(app, Phase::Stt);
+
+    // 获取配置
+    let volcengine_config = get_volcengine_config().await?;
+    if audio_bytes.is_empty() {
+        return Err(AppError::Input(tr!("stt.empty_audio")));
+    }
+
+    let audio = prepare_audio_blocking(audio_bytes, mime_type, &volcengine_config.stt).await?;
+
+    let provider = stt_provider(&volcengine_config);
+    let hotwords = collect_hotwords(&volcengine_config.stt).await;
+    let transcript = provider.transcribe(&audio, mime_type, &hotwords).await?;
+    // 整段识别的连接、上传与等待结果无法区分，只记录整体耗时
+    voice_metrics::record(app, Stage::SttFinal, started.elapsed());
+    Ok(transcript)
+}
+
+/// 语音识别
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(bytes = audio_bytes.len(), mime_type = %mime_type))]
+pub async fn stt_transcribe(
+    app: tauri::AppHandle,
+    audio_bytes: Vec<u8>,
+    mime_type: String,
+) -> Result<String, AppError> {
+    transcribe(&app, audio_bytes, &mime_type)
+        .await
+        .map(|transcript| transcript.text)
+}
+
+/// 语音识别 - 返回分句、字词时间戳与确定标记
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(bytes = audio_bytes.len(), mime_type = %mime_type))]
+pub async fn stt_transcribe_detailed(
+    app: tauri::AppHandle,
+    audio_bytes: Vec<u8>,
+    mime_type: String,
+) -> Result<SttTranscript, AppError> {
+    transcribe(&app, audio_bytes, &mime_type).await
+}
+
+/// 流式识别的后台任务：转发麦克风分段，推送中间 / 最终结果
+async fn run_stream(
+    window: WebviewWindow,
+    mut write: SttWriter,
+    mut read: SttReader,
+    mut audio_rx: mpsc::UnboundedReceiver<Vec<u8>>,
+    compression: Compression,
+) -> Result<String, AppError> {
+    let app = window.app_handle().clone();
+    let connected = Instant::now();
+    let mut last_sent = connected;
+    let mut transcript = String::new();
+    let mut sequence = 1;
+    let mut finishing = false;
+
+    loop {
+        tokio::select! {
+            chunk = audio_rx.recv(), if !finishing => {
+                sequence += 1;
+                // 发送端关闭表示录音结束，补一个空的负序号包
+                let audio_message = match chunk {
+                    Some(chunk) => build_audio_only_request(&chunk, sequence, compression)?,
+                    None => {
+                        finishing = true;
+                        build_audio_only_request(&[], -sequence, compression)?
+                    }
+                };
+                write
+                    .send(Message::Binary(audio_message))
+                    .await
+                    .map_err(|e| VoiceError::from_ws(&tr!("stt.send_audio_failed"), &e))?;
+                if finishing {
+                    last_sent = Instant::now();
+                    voice_metrics::record(&app, Stage::SttUpload, connected.elapsed());
+                }
+            }
+            ack = next_ack(&mut read, if finishing { -sequence } else { sequence }) => {
+                let ack = ack?;
+                if let Some(result) = ack.result {
+                    if result.text != transcript {
+                        transcript = result.text;
+                        let _ = window.emit(
+                            "stt-partial",
+                            SttPartialPayload {
+                                text: transcript.clone(),
+                            },
+                        );
+                    }
+                }
+                if ack.is_last {
+                    break;
+                }
+            }
+        }
+    }
+    voice_metrics::record(&app, Stage::SttFinal, last_sent.elapsed());
+
+    let _ = window.emit(
+        "stt-final",
+        SttFinalPayload {
+            text: transcript.clone(),
+        },
+    );
+
+    Ok(transcript)
+}
+
+/// 不支持流式识别的提供方：录音结束后整段识别，只推送最终结果
+async fn run_buffered(
+    window: WebviewWindow,
+    volcengine_config: VolcengineConfig,
+    mime_type: String,
+    hotwords: Vec<String>,
+    mut audio_rx: mpsc::UnboundedReceiver<Vec<u8>>,
+) -> Result<String, AppError> {
+    let mut audio = Vec::new();
+    while let Some(chunk) = audio_rx.recv().await {
+        audio.extend_from_slice(&chunk);
+    }
+    if audio.is_empty() {
+        return Err(AppError::Input(tr!("stt.empty_audio")));
+    }
+    let recorded = Instant::now();
+    let audio = prepare_audio_blocking(audio, &mime_type, &volcengine_config.stt).await?;
+
+    let provider = stt_provider(&volcengine_config);
+    let transcript = provider.transcribe(&audio, &mime_type, &hotwords).await?;
+    voice_metrics::record(window.app_handle(), Stage::SttFinal, recorded.elapsed());
+    let _ = window.emit(
+        "stt-final",
+        SttFinalPayload {
+            text: transcript.text.clone(),
+        },
+    );
+
+    Ok(transcript.text)
+}
+
+/// 流式识别 - 建立连接，之后通过 `stt_stream_push` 推送音频
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(mime_type = %mime_type))]
+pub async fn stt_stream_start(
+    app: tauri::AppHandle,
+    state: State<'_, SttStreamState>,
+    mime_type: String,
+) -> Result<(), AppError> {
+    // 开始录音即打断正在进行的播报
+    app.state::<TtsSpeakingState>().cancel();
+    let started = Instant::now();
+    voice_metrics::begin(&app, Phase::Stt);
+
+    let volcengine_config = get_volcengine_config().await?;
+    let hotwords = collect_hotwords(&volcengine_config.stt).await;
+
+    let window = app
+        .get_webview_window("main")
+        .ok_or_else(AppError::window_not_found)?;
+
+    // 同一时间只保留一路流式识别
+    if let Some(previous) = state.active.lock().await.take() {
+        previous.task.abort();
+    }
+
+    let stt_config = &volcengine_config.stt;
+    let (format, _) = parse_audio_format(&mime_type)?;
+    let (rate, channels) = declared_format(stt_config)?;
+    // 推送的 PCM 按实际采集格式检测语音
+    let capture = if format == "pcm" {
+        Some(pcm_format(&mime_type, (rate, channels))?)
+    } else {
+        None
+    };
+    let vad = capture
+        .filter(|&(_, capture_channels)| stt_config.vad.enabled && capture_channels == 1)
+        .map(|(capture_rate, _)| Vad::new(stt_config.vad, capture_rate));
+
+    let (audio_tx, audio_rx) = mpsc::unbounded_channel();
+    let streaming = volcengine_config.stt.provider == SttProviderKind::Volcengine;
+    let (stream, finish_timeout, converter) = if streaming {
+        let resource_id = resolve_resource_id(&volcengine_config)?;
+        let request = build_request(&mime_type, &volcengine_config.stt, &hotwords)?;
+        let (write, read) = open_session(&volcengine_config, &resource_id, &request).await?;
+        voice_metrics::record(&app, Stage::SttConnect, started.elapsed());
+        let compression = volcengine_config.payload_compression();
+        (
+            run_stream(window.clone(), write, read, audio_rx, compression).boxed(),
+            Duration::from_secs(STT_ACK_TIMEOUT_SECS),
+            // 流式识别直接转发分段，发送前转为请求声明的格式
+            capture
+                .map(|(capture_rate, capture_channels)| {
+                    PcmConverter::new(capture_rate, capture_channels, rate, channels)
+                })
+                .filter(|converter| !converter.is_passthrough()),
+        )
+    } else {
+        let config = volcengine_config.clone();
+        (
+            run_buffered(window.clone(), config, mime_type, hotwords, audio_rx).boxed(),
+            Duration::from_secs(STT_BUFFERED_TIMEOUT_SECS),
+            // 整段识别的提供方在识别前按 mime 参数统一转换
+            None,
+        )
+    };
+
+    let handle_window = window.clone();
+    let task = tokio::spawn(
+        async move {
+            let result = stream.await;
+            match &result {
+                Ok(text) => info!(text_len = text.chars().count(), "stt stream finished"),
+                Err(error) => {
+                    warn!(error = %error, "stt stream failed");
+                    let _ = window.emit::<SttStreamErrorPayload>("stt-stream-error", error.clone());
+                }
+            }
+            result
+        }
+        .in_current_span(),
+    );
+
+    // 建连期间可能有另一次启动抢先登记，被替换的一路同样中止
+    let replaced = state.active.lock().await.replace(SttStreamHandle {
+        audio_tx,
+        task,
+        finish_timeout,
+        vad,
+        heard_speech: false,
+        converter,
+        window: handle_window,
+    });
+    if let Some(previous) = replaced {
+        previous.task.abort();
+    }
+    Ok(())
+}
+
+/// 流式识别 - 推送一段麦克风音频
+#[tauri::command]
+pub async fn stt_stream_push(
+    state: State<'_, SttStreamState>,
+    chunk: Vec<u8>,
+) -> Result<(), AppError> {
+    let mut active = state.active.lock().await;
+    let stream = active
+        .as_mut()
+        .ok_or_else(|| AppError::State(tr!("stt.stream_not_started")))?;
+    let events = stream
+        .vad
+        .as_mut()
+        .map(|vad| vad.push_bytes(&chunk))
+        .unwrap_or_default();
+    let chunk = match stream.converter.as_mut() {
+        Some(converter) => converter.push(&chunk),
+        None => chunk,
+    };
+    // 重采样滞后几毫秒，开头的分段可能还没有输出
+    if !chunk.is_empty() {
+        stream
+            .audio_tx
+            .send(chunk)
+            .map_err(|_| AppError::State(tr!("stt.stream_ended")))?;
+    }
+
+    for event in events {
+        let (name, at_ms) = match event {
+            VadEvent::SpeechStart { at_ms } => {
+                stream.heard_speech = true;
+                ("stt-speech-start", at_ms)
+            }
+            VadEvent::SpeechEnd { at_ms } => ("stt-utterance-end", at_ms),
+        };
+        let _ = stream.window.emit(name, SttVadPayload { at_ms });
+    }
+    Ok(())
+}
+
+/// 流式识别 - 结束录音并返回最终文本
+#[tauri::command]
+pub async fn stt_stream_finish(state: State<'_, SttStreamState>) -> Result<String, AppError> {
+    let mut stream = state
+        .active
+        .lock()
+        .await
+        .take()
+        .ok_or_else(|| AppError::State(tr!("stt.stream_not_started")))?;
+
+    // 整段录音没有检测到语音时不发送最后一包，直接中止识别
+    if stream.vad.is_some() && !stream.heard_speech {
+        stream.task.abort();
+        return Err(AppError::Input(tr!("stt.no_speech")));
+    }
+
+    // 送出重采样滞留的尾部，再丢弃发送端通知后台任务发送最后一包
+    if let Some(rest) = stream.converter.as_mut().map(PcmConverter::finish) {
+        if !rest.is_empty() {
+            let _ = stream.audio_tx.send(rest);
+        }
+    }
+    drop(stream.audio_tx);
+
+    let abort_handle = stream.task.abort_handle();
+    match timeout(stream.finish_timeout, stream.task).await {
+        Ok(result) => {
+            result.map_err(|e| AppError::Internal(tr!("stt.stream_task_failed", error = e)))?
+        }
+        Err(_) => {
+            abort_handle.abort();
+            Err(VoiceError::network(tr!("stt.final_timeout")).fatal().into())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request_json(
+        mime_type: &str,
+        stt_config: &SttConfig,
+        hotwords: &[String],
+    ) -> serde_json::Value {
+        serde_json::to_value(build_request(mime_type, stt_config, hotwords).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn builds_request_from_config() {
+        let stt_config = SttConfig {
+            model_name: String::new(),
+            sample_rate: Some(8000),
+            channel: Some(2),
+            enable_itn: Some(true),
+            language: Some("zh-CN".to_string()),
+            ..SttConfig::default()
+        };
+        let request = request_json("audio/wav", &stt_config, &["Tauri".to_string()]);
+        assert_eq!(
+            request["audio"],
+            json!({
+                "format": "wav",
+                "codec": "raw",
+                "rate": 8000,
+                "bits": 16,
+                "channel": 2,
+                "language": "zh-CN",
+            })
+        );
+        // 未配置模型时使用 bigmodel，未设置的开关不发送
+        assert_eq!(
+            request["request"],
+            json!({
+                "model_name": "bigmodel",
+                "show_utterances": true,
+                "enable_itn": true,
+                "corpus": { "context": r#"{"hotwords":[{"word":"Tauri"}]}"# },
+            })
+        );
+
+        let request = request_json("audio/webm;codecs=opus", &SttConfig::default(), &[]);
+        assert_eq!(request["audio"]["format"], "webm");
+        assert_eq!(request["audio"]["codec"], "opus");
+        assert_eq!(request["audio"]["rate"], 16000);
+        assert_eq!(request["audio"]["channel"], 1);
+        assert!(request["request"].get("corpus").is_none());
+    }
+
+    #[test]
+    fn rejects_invalid_request_formats() {
+        let invalid_rate = SttConfig {
+            sample_rate: Some(0),
+            ..SttConfig::default()
+        };
+        let invalid_channels = SttConfig {
+            channel: Some(3),
+            ..SttConfig::default()
+        };
+        assert!(matches!(
+            build_request("audio/wav", &invalid_rate, &[]),
+            Err(AppError::Config(_))
+        ));
+        assert!(matches!(
+            build_request("audio/wav", &invalid_channels, &[]),
+            Err(AppError::Config(_))
+        ));
+        assert!(matches!(
+            build_request("audio/flac", &SttConfig::default(), &[]),
+            Err(AppError::Input(_))
+        ));
+    }
+
+    #[test]
+    fn paces_uncompressed_audio_by_duration_and_compressed_by_size() {
+        let pacing = |mime_type: &str, stt_config: &SttConfig| {
+            segment_pacing(&build_request(mime_type, stt_config, &[]).unwrap().audio)
+        };
+        // 16kHz 单声道 16 位：200ms 为 6400 字节
+        assert_eq!(
+            pacing("audio/pcm", &SttConfig::default()),
+            (6400, Duration::from_millis(STT_SEGMENT_INTERVAL_MS))
+        );
+        let stereo = SttConfig {
+            sample_rate: Some(8000),
+            channel: Some(2),
+            ..SttConfig::default()
+        };
+        assert_eq!(pacing("audio/wav", &stereo).0, 6400);
+        // 压缩格式与采样参数无关，按固定字节数分段
+        for mime_type in ["audio/ogg", "audio/mpeg", "audio/webm"] {
+            assert_eq!(
+                pacing(mime_type, &stereo),
+                (
+                    STT_COMPRESSED_SEGMENT_BYTES,
+                    Duration::from_millis(STT_COMPRESSED_SEGMENT_INTERVAL_MS)
+                )
+            );
+        }
+    }
+
+    #[test]
+    fn reads_pcm_capture_format_from_mime() {
+        assert_eq!(pcm_format("audio/pcm", (8000, 2)).unwrap(), (8000, 2));
+        assert_eq!(
+            pcm_format("audio/pcm; rate=16000; channels=1", (24000, 2)).unwrap(),
+            (16000, 1)
+        );
+        for mime_type in [
+            "audio/pcm;rate=0",
+            "audio/pcm;rate=fast",
+            "audio/pcm;channels=3",
+        ] {
+            assert!(matches!(
+                pcm_format(mime_type, (16000, 1)),
+                Err(AppError::Input(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn prepares_pcm_in_the_declared_format() {
+        // 16kHz 单声道采集、声明 8kHz 立体声：上传前重采样并复制到两个声道
+        let stt_config = SttConfig {
+            sample_rate: Some(8000),
+            channel: Some(2),
+            vad: vad::VadConfig {
+                enabled: false,
+                ..vad::VadConfig::default()
+            },
+            ..SttConfig::default()
+        };
+        let capture = PcmAudio {
+            sample_rate: 16000,
+            channels: 1,
+            samples: vec![0.25; 1600],
+        }
+        .to_pcm16();
+        let prepared =
+            prepare_audio(capture, "audio/pcm;rate=16000;channels=1", &stt_config).unwrap();
+        assert_eq!(prepared.len(), 800 * 2 * 2);
+    }
+}
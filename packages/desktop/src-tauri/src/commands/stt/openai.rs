@@ -0,0 +1,253 @@
+//! OpenAI / Whisper 兼容的 `/v1/audio/transcriptions` 语音识别，也适用于提供同样接口的自建服务
+
+use super::provider::{audio_file, hotword_prompt, language_code, SttProvider};
+use super::{SttTranscript, SttUtterance, SttWord};
+use crate::commands::config::SttConfig;
+use futures_util::future::BoxFuture;
+use futures_util::FutureExt;
+use reason_desktop_lib::error::AppError;
+use reason_desktop_lib::net::{self, RetryPolicy, VoiceError};
+use reason_desktop_lib::tr;
+use reqwest::multipart::{Form, Part};
+use serde::Deserialize;
+
+/// verbose_json 响应，时间单位为秒；普通 json 响应只有 `text`
+#[derive(Debug, Deserialize)]
+struct TranscriptionResponse {
+    #[serde(default)]
+    text: String,
+    #[serde(default)]
+    duration: Option<f64>,
+    #[serde(default)]
+    segments: Vec<TranscriptionSegment>,
+    #[serde(default)]
+    words: Vec<TranscriptionWord>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TranscriptionSegment {
+    #[serde(default)]
+    text: String,
+    #[serde(default)]
+    start: f64,
+    #[serde(default)]
+    end: f64,
+}
+
+#[derive(Debug, Deserialize)]
+struct TranscriptionWord {
+    #[serde(default)]
+    word: String,
+    #[serde(default)]
+    start: f64,
+    #[serde(default)]
+    end: f64,
+}
+
+fn to_ms(seconds: f64) -> i64 {
+    (seconds.max(0.0) * 1000.0).round() as i64
+}
+
+impl From<TranscriptionResponse> for SttTranscript {
+    fn from(response: TranscriptionResponse) -> Self {
+        let mut words = response.words.into_iter().peekable();
+        let utterances = response
+            .segments
+            .into_iter()
+            .map(|segment| {
+                let (start_time, end_time) = (to_ms(segment.start), to_ms(segment.end));
+                // 字词按时间归入所在分句
+                let mut segment_words = Vec::new();
+                while let Some(word) = words.next_if(|word| to_ms(word.start) < end_time) {
+                    segment_words.push(SttWord {
+                        text: word.word.trim().to_string(),
+                        start_time: to_ms(word.start),
+                        end_time: to_ms(word.end),
+                        blank_duration: 0,
+                    });
+                }
+                SttUtterance {
+                    text: segment.text.trim().to_string(),
+                    start_time,
+                    end_time,
+                    definite: true,
+                    words: segment_words,
+                }
+            })
+            .collect();
+
+        SttTranscript {
+            text: response.text.trim().to_string(),
+            utterances,
+            duration_ms: response.duration.map(|seconds| to_ms(seconds) as u64),
+        }
+    }
+}
+
+pub struct OpenAiStt {
+    config: SttConfig,
+    client: reqwest::Client,
+}
+
+impl OpenAiStt {
+    pub fn new(config: SttConfig) -> Self {
+        Self {
+            config,
+            client: net::http_client(),
+        }
+    }
+
+    async fn request(
+        &self,
+        audio: &[u8],
+        mime_type: &str,
+        hotwords: &[String],
+    ) -> Result<SttTranscript, AppError> {
+        let openai = &self.config.openai;
+        if openai.base_url.trim().is_empty() {
+            return Err(AppError::Config(tr!("stt.service_url_missing")));
+        }
+
+        let (file, ext) = audio_file(audio, mime_type, &self.config)?;
+        let prompt = hotword_prompt(hotwords);
+        Ok(
+            net::retry(RetryPolicy::default(), &tr!("stt.recognize_openai"), || {
+                self.send(&file, &ext, &prompt)
+            })
+            .await?,
+        )
+    }
+
+    /// 发送一次识别请求；multipart 表单只能使用一次，每次重试重新构建
+    async fn send(
+        &self,
+        file: &[u8],
+        ext: &str,
+        prompt: &str,
+    ) -> Result<SttTranscript, VoiceError> {
+        let openai = &self.config.openai;
+        let file = Part::bytes(file.to_vec()).file_name(format!("recording.{}", ext));
+        let mut form = Form::new()
+            .part("file", file)
+            .text("model", openai.model.clone());
+        if openai.timestamps {
+            form = form
+                .text("response_format", "verbose_json")
+                .text("timestamp_granularities[]", "segment")
+                .text("timestamp_granularities[]", "word");
+        } else {
+            form = form.text("response_format", "json");
+        }
+        if let Some(language) = language_code(&self.config) {
+            form = form.text("language", language);
+        }
+        if !prompt.is_empty() {
+            form = form.text("prompt", prompt.to_string());
+        }
+
+        let endpoint = format!(
+            "{}/audio/transcriptions",
+            openai.base_url.trim().trim_end_matches('/')
+        );
+        let mut builder = self.client.post(endpoint).multipart(form);
+        if !openai.api_key.is_empty() {
+            builder = builder.bearer_auth(&openai.api_key);
+        }
+        let response = builder
+            .send()
+            .await
+            .map_err(|e| VoiceError::from_reqwest(&tr!("http.request_failed"), &e))?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let detail = response.text().await.unwrap_or_default();
+            return Err(VoiceError::from_status(
+                &tr!("stt.request_failed"),
+                status.as_u16(),
+                &detail,
+            ));
+        }
+
+        response
+            .json::<TranscriptionResponse>()
+            .await
+            .map(SttTranscript::from)
+            .map_err(|e| VoiceError::from_reqwest(&tr!("http.parse_failed"), &e))
+    }
+}
+
+impl SttProvider for OpenAiStt {
+    fn transcribe<'a>(
+        &'a self,
+        audio: &'a [u8],
+        mime_type: &'a str,
+        hotwords: &'a [String],
+    ) -> BoxFuture<'a, Result<SttTranscript, AppError>> {
+        self.request(audio, mime_type, hotwords).boxed()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn transcript(response: serde_json::Value) -> SttTranscript {
+        serde_json::from_value::<TranscriptionResponse>(response)
+            .unwrap()
+            .into()
+    }
+
+    #[test]
+    fn buckets_words_into_segments() {
+        let transcript = transcript(json!({
+            "text": " Hello world. Bye. ",
+            "duration": 3.2,
+            "segments": [
+                { "text": " Hello world.", "start": 0.0, "end": 1.5 },
+                { "text": " Bye.", "start": 1.5, "end": 3.0 }
+            ],
+            "words": [
+                { "word": " Hello", "start": 0.0, "end": 0.6 },
+                { "word": " world.", "start": 0.7, "end": 1.4 },
+                { "word": " Bye.", "start": 1.6, "end": 2.9 },
+                // 晚于最后一个分句结束的字词被丢弃
+                { "word": " tail", "start": 3.05, "end": 3.1 }
+            ]
+        }));
+
+        assert_eq!(transcript.text, "Hello world. Bye.");
+        assert_eq!(transcript.duration_ms, Some(3200));
+        let utterances: Vec<_> = transcript
+            .utterances
+            .iter()
+            .map(|utterance| {
+                let words: Vec<_> = utterance
+                    .words
+                    .iter()
+                    .map(|word| (word.text.as_str(), word.start_time, word.end_time))
+                    .collect();
+                (utterance.text.as_str(), utterance.end_time, words)
+            })
+            .collect();
+        assert_eq!(
+            utterances,
+            [
+                (
+                    "Hello world.",
+                    1500,
+                    vec![("Hello", 0, 600), ("world.", 700, 1400)]
+                ),
+                ("Bye.", 3000, vec![("Bye.", 1600, 2900)])
+            ]
+        );
+    }
+
+    #[test]
+    fn plain_json_response_has_no_utterances() {
+        let transcript = transcript(json!({ "text": "你好" }));
+        assert_eq!(transcript.text, "你好");
+        assert!(transcript.utterances.is_empty());
+        assert_eq!(transcript.duration_ms, None);
+    }
+}
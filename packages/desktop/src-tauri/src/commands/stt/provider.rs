@@ -0,0 +1,65 @@
+//! 语音识别提供方：火山引擎、OpenAI / Whisper 兼容接口与本地命令
+
+use super::command::CommandStt;
+use super::openai::OpenAiStt;
+use super::{declared_format, parse_audio_format, SttTranscript, VolcengineStt};
+use crate::commands::config::{SttConfig, SttProviderKind, VolcengineConfig};
+use futures_util::future::BoxFuture;
+use reason_desktop_lib::audio::wav_header;
+use reason_desktop_lib::error::AppError;
+
+/// 热词提示的最大词数，Whisper 的提示词只取前 224 个 token
+const PROMPT_MAX_WORDS: usize = 50;
+
+pub trait SttProvider: Send + Sync {
+    /// 识别一段完整录音；`hotwords` 为用户词表与项目标识符，提供方可用作识别提示
+    fn transcribe<'a>(
+        &'a self,
+        audio: &'a [u8],
+        mime_type: &'a str,
+        hotwords: &'a [String],
+    ) -> BoxFuture<'a, Result<SttTranscript, AppError>>;
+}
+
+/// 按配置中的 `stt.provider` 选择提供方
+pub fn stt_provider(config: &VolcengineConfig) -> Box<dyn SttProvider> {
+    match config.stt.provider {
+        SttProviderKind::Volcengine => Box::new(VolcengineStt::new(config.clone())),
+        SttProviderKind::Openai => Box::new(OpenAiStt::new(config.stt.clone())),
+        SttProviderKind::Command => Box::new(CommandStt::new(config.stt.clone())),
+    }
+}
+
+/// 按录音格式返回上传用的文件内容与扩展名；裸 PCM 补上 WAV 文件头
+pub fn audio_file(
+    audio: &[u8],
+    mime_type: &str,
+    stt_config: &SttConfig,
+) -> Result<(Vec<u8>, String), AppError> {
+    let (format, _) = parse_audio_format(mime_type)?;
+    if format != "pcm" {
+        return Ok((audio.to_vec(), format));
+    }
+
+    let (rate, channels) = declared_format(stt_config)?;
+    let mut wav = wav_header(rate, channels, Some(audio.len() as u32));
+    wav.extend_from_slice(audio);
+    Ok((wav, "wav".to_string()))
+}
+
+/// ISO-639-1 语种代码，如 zh-CN → zh
+pub fn language_code(stt_config: &SttConfig) -> Option<String> {
+    let language = stt_config.language.as_deref()?.trim();
+    let code = language.split(['-', '_']).next().unwrap_or_default();
+    (!code.is_empty()).then(|| code.to_lowercase())
+}
+
+/// 把热词拼成识别提示
+pub fn hotword_prompt(hotwords: &[String]) -> String {
+    hotwords
+        .iter()
+        .take(PROMPT_MAX_WORDS)
+        .map(String::as_str)
+        .collect::<Vec<_>>()
+        .join(", ")
+}
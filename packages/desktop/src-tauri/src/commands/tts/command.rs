@@ -0,0 +1,125 @@
+//! 本地命令语音合成，如 piper、espeak-ng；命令把 WAV 写到标准输出
+
+use super::provider::{SpeechRequest, TtsProvider};
+use crate::commands::config::{CommandTtsConfig, TtsAudioParams};
+use futures_util::future::BoxFuture;
+use futures_util::FutureExt;
+use reason_desktop_lib::audio::parse_wav;
+use reason_desktop_lib::error::AppError;
+use reason_desktop_lib::tr;
+use std::process::Stdio;
+use tokio::io::AsyncWriteExt;
+use tokio::process::Command;
+use tokio::time::{timeout, Duration};
+
+pub struct CommandTts {
+    config: CommandTtsConfig,
+}
+
+impl CommandTts {
+    pub fn new(config: CommandTtsConfig) -> Self {
+        Self { config }
+    }
+
+    /// 替换参数中的占位符，返回参数与是否需要从标准输入传入文本
+    fn args(&self, request: &SpeechRequest) -> (Vec<String>, bool) {
+        let text_in_args = self.config.args.iter().any(|arg| arg.contains("{text}"));
+        let args = self
+            .config
+            .args
+            .iter()
+            .map(|arg| {
+                arg.replace("{text}", &request.text)
+                    .replace("{voice}", &request.voice)
+                    .replace("{speed}", &request.params.speed_ratio.to_string())
+            })
+            .collect();
+        (args, !text_in_args)
+    }
+
+    async fn run(&self, request: &SpeechRequest) -> Result<Vec<u8>, AppError> {
+        let program = self.config.program.trim();
+        if program.is_empty() {
+            return Err(AppError::Config(tr!("tts.command_missing")));
+        }
+        let (args, text_on_stdin) = self.args(request);
+
+        // 取消或超时时丢弃子进程即结束它
+        let mut child = Command::new(program)
+            .args(&args)
+            .stdin(if text_on_stdin {
+                Stdio::piped()
+            } else {
+                Stdio::null()
+            })
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()
+            .map_err(|e| AppError::process(tr!("tts.command_start_failed"), e.to_string()))?;
+
+        if let Some(mut stdin) = child.stdin.take() {
+            let text = request.text.clone();
+            // 与读取输出并行写入，避免双方都等待管道而卡住
+            tokio::spawn(async move {
+                let _ = stdin.write_all(text.as_bytes()).await;
+                let _ = stdin.write_all(b"\n").await;
+            });
+        }
+
+        let secs = self.config.timeout_secs;
+        let output = timeout(Duration::from_secs(secs), child.wait_with_output())
+            .await
+            .map_err(|_| AppError::process(tr!("tts.command_timeout", secs = secs), ""))?
+            .map_err(|e| AppError::process(tr!("tts.command_wait_failed"), e.to_string()))?;
+
+        if !output.status.success() {
+            return Err(AppError::process(
+                tr!("tts.command_failed", status = output.status),
+                String::from_utf8_lossy(&output.stderr),
+            ));
+        }
+
+        let (format, _) = parse_wav(&output.stdout)
+            .map_err(|e| AppError::process(tr!("tts.command_invalid_output"), e))?;
+        if format.channels != 1 || format.bits_per_sample != 16 {
+            return Err(AppError::Config(tr!("tts.command_output_format")));
+        }
+        if format.sample_rate != self.config.sample_rate {
+            return Err(AppError::Config(tr!(
+                "tts.command_sample_rate_mismatch",
+                actual = format.sample_rate,
+                expected = self.config.sample_rate
+            )));
+        }
+        Ok(output.stdout)
+    }
+}
+
+impl TtsProvider for CommandTts {
+    fn default_voice(&self) -> &str {
+        &self.config.voice
+    }
+
+    /// 固定输出 wav，采样率取配置值
+    fn output_params(&self, mut params: TtsAudioParams) -> Result<TtsAudioParams, AppError> {
+        params.format = "wav".to_string();
+        params.sample_rate = self.config.sample_rate;
+        Ok(params)
+    }
+
+    fn cache_scope(&self, _streaming: bool) -> String {
+        format!(
+            "command:{} {}",
+            self.config.program,
+            self.config.args.join(" ")
+        )
+    }
+
+    fn synthesize<'a>(
+        &'a self,
+        request: &'a SpeechRequest,
+    ) -> BoxFuture<'a, Result<Vec<u8>, AppError>> {
+        self.run(request).boxed()
+    }
+}
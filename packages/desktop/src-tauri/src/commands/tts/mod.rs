@@ -0,0 +1,1678 @@
+mod command;
+mod openai;
+mod provider;
+
+use crate::commands::config::{
+    get_tts_cache_dir, get_volcengine_config, TtsAudioOverrides, TtsAudioParams, TtsProviderKind,
+    VolcengineConfig,
+};
+use crate::commands::voice_metrics;
+use base64::{engine::general_purpose::STANDARD, Engine};
+use futures_util::future::BoxFuture;
+use futures_util::stream::{SplitSink, SplitStream, Stream};
+use futures_util::{FutureExt, SinkExt, StreamExt};
+use reason_desktop_lib::audio::{parse_wav, wav_header, WavFormat};
+use reason_desktop_lib::error::AppError;
+use reason_desktop_lib::metrics::{Phase, Stage};
+use reason_desktop_lib::net::{self, RetryPolicy, VoiceError};
+use reason_desktop_lib::segmenter::{self, Segmenter};
+use reason_desktop_lib::speech_text::{self, SpeechNormalizer};
+use reason_desktop_lib::subtitles::{self, AudioEncoding, SubtitleCue, SubtitleTrack};
+use reason_desktop_lib::tr;
+use reason_desktop_lib::tts_cache::{CachedSpeech, TtsCache, TtsCacheKey, TtsCacheStats};
+use reason_desktop_lib::volcengine;
+use reason_desktop_lib::volcengine::protocol::{
+    Compression, Event, Frame, MessageType, Serialization,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::sync::atomic::{AtomicU64, Ordering};
+use tauri::{AppHandle, Emitter, Manager, State, WebviewWindow};
+use tokio::net::TcpStream;
+use tokio::sync::{mpsc, oneshot, Mutex};
+use tokio::task::JoinHandle;
+use tokio::time::{sleep, timeout, Duration, Instant};
+use tokio_tungstenite::connect_async;
+use tokio_tungstenite::tungstenite::client::IntoClientRequest;
+use tokio_tungstenite::tungstenite::error::Error as WsError;
+use tokio_tungstenite::tungstenite::http::header::HeaderValue;
+use tokio_tungstenite::tungstenite::Message;
+use tokio_tungstenite::{MaybeTlsStream, WebSocketStream};
+use tracing::{debug, info, warn, Instrument};
+use uuid::Uuid;
+
+use provider::{tts_provider, SpeechRequest, TtsProvider};
+
+const TTS_HTTP_ENDPOINT: &str = "https://openspeech.bytedance.com/api/v1/tts";
+const TTS_WS_ENDPOINT: &str = "wss://openspeech.bytedance.com/api/v3/tts/bidirection";
+
+const TTS_STREAM_CHUNK_DELAY_MS: u64 = 80;
+/// 关闭增量会话后等待剩余音频合成完毕的时间
+const TTS_SESSION_CLOSE_TIMEOUT_SECS: u64 = 60;
+/// 打断上一路增量合成后等待其收尾的时间，超时直接中止任务
+const TTS_SESSION_CANCEL_TIMEOUT_SECS: u64 = 2;
+/// 取消后断开连接（FinishSession / FinishConnection / close）的期限
+const TTS_CANCEL_TEARDOWN_SECS: u64 = 5;
+/// 空闲连接的最长保留时间，超过后重新建连（服务端会断开长时间空闲的连接）
+const TTS_CONNECTION_IDLE_SECS: u64 = 50;
+/// 重放缓存音频时每个分块的大小
+const TTS_REPLAY_CHUNK_BYTES: usize = 8 * 1024;
+
+type TtsSocket = WebSocketStream<MaybeTlsStream<TcpStream>>;
+type TtsWriter = SplitSink<TtsSocket, Message>;
+type TtsReader = SplitStream<TtsSocket>;
+
+#[derive(Clone, Serialize)]
+pub struct TtsStreamChunkPayload {
+    pub chunk: Vec<u8>,
+}
+
+#[derive(Clone, Serialize)]
+pub struct TtsStreamFinishedPayload {
+    #[serde(rename = "totalBytes")]
+    pub total_bytes: usize,
+}
+
+/// 流式合成错误事件，与命令返回的错误结构相同
+pub type TtsStreamErrorPayload = AppError;
+
+/// 首个音频分块的延迟（自发起合成起算）
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TtsFirstAudioPayload {
+    pub latency_ms: u64,
+    pub reused_connection: bool,
+}
+
+#[derive(Clone, Serialize)]
+pub struct TtsStreamCancelledPayload {
+    #[serde(rename = "totalBytes")]
+    pub total_bytes: usize,
+}
+
+/// 句子开始 / 结束事件，`phase` 为 "start" 或 "end"
+#[derive(Clone, Serialize)]
+pub struct TtsSentencePayload {
+    pub phase: &'static str,
+    #[serde(flatten)]
+    pub cue: SubtitleCue,
+}
+
+/// 最近一次流式合成的字幕，供导出使用
+#[derive(Default)]
+pub struct TtsSubtitleState {
+    last: Mutex<Vec<SubtitleCue>>,
+}
+
+/// 进行中的增量合成会话
+struct TtsSessionHandle {
+    text_tx: mpsc::UnboundedSender<String>,
+    task: JoinHandle<Result<usize, AppError>>,
+}
+
+#[derive(Default)]
+pub struct TtsSessionState {
+    active: Mutex<Option<TtsSessionHandle>>,
+}
+
+/// 当前播报的取消信号；`tts_cancel`、开始录音或开始新的播报都会打断它
+#[derive(Default)]
+pub struct TtsSpeakingState {
+    current: std::sync::Mutex<Option<(u64, oneshot::Sender<()>)>>,
+    next_id: AtomicU64,
+}
+
+impl TtsSpeakingState {
+    /// 登记一次新播报并打断正在进行的播报，返回播报 id 与取消信号
+    fn begin(&self) -> (u64, oneshot::Receiver<()>) {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let (cancel_tx, cancel_rx) = oneshot::channel();
+        if let Some((_, previous)) = self.lock().replace((id, cancel_tx)) {
+            let _ = previous.send(());
+        }
+        (id, cancel_rx)
+    }
+
+    /// 播报结束，只清除自己登记的那一次
+    fn end(&self, id: u64) {
+        let mut current = self.lock();
+        if current
+            .as_ref()
+            .is_some_and(|(current_id, _)| *current_id == id)
+        {
+            *current = None;
+        }
+    }
+
+    /// 打断正在进行的播报，返回是否确有播报被打断
+    pub fn cancel(&self) -> bool {
+        match self.lock().take() {
+            Some((_, cancel_tx)) => cancel_tx.send(()).is_ok(),
+            None => false,
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Option<(u64, oneshot::Sender<()>)>> {
+        self.current.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// 一次播报的结束方式
+#[derive(Clone, Copy)]
+enum SpeechEnd {
+    Finished,
+    Cancelled,
+}
+
+/// TTS 请求
+#[derive(Debug, Serialize)]
+struct TtsRequest {
+    app: TtsAppConfig,
+    user: TtsUserConfig,
+    audio: TtsAudioConfig,
+    request: TtsRequestConfig,
+}
+
+#[derive(Debug, Serialize)]
+struct TtsAppConfig {
+    appid: String,
+    token: String,
+    cluster: String,
+}
+
+#[derive(Debug, Serialize)]
+struct TtsUserConfig {
+    uid: String,
+}
+
+#[derive(Debug, Serialize)]
+struct TtsAudioConfig {
+    voice_type: String,
+    encoding: String,
+    rate: u32,
+    speed_ratio: f32,
+    volume_ratio: f32,
+    pitch_ratio: f32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    emotion: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    enable_emotion: Option<bool>,
+}
+
+#[derive(Debug, Serialize)]
+struct TtsRequestConfig {
+    reqid: String,
+    text: String,
+    operation: String,
+}
+
+/// TTS 响应
+#[derive(Debug, Deserialize)]
+struct TtsResponse {
+    #[serde(default)]
+    code: i32,
+    #[serde(default)]
+    message: String,
+    #[serde(default)]
+    data: Option<String>, // Base64 编码的音频数据
+}
+
+fn encode_event(
+    event: Event,
+    session_id: Option<&str>,
+    payload: &[u8],
+    compression: Compression,
+) -> Result<Vec<u8>, VoiceError> {
+    Frame::client_event(event, session_id, payload.to_vec())
+        .compress(compression)
+        .and_then(|frame| frame.encode())
+        .map_err(|e| VoiceError::protocol(tr!("protocol.build_frame_failed", error = e)))
+}
+
+fn decode_frame(data: &[u8]) -> Result<Frame, VoiceError> {
+    Frame::decode(data)
+        .and_then(Frame::decompress)
+        .map_err(|e| VoiceError::protocol(tr!("protocol.parse_frame_failed", error = e)))
+}
+
+/// 等待指定事件，每条消息的等待不超过读取期限
+async fn wait_for_event<S>(ws_read: &mut S, target_event: Event) -> Result<(), VoiceError>
+where
+    S: Stream<Item = Result<Message, WsError>> + Unpin,
+{
+    loop {
+        let message = net::with_timeout(net::READ_TIMEOUT, &tr!("tts.wait_response"), async {
+            match ws_read.next().await {
+                Some(message) => {
+                    message.map_err(|e| VoiceError::from_ws(&tr!("ws.receive_failed"), &e))
+                }
+                None => Err(VoiceError::network(tr!("tts.connection_ended"))),
+            }
+        })
+        .await?;
+        match message {
+            Message::Binary(data) => {
+                let frame = decode_frame(&data)?;
+                if frame.is_error() {
+                    return Err(volcengine::frame_error(&tr!("tts.service_error"), &frame));
+                }
+                if frame.event == Some(target_event) {
+                    return Ok(());
+                }
+            }
+            Message::Text(text) => {
+                return Err(VoiceError::protocol(tr!("tts.text_message", text = text)));
+            }
+            Message::Close(_) => {
+                return Err(VoiceError::network(tr!("tts.connection_closed")));
+            }
+            _ => {}
+        }
+    }
+}
+
+/// 语音合成 - 整段返回音频，按配置选择提供方
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(text_len = text.chars().count()))]
+pub async fn tts_speak(
+    app: tauri::AppHandle,
+    text: String,
+    voice_type: Option<String>,
+    audio: Option<TtsAudioOverrides>,
+) -> Result<Vec<u8>, AppError> {
+    let started = Instant::now();
+    voice_metrics::begin(&app, Phase::Tts);
+
+    // 获取配置
+    let volcengine_config = get_volcengine_config().await?;
+    let provider = tts_provider(&app, &volcengine_config);
+
+    let tts_config = &volcengine_config.tts;
+    let params = provider.output_params(tts_config.audio.resolve(tts_config.provider, audio)?)?;
+    let text = speech_text::normalize(&text, volcengine_config.tts.normalize);
+    if text.trim().is_empty() {
+        return Err(AppError::Input(tr!("tts.empty_text")));
+    }
+    let request = SpeechRequest {
+        voice: voice_type.unwrap_or_else(|| provider.default_voice().to_string()),
+        text,
+        params,
+    };
+
+    // 整段音频由前端播放，后端无从打断；登记本次播报只为打断正在进行的播报，
+    // 不保留取消信号，期间 `tts_cancel` 如实返回没有可打断的播报
+    let speaking = app.state::<TtsSpeakingState>();
+    let (speech_id, _) = speaking.begin();
+
+    let cache = SpeechCache::new(
+        &volcengine_config,
+        TtsCacheKey {
+            text: &request.text,
+            voice_type: &request.voice,
+            audio_params: &json!(request.params),
+            resource_id: &provider.cache_scope(false),
+        },
+    );
+    if let Some(cache) = &cache {
+        if let Some(speech) = cache.lookup().await {
+            debug!(bytes = speech.audio.len(), "tts cache hit");
+            speaking.end(speech_id);
+            finish_whole(&app, started);
+            return Ok(speech.audio);
+        }
+    }
+
+    let result = provider.synthesize(&request).await;
+    speaking.end(speech_id);
+    let audio_bytes = result.inspect_err(|_| voice_metrics::finish(&app))?;
+    finish_whole(&app, started);
+
+    if let Some(cache) = cache {
+        cache.store(CachedSpeech {
+            audio: audio_bytes.clone(),
+            cues: Vec::new(),
+        });
+    }
+
+    Ok(audio_bytes)
+}
+
+/// 整段合成没有流式过程，首包与结束耗时相同
+fn finish_whole(app: &AppHandle, started: Instant) {
+    let elapsed = started.elapsed();
+    voice_metrics::record(app, Stage::TtsFirstAudio, elapsed);
+    voice_metrics::record(app, Stage::TtsFinish, elapsed);
+    voice_metrics::finish(app);
+}
+
+/// 火山引擎语音合成：整段合成走 HTTP 接口，流式合成走双向 WebSocket 并复用连接池
+struct VolcengineTts {
+    app: AppHandle,
+    config: VolcengineConfig,
+}
+
+impl VolcengineTts {
+    fn new(app: &AppHandle, config: VolcengineConfig) -> Self {
+        Self {
+            app: app.clone(),
+            config,
+        }
+    }
+
+    async fn synthesize_http(&self, request: &SpeechRequest) -> Result<Vec<u8>, AppError> {
+        let volcengine_config = &self.config;
+        if volcengine_config.app_id.is_empty() || volcengine_config.access_token.is_empty() {
+            return Err(AppError::Config(tr!("config.volcengine_missing")));
+        }
+        let params = &request.params;
+
+        // 构建请求
+        let request = TtsRequest {
+            app: TtsAppConfig {
+                appid: volcengine_config.app_id.clone(),
+                token: volcengine_config.access_token.clone(),
+                cluster: volcengine_config.tts.cluster.clone(),
+            },
+            user: TtsUserConfig {
+                uid: "reason-desktop".to_string(),
+            },
+            audio: TtsAudioConfig {
+                voice_type: request.voice.clone(),
+                encoding: params.format.clone(),
+                rate: params.sample_rate,
+                speed_ratio: params.speed_ratio,
+                volume_ratio: params.volume_ratio,
+                pitch_ratio: params.pitch_ratio,
+                enable_emotion: params.emotion.as_ref().map(|_| true),
+                emotion: params.emotion.clone(),
+            },
+            request: TtsRequestConfig {
+                reqid: Uuid::new_v4().to_string(),
+                text: request.text.clone(),
+                operation: "query".to_string(),
+            },
+        };
+
+        // 发送 HTTP 请求，网络错误与服务端繁忙时重试
+        Ok(
+            net::retry(RetryPolicy::default(), &tr!("tts.http_request"), || {
+                self.send_http(&request)
+            })
+            .await?,
+        )
+    }
+
+    async fn send_http(&self, request: &TtsRequest) -> Result<Vec<u8>, VoiceError> {
+        let response = net::http_client()
+            .post(volcengine::endpoint(TTS_HTTP_ENDPOINT))
+            .header(
+                "Authorization",
+                format!("Bearer;{}", self.config.access_token),
+            )
+            .header("Content-Type", "application/json")
+            .json(request)
+            .send()
+            .await
+            .map_err(|e| VoiceError::from_reqwest(&tr!("http.request_failed"), &e))?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let detail = response.text().await.unwrap_or_default();
+            return Err(VoiceError::from_status(
+                &tr!("tts.request_failed"),
+                status.as_u16(),
+                &detail,
+            ));
+        }
+
+        let tts_response: TtsResponse = response
+            .json()
+            .await
+            .map_err(|e| VoiceError::from_reqwest(&tr!("http.parse_failed"), &e))?;
+
+        if tts_response.code != 0 && tts_response.code != 3000 {
+            return Err(VoiceError::from_service(
+                &tr!("tts.failed"),
+                Some(tts_response.code as i64),
+                &tts_response.message,
+            ));
+        }
+
+        let audio_base64 = tts_response
+            .data
+            .ok_or_else(|| VoiceError::protocol(tr!("tts.no_audio")))?;
+
+        // 解码 Base64
+        STANDARD
+            .decode(&audio_base64)
+            .map_err(|e| VoiceError::protocol(tr!("tts.decode_failed", error = e)))
+    }
+
+    async fn stream_ws(
+        &self,
+        request: &SpeechRequest,
+        chunks: Vec<String>,
+        output: &mut TtsOutput,
+        cancel_rx: &mut oneshot::Receiver<()>,
+    ) -> Result<SpeechEnd, AppError> {
+        let volcengine_config = &self.config;
+        debug!(
+            app_id_set = !volcengine_config.app_id.is_empty(),
+            access_token_set = !volcengine_config.access_token.is_empty(),
+            "tts credentials"
+        );
+        let resource_id = resolve_resource_id(volcengine_config)?;
+
+        // 尚未下发任何音频或字幕时连接中断，换一条连接重新合成
+        let policy = RetryPolicy::default();
+        let mut attempt = 1;
+        loop {
+            let error = match self
+                .stream_once(&resource_id, request, chunks.clone(), output, cancel_rx)
+                .await
+            {
+                Err(error)
+                    if error.retryable && !output.has_output() && attempt < policy.attempts =>
+                {
+                    error
+                }
+                result => return Ok(result?),
+            };
+            let delay = policy.delay(attempt - 1);
+            warn!(
+                attempt,
+                delay_ms = delay.as_millis() as u64,
+                error = %error.message,
+                "tts stream failed before first audio, retrying"
+            );
+            tokio::select! {
+                _ = &mut *cancel_rx => return Ok(SpeechEnd::Cancelled),
+                _ = sleep(delay) => {}
+            }
+            attempt += 1;
+        }
+    }
+
+    async fn stream_once(
+        &self,
+        resource_id: &str,
+        request: &SpeechRequest,
+        chunks: Vec<String>,
+        output: &mut TtsOutput,
+        cancel_rx: &mut oneshot::Receiver<()>,
+    ) -> Result<SpeechEnd, VoiceError> {
+        let connection = open_session(
+            &self.app,
+            &self.config,
+            resource_id,
+            &request.voice,
+            &request.params,
+        )
+        .await?;
+        output.reused_connection = connection.link.reused;
+
+        let (connection, end) = run_stream(connection, output, chunks, cancel_rx).await?;
+        end_connection(&self.app, connection, end).await;
+        Ok(end)
+    }
+}
+
+impl TtsProvider for VolcengineTts {
+    fn default_voice(&self) -> &str {
+        &self.config.tts.voice_type
+    }
+
+    fn cache_scope(&self, streaming: bool) -> String {
+        if streaming {
+            resolve_resource_id(&self.config).unwrap_or_default()
+        } else {
+            self.config.tts.cluster.clone()
+        }
+    }
+
+    fn synthesize<'a>(
+        &'a self,
+        request: &'a SpeechRequest,
+    ) -> BoxFuture<'a, Result<Vec<u8>, AppError>> {
+        self.synthesize_http(request).boxed()
+    }
+
+    fn stream<'a>(
+        &'a self,
+        request: &'a SpeechRequest,
+        chunks: Vec<String>,
+        output: &'a mut TtsOutput,
+        cancel_rx: &'a mut oneshot::Receiver<()>,
+    ) -> BoxFuture<'a, Result<SpeechEnd, AppError>> {
+        self.stream_ws(request, chunks, output, cancel_rx).boxed()
+    }
+}
+
+/// 某次合成在磁盘缓存中的位置
+struct SpeechCache {
+    cache: TtsCache,
+    key: String,
+}
+
+impl SpeechCache {
+    /// 按配置定位缓存条目，关闭缓存时返回 None
+    fn new(volcengine_config: &VolcengineConfig, key: TtsCacheKey<'_>) -> Option<Self> {
+        let config = &volcengine_config.tts.cache;
+        if !config.enabled {
+            return None;
+        }
+        Some(Self {
+            cache: TtsCache::new(
+                get_tts_cache_dir(),
+                config.max_size_mb * 1024 * 1024,
+                Duration::from_secs(config.max_age_days * 24 * 3600),
+            ),
+            key: key.digest(),
+        })
+    }
+
+    async fn lookup(&self) -> Option<CachedSpeech> {
+        let cache = self.cache.clone();
+        let key = self.key.clone();
+        tokio::task::spawn_blocking(move || cache.get(&key))
+            .await
+            .ok()
+            .flatten()
+    }
+
+    /// 后台写入缓存，失败只记录日志
+    fn store(self, speech: CachedSpeech) {
+        tokio::task::spawn_blocking(move || {
+            if let Err(e) = self.cache.put(&self.key, &speech) {
+                warn!(error = %e, "tts cache write failed");
+            }
+        });
+    }
+}
+
+/// 校验鉴权配置，返回语音合成资源 ID
+fn resolve_resource_id(volcengine_config: &VolcengineConfig) -> Result<String, AppError> {
+    if volcengine_config.app_id.is_empty() || volcengine_config.access_token.is_empty() {
+        return Err(AppError::Config(tr!("config.volcengine_missing")));
+    }
+    let resource_id = if !volcengine_config.tts.resource_id.is_empty() {
+        volcengine_config.tts.resource_id.clone()
+    } else {
+        volcengine_config
+            .legacy_resource_id
+            .clone()
+            .unwrap_or_default()
+    };
+    if resource_id.is_empty() {
+        return Err(AppError::Config(tr!("tts.resource_id_missing")));
+    }
+    Ok(resource_id)
+}
+
+/// 已完成 StartConnection 的双向 TTS 连接，可依次承载多个会话
+struct TtsLink {
+    write: TtsWriter,
+    read: TtsReader,
+    compression: Compression,
+    /// 鉴权配置标识，配置变更后不再复用
+    key: String,
+    /// 服务端已关闭或读取出错，不能放回连接池
+    closed: bool,
+    /// 是否取自连接池
+    reused: bool,
+}
+
+fn connection_key(volcengine_config: &VolcengineConfig, resource_id: &str) -> String {
+    format!(
+        "{}|{}|{}|{:?}",
+        volcengine_config.app_id,
+        volcengine_config.access_token,
+        resource_id,
+        volcengine_config.payload_compression()
+    )
+}
+
+/// 建立 WebSocket 连接，网络错误时退避重试
+async fn connect_link(
+    volcengine_config: &VolcengineConfig,
+    resource_id: &str,
+) -> Result<TtsLink, VoiceError> {
+    net::retry(RetryPolicy::default(), &tr!("tts.connect"), || {
+        connect_link_once(volcengine_config, resource_id)
+    })
+    .await
+}
+
+/// 建立一次 WebSocket 连接（StartConnection → ConnectionStarted）
+async fn connect_link_once(
+    volcengine_config: &VolcengineConfig,
+    resource_id: &str,
+) -> Result<TtsLink, VoiceError> {
+    let connect_id = Uuid::new_v4().to_string();
+    let compression = volcengine_config.payload_compression();
+
+    info!(resource_id, "tts connect");
+
+    let mut ws_request = volcengine::endpoint(TTS_WS_ENDPOINT)
+        .into_client_request()
+        .map_err(|e| VoiceError::input(tr!("ws.build_request_failed", error = e)))?;
+    ws_request.headers_mut().insert(
+        "X-Api-App-Key",
+        HeaderValue::from_str(&volcengine_config.app_id).map_err(|e| {
+            VoiceError::input(tr!(
+                "ws.invalid_header",
+                header = "X-Api-App-Key",
+                error = e
+            ))
+        })?,
+    );
+    ws_request.headers_mut().insert(
+        "X-Api-Access-Key",
+        HeaderValue::from_str(&volcengine_config.access_token).map_err(|e| {
+            VoiceError::input(tr!(
+                "ws.invalid_header",
+                header = "X-Api-Access-Key",
+                error = e
+            ))
+        })?,
+    );
+    ws_request.headers_mut().insert(
+        "X-Api-Resource-Id",
+        HeaderValue::from_str(resource_id).map_err(|e| {
+            VoiceError::input(tr!(
+                "ws.invalid_header",
+                header = "X-Api-Resource-Id",
+                error = e
+            ))
+        })?,
+    );
+    ws_request.headers_mut().insert(
+        "X-Api-Connect-Id",
+        HeaderValue::from_str(&connect_id).map_err(|e| {
+            VoiceError::input(tr!(
+                "ws.invalid_header",
+                header = "X-Api-Connect-Id",
+                error = e
+            ))
+        })?,
+    );
+
+    let (ws_stream, _) = net::with_timeout(net::CONNECT_TIMEOUT, &tr!("ws.connect"), async {
+        connect_async(ws_request)
+            .await
+            .map_err(|e| VoiceError::from_ws(&tr!("ws.connect_failed"), &e))
+    })
+    .await?;
+    let (mut write, mut read) = ws_stream.split();
+
+    let start_conn = encode_event(Event::StartConnection, None, b"{}", compression)?;
+    write
+        .send(Message::Binary(start_conn))
+        .await
+        .map_err(|e| VoiceError::from_ws(&tr!("tts.send_connect_failed"), &e))?;
+    wait_for_event(&mut read, Event::ConnectionStarted).await?;
+
+    Ok(TtsLink {
+        write,
+        read,
+        compression,
+        key: connection_key(volcengine_config, resource_id),
+        closed: false,
+        reused: false,
+    })
+}
+
+impl TtsLink {
+    /// 开启一次会话（StartSession → SessionStarted）
+    async fn start_session(
+        mut self,
+        voice: &str,
+        params: &TtsAudioParams,
+    ) -> Result<TtsConnection, VoiceError> {
+        let session_id = Uuid::new_v4().to_string();
+
+        info!(
+            voice_type = voice,
+            reused = self.reused,
+            "tts start session"
+        );
+
+        let session_payload = json!({
+            "user": { "uid": "reason-desktop" },
+            "event": Event::StartSession.code(),
+            "namespace": "BidirectionalTTS",
+            "req_params": session_req_params(voice, params)
+        });
+        let session_payload_bytes = serde_json::to_vec(&session_payload)
+            .map_err(|e| VoiceError::protocol(tr!("protocol.serialize_failed", error = e)))?;
+        let start_session = encode_event(
+            Event::StartSession,
+            Some(&session_id),
+            &session_payload_bytes,
+            self.compression,
+        )?;
+        self.write
+            .send(Message::Binary(start_session))
+            .await
+            .map_err(|e| VoiceError::from_ws(&tr!("tts.send_session_failed"), &e))?;
+        wait_for_event(&mut self.read, Event::SessionStarted).await?;
+
+        Ok(TtsConnection {
+            link: self,
+            session_id,
+        })
+    }
+
+    /// 读取下一条消息，连接已结束时返回 None；连接关闭、出错或超时时做标记
+    ///
+    /// `deadline` 为 None 时不限等待时间，用于文本尚未发完、服务端可能长时间无消息的会话。
+    async fn next_message(
+        &mut self,
+        deadline: Option<Duration>,
+    ) -> Result<Option<Message>, VoiceError> {
+        let message = match deadline {
+            Some(deadline) => match timeout(deadline, self.read.next()).await {
+                Ok(message) => message,
+                Err(_) => {
+                    self.closed = true;
+                    return Err(VoiceError::network(tr!(
+                        "tts.audio_timeout",
+                        secs = deadline.as_secs()
+                    )));
+                }
+            },
+            None => self.read.next().await,
+        };
+        if matches!(message, None | Some(Err(_)) | Some(Ok(Message::Close(_)))) {
+            self.closed = true;
+        }
+        message
+            .transpose()
+            .map_err(|e| VoiceError::from_ws(&tr!("ws.receive_failed"), &e))
+    }
+
+    /// 空闲期间是否仍可用：不阻塞地检查服务端是否已关闭连接
+    fn is_alive(&mut self) -> bool {
+        while !self.closed {
+            match self.read.next().now_or_never() {
+                None => return true,
+                Some(Some(Ok(Message::Ping(_) | Message::Pong(_)))) => {}
+                Some(_) => self.closed = true,
+            }
+        }
+        false
+    }
+
+    /// 结束连接，失败时忽略
+    async fn close(mut self) {
+        if self.closed {
+            return;
+        }
+        if let Ok(finish_conn) =
+            encode_event(Event::FinishConnection, None, b"{}", self.compression)
+        {
+            let _ = self.write.send(Message::Binary(finish_conn)).await;
+            let _ = wait_for_event(&mut self.read, Event::ConnectionFinished).await;
+        }
+    }
+}
+
+/// 倍率换算为双向接口的百分比调节值（0 为原速，-50 为半速，100 为两倍）
+fn ratio_to_rate(ratio: f32) -> i32 {
+    ((ratio - 1.0) * 100.0).round().clamp(-50.0, 100.0) as i32
+}
+
+/// 倍率换算为半音数（0.5 为降八度，2.0 为升八度）
+fn ratio_to_semitones(ratio: f32) -> i32 {
+    (12.0 * ratio.log2()).round().clamp(-12.0, 12.0) as i32
+}
+
+/// 双向合成会话的请求参数，与 HTTP 接口的倍率参数效果一致；
+/// 双向接口不直接输出 wav，改为请求 pcm 并在下发时补上文件头
+fn session_req_params(voice: &str, params: &TtsAudioParams) -> serde_json::Value {
+    let format = match params.format.as_str() {
+        "wav" => "pcm",
+        format => format,
+    };
+    let mut audio_params = json!({
+        "format": format,
+        "sample_rate": params.sample_rate,
+        "speech_rate": ratio_to_rate(params.speed_ratio),
+        "loudness_rate": ratio_to_rate(params.volume_ratio)
+    });
+    if let Some(emotion) = &params.emotion {
+        audio_params["emotion"] = json!(emotion);
+    }
+
+    let mut req_params = json!({
+        "speaker": voice,
+        "audio_params": audio_params
+    });
+    let pitch = ratio_to_semitones(params.pitch_ratio);
+    if pitch != 0 {
+        req_params["additions"] = json!(json!({ "post_process": { "pitch": pitch } }).to_string());
+    }
+    req_params
+}
+
+/// 空闲连接及其放入连接池的时间
+struct PooledLink {
+    link: TtsLink,
+    idle_since: Instant,
+}
+
+impl PooledLink {
+    fn reusable(&mut self, key: &str) -> bool {
+        self.link.key == key
+            && self.idle_since.elapsed() < Duration::from_secs(TTS_CONNECTION_IDLE_SECS)
+            && self.link.is_alive()
+    }
+}
+
+/// 预热并复用的 TTS 连接，多次播报共用一条 WebSocket，省去每次的握手与建连
+#[derive(Default)]
+pub struct TtsConnectionPool {
+    idle: Mutex<Option<PooledLink>>,
+}
+
+impl TtsConnectionPool {
+    /// 取一条连接：优先复用空闲连接，已失效或配置变更时重新建连
+    async fn acquire(
+        &self,
+        volcengine_config: &VolcengineConfig,
+        resource_id: &str,
+    ) -> Result<TtsLink, VoiceError> {
+        let key = connection_key(volcengine_config, resource_id);
+        if let Some(mut pooled) = self.idle.lock().await.take() {
+            if pooled.reusable(&key) {
+                pooled.link.reused = true;
+                return Ok(pooled.link);
+            }
+            debug!("tts discard stale pooled connection");
+            tokio::spawn(pooled.link.close());
+        }
+        connect_link(volcengine_config, resource_id).await
+    }
+
+    /// 会话正常结束后归还连接；连接池已有空闲连接时关闭多余的一条
+    async fn release(&self, link: TtsLink) {
+        if link.closed {
+            return;
+        }
+        let mut idle = self.idle.lock().await;
+        if idle.is_some() {
+            drop(idle);
+            link.close().await;
+            return;
+        }
+        *idle = Some(PooledLink {
+            link,
+            idle_since: Instant::now(),
+        });
+    }
+
+    /// 预热：没有可用的空闲连接时提前建连
+    async fn warm(
+        &self,
+        volcengine_config: &VolcengineConfig,
+        resource_id: &str,
+    ) -> Result<(), VoiceError> {
+        let key = connection_key(volcengine_config, resource_id);
+        // 建连期间持有锁，随后的播报会等待这条连接而不是另建一条
+        let mut idle = self.idle.lock().await;
+        if let Some(pooled) = idle.as_mut() {
+            if pooled.reusable(&key) {
+                return Ok(());
+            }
+        }
+        if let Some(stale) = idle.take() {
+            tokio::spawn(stale.link.close());
+        }
+
+        let link = connect_link(volcengine_config, resource_id).await?;
+        debug!("tts connection prewarmed");
+        *idle = Some(PooledLink {
+            link,
+            idle_since: Instant::now(),
+        });
+        Ok(())
+    }
+}
+
+/// 从连接池取连接并开启会话；复用的连接已被服务端关闭时透明重连一次
+async fn open_session(
+    app: &AppHandle,
+    volcengine_config: &VolcengineConfig,
+    resource_id: &str,
+    voice: &str,
+    params: &TtsAudioParams,
+) -> Result<TtsConnection, VoiceError> {
+    let started = Instant::now();
+    let link = app
+        .state::<TtsConnectionPool>()
+        .acquire(volcengine_config, resource_id)
+        .await?;
+    let connection = if !link.reused {
+        link.start_session(voice, params).await?
+    } else {
+        match link.start_session(voice, params).await {
+            Ok(connection) => connection,
+            Err(error) => {
+                warn!(error = %error.message, "tts pooled connection unusable, reconnecting");
+                connect_link(volcengine_config, resource_id)
+                    .await?
+                    .start_session(voice, params)
+                    .await?
+            }
+        }
+    };
+    voice_metrics::record(app, Stage::TtsConnect, started.elapsed());
+    Ok(connection)
+}
+
+/// 后台预热 TTS 连接，供随后的播报直接复用；未配置时静默跳过
+pub fn prewarm(app: &AppHandle) {
+    let app = app.clone();
+    tokio::spawn(async move {
+        if let Err(message) = warm_connection(&app).await {
+            debug!(reason = %message, "tts prewarm skipped");
+        }
+    });
+}
+
+async fn warm_connection(app: &AppHandle) -> Result<(), AppError> {
+    let volcengine_config = get_volcengine_config().await?;
+    // 只有火山引擎的双向连接需要预热
+    if volcengine_config.tts.provider != TtsProviderKind::Volcengine {
+        return Ok(());
+    }
+    let resource_id = resolve_resource_id(&volcengine_config)?;
+    Ok(app
+        .state::<TtsConnectionPool>()
+        .warm(&volcengine_config, &resource_id)
+        .await?)
+}
+
+/// 已开启会话的双向 TTS 连接
+struct TtsConnection {
+    link: TtsLink,
+    session_id: String,
+}
+
+impl TtsConnection {
+    /// 发送一段待合成文本
+    async fn send_text(&mut self, text: &str) -> Result<(), VoiceError> {
+        let task_payload = json!({
+            "event": Event::TaskRequest.code(),
+            "namespace": "BidirectionalTTS",
+            "req_params": {
+                "text": text
+            }
+        });
+        let task_payload_bytes = serde_json::to_vec(&task_payload)
+            .map_err(|e| VoiceError::protocol(tr!("protocol.serialize_failed", error = e)))?;
+        let task_request = encode_event(
+            Event::TaskRequest,
+            Some(&self.session_id),
+            &task_payload_bytes,
+            self.link.compression,
+        )?;
+        self.link
+            .write
+            .send(Message::Binary(task_request))
+            .await
+            .map_err(|e| VoiceError::from_ws(&tr!("tts.send_text_failed"), &e))
+    }
+
+    /// 文本已全部发送，请求服务端合成完剩余内容后结束会话
+    async fn finish_session(&mut self) -> Result<(), VoiceError> {
+        let finish_session = encode_event(
+            Event::FinishSession,
+            Some(&self.session_id),
+            b"{}",
+            self.link.compression,
+        )?;
+        self.link
+            .write
+            .send(Message::Binary(finish_session))
+            .await
+            .map_err(|e| VoiceError::from_ws(&tr!("tts.send_finish_failed"), &e))
+    }
+
+    /// 取消：立即发送 FinishSession / FinishConnection 并断开，不再等待剩余音频
+    async fn cancel(mut self) {
+        let _ = self.finish_session().await;
+        if let Ok(finish_conn) =
+            encode_event(Event::FinishConnection, None, b"{}", self.link.compression)
+        {
+            let _ = self.link.write.send(Message::Binary(finish_conn)).await;
+        }
+        let _ = self.link.write.close().await;
+    }
+}
+
+/// 把服务端音频与句子事件转发给前端，并记录字幕
+struct TtsOutput {
+    app: AppHandle,
+    window: WebviewWindow,
+    total_bytes: usize,
+    track: SubtitleTrack,
+    /// 首包延迟的计时起点
+    started: Option<Instant>,
+    reused_connection: bool,
+    /// 设置后在合成正常结束时把音频与字幕写入缓存
+    cache: Option<SpeechCache>,
+    audio: Vec<u8>,
+    /// wav 输出时补在首个分块前的流式文件头
+    wav_header: Option<Vec<u8>>,
+    /// wav 输出的格式，逐段合成时用于校验并去掉每段自带的文件头
+    wav_format: Option<WavFormat>,
+}
+
+impl TtsOutput {
+    fn new(app: &AppHandle, window: WebviewWindow, params: &TtsAudioParams) -> Self {
+        let encoding = AudioEncoding::from_format(&params.format, params.sample_rate)
+            .unwrap_or(AudioEncoding::Mp3);
+        Self {
+            app: app.clone(),
+            window,
+            total_bytes: 0,
+            track: SubtitleTrack::with_encoding(encoding),
+            started: None,
+            reused_connection: false,
+            cache: None,
+            audio: Vec::new(),
+            wav_header: (params.format == "wav").then(|| wav_header(params.sample_rate, 1, None)),
+            wav_format: (params.format == "wav").then_some(WavFormat::pcm16(params.sample_rate, 1)),
+        }
+    }
+
+    /// 开始计算首包延迟，只记录第一次调用
+    fn mark_started(&mut self) {
+        self.started.get_or_insert_with(Instant::now);
+    }
+
+    /// 是否已向前端下发过音频或句子事件，之后出错不能再整段重试
+    fn has_output(&self) -> bool {
+        !self.track.is_empty()
+    }
+
+    fn emit_audio(&mut self, mut audio: Vec<u8>) {
+        if let Some(mut header) = self.wav_header.take() {
+            header.append(&mut audio);
+            audio = header;
+        }
+        if self.total_bytes == 0 {
+            let latency = self
+                .started
+                .map(|started| started.elapsed())
+                .unwrap_or_default();
+            let latency_ms = latency.as_millis() as u64;
+            voice_metrics::record(&self.app, Stage::TtsFirstAudio, latency);
+            info!(
+                size = audio.len(),
+                latency_ms,
+                reused = self.reused_connection,
+                "tts first audio chunk"
+            );
+            let _ = self.window.emit(
+                "tts-first-audio",
+                TtsFirstAudioPayload {
+                    latency_ms,
+                    reused_connection: self.reused_connection,
+                },
+            );
+        }
+        self.total_bytes += audio.len();
+        self.track.push_audio(&audio);
+        if self.cache.is_some() {
+            self.audio.extend_from_slice(&audio);
+        }
+        let _ = self
+            .window
+            .emit("tts-stream-chunk", TtsStreamChunkPayload { chunk: audio });
+    }
+
+    /// 下发一段整段合成的音频并作为一句字幕，供不支持流式句子事件的提供方使用
+    fn emit_segment(&mut self, text: &str, audio: Vec<u8>) -> Result<(), AppError> {
+        let audio = match self.wav_format {
+            Some(expected) => {
+                let (format, data) = parse_wav(&audio).map_err(AppError::Input)?;
+                if format != expected {
+                    return Err(AppError::Input(tr!(
+                        "tts.segment_format_mismatch",
+                        rate = format.sample_rate,
+                        channels = format.channels,
+                        bits = format.bits_per_sample,
+                        expected = expected.sample_rate
+                    )));
+                }
+                data.to_vec()
+            }
+            None => audio,
+        };
+
+        let cue = self
+            .track
+            .start_sentence(json!({ "text": text }).to_string().as_bytes());
+        self.emit_sentence("start", cue);
+        self.emit_audio(audio);
+        if let Some(cue) = self.track.end_sentence(b"{}") {
+            self.emit_sentence("end", cue);
+        }
+        Ok(())
+    }
+
+    fn emit_sentence(&self, phase: &'static str, cue: SubtitleCue) {
+        let _ = self
+            .window
+            .emit("tts-sentence", TtsSentencePayload { phase, cue });
+    }
+
+    fn emit_error(&self, error: &AppError) {
+        let _ = self
+            .window
+            .emit::<TtsStreamErrorPayload>("tts-stream-error", error.clone());
+    }
+
+    /// 处理一条服务端消息，返回会话是否已结束
+    fn handle_message(&mut self, message: Message) -> Result<bool, VoiceError> {
+        let data = match message {
+            Message::Binary(data) => data,
+            Message::Text(text) => {
+                return Err(VoiceError::protocol(tr!("tts.text_message", text = text)))
+            }
+            Message::Close(_) => return Ok(true),
+            _ => return Ok(false),
+        };
+
+        let frame = decode_frame(&data)?;
+        if frame.is_error() {
+            return Err(volcengine::frame_error(&tr!("tts.service_error"), &frame));
+        }
+
+        if frame.message_type == MessageType::AudioOnlyResponse && !frame.payload.is_empty() {
+            self.emit_audio(frame.payload);
+            return Ok(false);
+        }
+
+        match frame.event {
+            Some(Event::TtsResponse) if !frame.payload.is_empty() => {
+                self.emit_audio(frame.payload);
+            }
+            Some(Event::TtsSentenceStart) => {
+                let cue = self.track.start_sentence(&frame.payload);
+                self.emit_sentence("start", cue);
+            }
+            Some(Event::TtsSentenceEnd) => {
+                if let Some(cue) = self.track.end_sentence(&frame.payload) {
+                    self.emit_sentence("end", cue);
+                }
+            }
+            Some(Event::SessionFinished) => return Ok(true),
+            Some(event)
+                if frame.serialization == Serialization::Json && !frame.payload.is_empty() =>
+            {
+                let payload_text = String::from_utf8_lossy(&frame.payload);
+                debug!(event = event.code(), payload = %payload_text, "tts event");
+            }
+            _ => {}
+        }
+        Ok(false)
+    }
+
+    /// 重放缓存的合成结果，按字节位置穿插句子事件，与在线合成的事件顺序一致；
+    /// 每个分块之间检查取消信号，被打断时与在线合成一样通知前端已取消
+    async fn replay(
+        mut self,
+        speech: CachedSpeech,
+        cancel_rx: &mut oneshot::Receiver<()>,
+    ) -> usize {
+        // 缓存的音频已带文件头
+        self.wav_header = None;
+        let cues = speech.cues;
+        let (mut started, mut ended, mut offset) = (0, 0, 0);
+        for chunk in speech.audio.chunks(TTS_REPLAY_CHUNK_BYTES) {
+            tokio::task::yield_now().await;
+            if cancel_rx.try_recv().is_ok() {
+                return self.cancel();
+            }
+            while started < cues.len() && cues[started].start_byte <= offset {
+                self.emit_sentence("start", cues[started].clone());
+                started += 1;
+            }
+            self.emit_audio(chunk.to_vec());
+            offset += chunk.len();
+            while ended < started && cues[ended].end_byte <= offset {
+                self.emit_sentence("end", cues[ended].clone());
+                ended += 1;
+            }
+        }
+        for (index, cue) in cues.iter().enumerate().skip(ended) {
+            if index >= started {
+                self.emit_sentence("start", cue.clone());
+            }
+            self.emit_sentence("end", cue.clone());
+        }
+
+        self.complete(cues).await
+    }
+
+    /// 合成正常结束：写入缓存后收尾
+    async fn finish(mut self) -> usize {
+        let cues = std::mem::take(&mut self.track).finish();
+        if let Some(cache) = self.cache.take() {
+            cache.store(CachedSpeech {
+                audio: std::mem::take(&mut self.audio),
+                cues: cues.clone(),
+            });
+        }
+        self.complete(cues).await
+    }
+
+    /// 保存字幕并通知前端合成结束，返回音频总字节数
+    async fn complete(self, cues: Vec<SubtitleCue>) -> usize {
+        *self.app.state::<TtsSubtitleState>().last.lock().await = cues;
+
+        let _ = self.window.emit(
+            "tts-stream-finished",
+            TtsStreamFinishedPayload {
+                total_bytes: self.total_bytes,
+            },
+        );
+        info!(total_bytes = self.total_bytes, "tts session finished");
+        if let Some(started) = self.started {
+            voice_metrics::record(&self.app, Stage::TtsFinish, started.elapsed());
+        }
+        voice_metrics::finish(&self.app);
+
+        self.total_bytes
+    }
+
+    /// 按结束方式通知前端，返回已下发的音频字节数
+    async fn end(self, end: SpeechEnd) -> usize {
+        match end {
+            SpeechEnd::Finished => self.finish().await,
+            SpeechEnd::Cancelled => self.cancel(),
+        }
+    }
+
+    /// 通知前端播报已取消，尚未下发的音频全部丢弃
+    fn cancel(self) -> usize {
+        let _ = self.window.emit(
+            "tts-stream-cancelled",
+            TtsStreamCancelledPayload {
+                total_bytes: self.total_bytes,
+            },
+        );
+        info!(total_bytes = self.total_bytes, "tts session cancelled");
+        voice_metrics::finish(&self.app);
+
+        self.total_bytes
+    }
+}
+
+/// 按结束方式收尾连接：正常结束的连接归还连接池，取消的连接在后台限时断开，不阻塞取消通知
+async fn end_connection(app: &AppHandle, connection: TtsConnection, end: SpeechEnd) {
+    match end {
+        SpeechEnd::Finished => {
+            app.state::<TtsConnectionPool>()
+                .release(connection.link)
+                .await
+        }
+        SpeechEnd::Cancelled => {
+            tokio::spawn(async move {
+                let teardown = async {
+                    connection.cancel().await;
+                    Ok(())
+                };
+                let deadline = Duration::from_secs(TTS_CANCEL_TEARDOWN_SECS);
+                if let Err(error) = net::with_timeout(deadline, &tr!("tts.cancel"), teardown).await
+                {
+                    debug!(error = %error.message, "tts cancel teardown abandoned");
+                }
+            });
+        }
+    }
+}
+
+/// 语音合成 - 流式下发音频与句子事件，按配置选择提供方
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(text_len = text.chars().count()))]
+pub async fn tts_speak_stream(
+    app: tauri::AppHandle,
+    text: String,
+    voice_type: Option<String>,
+    audio: Option<TtsAudioOverrides>,
+) -> Result<(), AppError> {
+    voice_metrics::begin(&app, Phase::Tts);
+    let volcengine_config = get_volcengine_config().await?;
+    let provider = tts_provider(&app, &volcengine_config);
+
+    let window = app
+        .get_webview_window("main")
+        .ok_or_else(AppError::window_not_found)?;
+
+    info!(
+        text_len = text.chars().count(),
+        voice_override = voice_type.is_some(),
+        provider = ?volcengine_config.tts.provider,
+        "tts start"
+    );
+
+    let tts_config = &volcengine_config.tts;
+    let params = provider.output_params(tts_config.audio.resolve(tts_config.provider, audio)?)?;
+    let mut output = TtsOutput::new(&app, window, &params);
+    output.mark_started();
+
+    let text = speech_text::normalize(&text, volcengine_config.tts.normalize);
+    let chunks = segmenter::split(&text, volcengine_config.tts.segmenter);
+    if chunks.is_empty() {
+        return Err(AppError::Input(tr!("tts.empty_text")));
+    }
+
+    let request = SpeechRequest {
+        voice: voice_type.unwrap_or_else(|| provider.default_voice().to_string()),
+        text,
+        params,
+    };
+    // 先登记本次播报并打断正在进行的播报，命中缓存的重放同样可被取消
+    let speaking = app.state::<TtsSpeakingState>();
+    let (speech_id, mut cancel_rx) = speaking.begin();
+
+    let cache = SpeechCache::new(
+        &volcengine_config,
+        TtsCacheKey {
+            text: &request.text,
+            voice_type: &request.voice,
+            audio_params: &json!(request.params),
+            resource_id: &provider.cache_scope(true),
+        },
+    );
+    if let Some(cache) = &cache {
+        if let Some(speech) = cache.lookup().await {
+            debug!(bytes = speech.audio.len(), "tts cache hit");
+            output.replay(speech, &mut cancel_rx).await;
+            speaking.end(speech_id);
+            return Ok(());
+        }
+    }
+    output.cache = cache;
+
+    let result = provider
+        .stream(&request, chunks, &mut output, &mut cancel_rx)
+        .await;
+    speaking.end(speech_id);
+
+    match result {
+        Ok(end) => {
+            output.end(end).await;
+            Ok(())
+        }
+        Err(error) => {
+            warn!(error = %error, "tts stream failed");
+            output.emit_error(&error);
+            voice_metrics::finish(&output.app);
+            Err(error)
+        }
+    }
+}
+
+/// 整段合成：按节奏发送分段文本，同时转发音频，收到取消信号立即停止
+async fn run_stream(
+    mut connection: TtsConnection,
+    output: &mut TtsOutput,
+    chunks: Vec<String>,
+    cancel_rx: &mut oneshot::Receiver<()>,
+) -> Result<(TtsConnection, SpeechEnd), VoiceError> {
+    let mut pending = chunks.into_iter();
+    let mut finishing = false;
+    let pacing = sleep(Duration::ZERO);
+    tokio::pin!(pacing);
+
+    let end = loop {
+        tokio::select! {
+            _ = &mut *cancel_rx => break SpeechEnd::Cancelled,
+            _ = &mut pacing, if !finishing => {
+                if let Some(chunk) = pending.next() {
+                    connection.send_text(&chunk).await?;
+                }
+                if pending.as_slice().is_empty() {
+                    finishing = true;
+                    connection.finish_session().await?;
+                } else {
+                    pacing
+                        .as_mut()
+                        .reset(Instant::now() + Duration::from_millis(TTS_STREAM_CHUNK_DELAY_MS));
+                }
+            },
+            message = connection.link.next_message(Some(net::READ_TIMEOUT)) => match message? {
+                Some(message) => {
+                    if output.handle_message(message)? {
+                        break SpeechEnd::Finished;
+                    }
+                }
+                None => break SpeechEnd::Finished,
+            },
+        }
+    };
+
+    Ok((connection, end))
+}
+
+/// 增量合成的后台任务：边接收文本边分句合成，同时转发音频
+async fn run_session(
+    mut connection: TtsConnection,
+    mut output: TtsOutput,
+    mut normalizer: SpeechNormalizer,
+    mut segmenter: Segmenter,
+    mut text_rx: mpsc::UnboundedReceiver<String>,
+    mut cancel_rx: oneshot::Receiver<()>,
+) -> Result<usize, AppError> {
+    let mut finishing = false;
+
+    let end = loop {
+        tokio::select! {
+            _ = &mut cancel_rx => break SpeechEnd::Cancelled,
+            text = text_rx.recv(), if !finishing => match text {
+                Some(text) => {
+                    output.mark_started();
+                    for chunk in segmenter.push(&normalizer.push(&text)) {
+                        connection.send_text(&chunk).await?;
+                    }
+                }
+                // 发送端关闭表示文本结束：送出剩余文本并结束会话
+                None => {
+                    finishing = true;
+                    for chunk in segmenter.push(&normalizer.finish()) {
+                        connection.send_text(&chunk).await?;
+                    }
+                    if let Some(rest) = segmenter.take() {
+                        connection.send_text(&rest).await?;
+                    }
+                    connection.finish_session().await?;
+                }
+            },
+            // 文本发完后服务端应持续返回音频，此前等待文本期间可能长时间没有消息
+            message = connection.link.next_message(finishing.then_some(net::READ_TIMEOUT)) => {
+                match message? {
+                    Some(message) => {
+                        if output.handle_message(message)? {
+                            break SpeechEnd::Finished;
+                        }
+                    }
+                    None => return Err(VoiceError::network(tr!("tts.connection_ended")).into()),
+                }
+            }
+        }
+    };
+
+    // 先通知前端（取消时立即送出 tts-stream-cancelled），再收尾连接
+    let app = output.app.clone();
+    let total_bytes = output.end(end).await;
+    end_connection(&app, connection, end).await;
+    Ok(total_bytes)
+}
+
+/// 增量合成会话 - 建立连接，之后通过 `tts_session_push` 推送文本
+#[tauri::command]
+#[tracing::instrument(skip_all)]
+pub async fn tts_session_open(
+    app: tauri::AppHandle,
+    state: State<'_, TtsSessionState>,
+    voice_type: Option<String>,
+    audio: Option<TtsAudioOverrides>,
+) -> Result<(), AppError> {
+    voice_metrics::begin(&app, Phase::Tts);
+    let volcengine_config = get_volcengine_config().await?;
+    if volcengine_config.tts.provider != TtsProviderKind::Volcengine {
+        return Err(AppError::Config(tr!("tts.session_volcengine_only")));
+    }
+    let provider = tts_provider(&app, &volcengine_config);
+    let resource_id = resolve_resource_id(&volcengine_config)?;
+    let voice = voice_type.unwrap_or_else(|| provider.default_voice().to_string());
+    let tts_config = &volcengine_config.tts;
+    let params = provider.output_params(tts_config.audio.resolve(tts_config.provider, audio)?)?;
+
+    let window = app
+        .get_webview_window("main")
+        .ok_or_else(AppError::window_not_found)?;
+
+    // 同一时间只保留一路增量合成；建连期间一直持有锁，并发的打开依次进行
+    let mut active = state.active.lock().await;
+    if let Some(previous) = active.take() {
+        cancel_session(&app, previous).await;
+    }
+
+    let connection = open_session(&app, &volcengine_config, &resource_id, &voice, &params).await?;
+    let (text_tx, text_rx) = mpsc::unbounded_channel();
+    let mut output = TtsOutput::new(&app, window.clone(), &params);
+    output.reused_connection = connection.link.reused;
+    let normalizer = SpeechNormalizer::new(volcengine_config.tts.normalize);
+    let segmenter = Segmenter::new(volcengine_config.tts.segmenter);
+    let (speech_id, cancel_rx) = app.state::<TtsSpeakingState>().begin();
+
+    let task = tokio::spawn(
+        async move {
+            let result = run_session(
+                connection, output, normalizer, segmenter, text_rx, cancel_rx,
+            )
+            .await;
+            app.state::<TtsSpeakingState>().end(speech_id);
+            if let Err(error) = &result {
+                warn!(error = %error, "tts session failed");
+                let _ = window.emit::<TtsStreamErrorPayload>("tts-stream-error", error.clone());
+                voice_metrics::finish(&app);
+            }
+            result
+        }
+        .in_current_span(),
+    );
+
+    *active = Some(TtsSessionHandle { text_tx, task });
+    Ok(())
+}
+
+/// 经取消信号结束上一路增量合成，使其照常通知前端并断开连接；迟迟不结束时中止任务
+async fn cancel_session(app: &AppHandle, session: TtsSessionHandle) {
+    // 文本发送端保留到任务结束，避免被当作文本结束而继续合成剩余内容
+    let TtsSessionHandle { text_tx, mut task } = session;
+    app.state::<TtsSpeakingState>().cancel();
+
+    let wait = Duration::from_secs(TTS_SESSION_CANCEL_TIMEOUT_SECS);
+    if timeout(wait, &mut task).await.is_err() {
+        task.abort();
+    }
+    drop(text_tx);
+}
+
+/// 增量合成会话 - 追加一段文本（如 Agent 的流式输出）
+#[tauri::command]
+pub async fn tts_session_push(
+    state: State<'_, TtsSessionState>,
+    text: String,
+) -> Result<(), AppError> {
+    let active = state.active.lock().await;
+    let session = active
+        .as_ref()
+        .ok_or_else(|| AppError::State(tr!("tts.session_not_started")))?;
+    session
+        .text_tx
+        .send(text)
+        .map_err(|_| AppError::State(tr!("tts.session_ended")))
+}
+
+/// 增量合成会话 - 文本结束，等待剩余音频合成完毕并返回音频总字节数
+#[tauri::command]
+pub async fn tts_session_close(state: State<'_, TtsSessionState>) -> Result<usize, AppError> {
+    let session = state
+        .active
+        .lock()
+        .await
+        .take()
+        .ok_or_else(|| AppError::State(tr!("tts.session_not_started")))?;
+
+    // 丢弃发送端即通知后台任务送出剩余文本
+    drop(session.text_tx);
+
+    let abort_handle = session.task.abort_handle();
+    match timeout(
+        Duration::from_secs(TTS_SESSION_CLOSE_TIMEOUT_SECS),
+        session.task,
+    )
+    .await
+    {
+        Ok(result) => {
+            result.map_err(|e| AppError::Internal(tr!("tts.session_task_failed", error = e)))?
+        }
+        Err(_) => {
+            abort_handle.abort();
+            Err(VoiceError::network(tr!("tts.session_close_timeout"))
+                .fatal()
+                .into())
+        }
+    }
+}
+
+/// 预热语音合成连接（如 Agent 开始运行时），随后的播报可直接复用
+#[tauri::command]
+pub async fn tts_prewarm(app: tauri::AppHandle) -> Result<(), AppError> {
+    warm_connection(&app).await
+}
+
+/// 清空语音合成缓存，返回被删除的条目统计
+#[tauri::command]
+pub async fn tts_cache_clear() -> Result<TtsCacheStats, AppError> {
+    let cache = TtsCache::new(get_tts_cache_dir(), 0, Duration::ZERO);
+    tokio::task::spawn_blocking(move || cache.clear())
+        .await
+        .map_err(|e| AppError::Internal(tr!("tts.cache_task_failed", error = e)))?
+        .map_err(|e| AppError::Io(tr!("tts.cache_clear_failed", error = e)))
+}
+
+/// 打断当前播报：立即结束会话并丢弃尚未下发的音频，返回是否确有播报被打断
+#[tauri::command]
+pub fn tts_cancel(speaking: State<'_, TtsSpeakingState>) -> bool {
+    speaking.cancel()
+}
+
+/// 导出最近一次流式合成的字幕，`format` 为 "srt" 或 "vtt"
+#[tauri::command]
+pub async fn tts_export_subtitles(
+    state: State<'_, TtsSubtitleState>,
+    format: String,
+) -> Result<String, AppError> {
+    let cues = state.last.lock().await;
+    if cues.is_empty() {
+        return Err(AppError::State(tr!("tts.no_subtitles")));
+    }
+
+    match format.to_lowercase().as_str() {
+        "srt" => Ok(subtitles::to_srt(&cues)),
+        "vtt" | "webvtt" => Ok(subtitles::to_webvtt(&cues)),
+        other => Err(AppError::Input(tr!(
+            "tts.unsupported_subtitle_format",
+            format = other
+        ))),
+    }
+}
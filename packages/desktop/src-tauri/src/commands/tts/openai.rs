@@ -0,0 +1,108 @@
+//! OpenAI 兼容的 `/v1/audio/speech` 语音合成，也适用于提供同样接口的自建服务
+
+use super::provider::{SpeechRequest, TtsProvider};
+use crate::commands::config::OpenAiTtsConfig;
+use futures_util::future::BoxFuture;
+use futures_util::FutureExt;
+use reason_desktop_lib::error::AppError;
+use reason_desktop_lib::net::{self, RetryPolicy, VoiceError};
+use reason_desktop_lib::tr;
+use serde::Serialize;
+
+#[derive(Debug, Serialize)]
+struct SpeechBody<'a> {
+    model: &'a str,
+    input: &'a str,
+    voice: &'a str,
+    response_format: &'a str,
+    speed: f32,
+}
+
+/// 支持的格式与采样率在 `TtsAudioParams::resolve` 中校验；音量、音调与情感参数不受支持，会被忽略
+pub struct OpenAiTts {
+    config: OpenAiTtsConfig,
+    client: reqwest::Client,
+}
+
+impl OpenAiTts {
+    pub fn new(config: OpenAiTtsConfig) -> Self {
+        Self {
+            config,
+            client: net::http_client(),
+        }
+    }
+
+    fn endpoint(&self) -> String {
+        format!(
+            "{}/audio/speech",
+            self.config.base_url.trim().trim_end_matches('/')
+        )
+    }
+
+    async fn request(&self, request: &SpeechRequest) -> Result<Vec<u8>, AppError> {
+        if self.config.base_url.trim().is_empty() {
+            return Err(AppError::Config(tr!("tts.service_url_missing")));
+        }
+        let body = SpeechBody {
+            model: &self.config.model,
+            input: &request.text,
+            voice: &request.voice,
+            response_format: &request.params.format,
+            speed: request.params.speed_ratio,
+        };
+
+        Ok(net::retry(
+            RetryPolicy::default(),
+            &tr!("tts.synthesize_openai"),
+            || self.send(&body),
+        )
+        .await?)
+    }
+
+    async fn send(&self, body: &SpeechBody<'_>) -> Result<Vec<u8>, VoiceError> {
+        let mut builder = self.client.post(self.endpoint()).json(body);
+        if !self.config.api_key.is_empty() {
+            builder = builder.bearer_auth(&self.config.api_key);
+        }
+        let response = builder
+            .send()
+            .await
+            .map_err(|e| VoiceError::from_reqwest(&tr!("http.request_failed"), &e))?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let detail = response.text().await.unwrap_or_default();
+            return Err(VoiceError::from_status(
+                &tr!("tts.request_failed"),
+                status.as_u16(),
+                &detail,
+            ));
+        }
+
+        let audio = response
+            .bytes()
+            .await
+            .map_err(|e| VoiceError::from_reqwest(&tr!("tts.read_audio_failed"), &e))?;
+        if audio.is_empty() {
+            return Err(VoiceError::protocol(tr!("tts.no_audio")));
+        }
+        Ok(audio.to_vec())
+    }
+}
+
+impl TtsProvider for OpenAiTts {
+    fn default_voice(&self) -> &str {
+        &self.config.voice
+    }
+
+    fn cache_scope(&self, _streaming: bool) -> String {
+        format!("openai:{}:{}", self.endpoint(), self.config.model)
+    }
+
+    fn synthesize<'a>(
+        &'a self,
+        request: &'a SpeechRequest,
+    ) -> BoxFuture<'a, Result<Vec<u8>, AppError>> {
+        self.request(request).boxed()
+    }
+}
@@ -0,0 +1,77 @@
+//! 语音合成提供方：火山引擎、OpenAI 兼容接口与本地命令
+//!
+//! 各提供方只负责产出音频，事件下发、缓存与取消由 `tts_speak` / `tts_speak_stream` 统一处理，
+//! 前端收到的事件与提供方无关。
+
+use super::command::CommandTts;
+use super::openai::OpenAiTts;
+use super::{SpeechEnd, TtsOutput, VolcengineTts};
+use crate::commands::config::{TtsAudioParams, TtsProviderKind, VolcengineConfig};
+use futures_util::future::BoxFuture;
+use futures_util::FutureExt;
+use reason_desktop_lib::error::AppError;
+use tauri::AppHandle;
+use tokio::sync::oneshot;
+
+/// 一次合成请求
+pub struct SpeechRequest {
+    pub text: String,
+    pub voice: String,
+    pub params: TtsAudioParams,
+}
+
+pub trait TtsProvider: Send + Sync {
+    /// 未指定音色时使用的音色
+    fn default_voice(&self) -> &str;
+
+    /// 换算为提供方实际输出的音频参数，不支持的格式返回错误
+    fn output_params(&self, params: TtsAudioParams) -> Result<TtsAudioParams, AppError> {
+        Ok(params)
+    }
+
+    /// 区分缓存条目的提供方标识（服务地址、模型、资源 ID 等）
+    fn cache_scope(&self, streaming: bool) -> String;
+
+    /// 整段合成，返回 `request.params` 格式的完整音频
+    fn synthesize<'a>(
+        &'a self,
+        request: &'a SpeechRequest,
+    ) -> BoxFuture<'a, Result<Vec<u8>, AppError>>;
+
+    /// 流式合成：音频与句子事件写入 `output`，收到取消信号立即停止
+    ///
+    /// 默认逐段调用 `synthesize`，每段作为一句字幕下发。
+    fn stream<'a>(
+        &'a self,
+        request: &'a SpeechRequest,
+        chunks: Vec<String>,
+        output: &'a mut TtsOutput,
+        cancel_rx: &'a mut oneshot::Receiver<()>,
+    ) -> BoxFuture<'a, Result<SpeechEnd, AppError>> {
+        async move {
+            for chunk in chunks {
+                let segment = SpeechRequest {
+                    text: chunk,
+                    voice: request.voice.clone(),
+                    params: request.params.clone(),
+                };
+                let audio = tokio::select! {
+                    _ = &mut *cancel_rx => return Ok(SpeechEnd::Cancelled),
+                    audio = self.synthesize(&segment) => audio?,
+                };
+                output.emit_segment(&segment.text, audio)?;
+            }
+            Ok(SpeechEnd::Finished)
+        }
+        .boxed()
+    }
+}
+
+/// 按配置中的 `tts.provider` 选择提供方
+pub fn tts_provider(app: &AppHandle, config: &VolcengineConfig) -> Box<dyn TtsProvider> {
+    match config.tts.provider {
+        TtsProviderKind::Volcengine => Box::new(VolcengineTts::new(app, config.clone())),
+        TtsProviderKind::Openai => Box::new(OpenAiTts::new(config.tts.openai.clone())),
+        TtsProviderKind::Command => Box::new(CommandTts::new(config.tts.command.clone())),
+    }
+}
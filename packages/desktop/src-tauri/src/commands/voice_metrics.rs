@@ -0,0 +1,102 @@
+use crate::commands::config::get_voice_metrics_path;
+use reason_desktop_lib::error::AppError;
+use reason_desktop_lib::metrics::{
+    self, InteractionMetrics, InteractionTracker, MetricsStore, Phase, Stage, VoiceStats,
+};
+use reason_desktop_lib::tr;
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Emitter, Manager, State};
+
+/// 本地保留的交互记录数
+const MAX_RECORDS: usize = 500;
+/// `voice_stats` 默认统计的最近交互数
+const DEFAULT_STATS_LIMIT: usize = 100;
+
+/// 一次交互归档时推送给前端的各环节耗时
+pub type VoiceMetricsPayload = InteractionMetrics;
+
+/// 语音交互耗时统计，由 Tauri 托管
+pub struct VoiceMetricsState {
+    tracker: Mutex<InteractionTracker>,
+    store: MetricsStore,
+}
+
+impl Default for VoiceMetricsState {
+    fn default() -> Self {
+        Self {
+            tracker: Mutex::default(),
+            store: MetricsStore::new(get_voice_metrics_path(), MAX_RECORDS),
+        }
+    }
+}
+
+impl VoiceMetricsState {
+    fn tracker(&self) -> std::sync::MutexGuard<'_, InteractionTracker> {
+        self.tracker.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or_default()
+}
+
+/// 某个阶段开始，必要时归档上一轮交互
+pub fn begin(app: &AppHandle, phase: Phase) {
+    let finished = app
+        .state::<VoiceMetricsState>()
+        .tracker()
+        .begin(phase, now_ms());
+    if let Some(interaction) = finished {
+        archive(app, interaction);
+    }
+}
+
+/// 记录一个环节的耗时
+pub fn record(app: &AppHandle, stage: Stage, elapsed: Duration) {
+    let ms = elapsed.as_millis() as u64;
+    tracing::debug!(stage = ?stage, ms, "voice stage timed");
+    app.state::<VoiceMetricsState>()
+        .tracker()
+        .record(stage, ms, now_ms());
+}
+
+/// 交互结束（播报完成或被打断），立即归档
+pub fn finish(app: &AppHandle) {
+    let finished = app.state::<VoiceMetricsState>().tracker().finish();
+    if let Some(interaction) = finished {
+        archive(app, interaction);
+    }
+}
+
+/// 推送本轮耗时明细并在后台写入本地记录
+fn archive(app: &AppHandle, interaction: InteractionMetrics) {
+    tracing::info!(stages = ?interaction.stages, "voice interaction timed");
+    if let Some(window) = app.get_webview_window("main") {
+        let _ = window.emit::<VoiceMetricsPayload>("voice-metrics", interaction.clone());
+    }
+    let store = app.state::<VoiceMetricsState>().store.clone();
+    tokio::task::spawn_blocking(move || {
+        if let Err(e) = store.append(&interaction) {
+            tracing::warn!(error = %e, "voice metrics write failed");
+        }
+    });
+}
+
+/// 最近 `limit` 次交互（默认 100）各环节耗时的分位数
+#[tauri::command]
+pub async fn voice_stats(
+    state: State<'_, VoiceMetricsState>,
+    limit: Option<usize>,
+) -> Result<VoiceStats, AppError> {
+    let store = state.store.clone();
+    let limit = limit.unwrap_or(DEFAULT_STATS_LIMIT);
+    let recent = tokio::task::spawn_blocking(move || store.recent(limit))
+        .await
+        .map_err(|e| AppError::Internal(tr!("metrics.task_failed", error = e)))?
+        .map_err(|e| AppError::Io(tr!("metrics.read_failed", error = e)))?;
+    Ok(metrics::summarize(&recent))
+}
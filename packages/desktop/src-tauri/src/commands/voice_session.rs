@@ -1,3 +1,5 @@
+use reason_desktop_lib::error::AppError;
+use reason_desktop_lib::tr;
 use serde::Serialize;
 use std::fs::{self, OpenOptions};
 use std::io::Write;
@@ -13,7 +15,7 @@ pub struct VoiceSessionState {
 }
 
 impl VoiceSessionState {
-    pub fn new() -> Result<Self, String> {
+    pub fn new() -> Result<Self, AppError> {
         let session_id = Uuid::new_v4().to_string();
         let file_path = voice_session_file_path(&session_id);
 
@@ -53,16 +55,17 @@ fn voice_session_file_path(session_id: &str) -> PathBuf {
     voice_session_dir().join(format!("{}.jsonl", session_id))
 }
 
-fn ensure_session_file(path: &Path) -> Result<(), String> {
+fn ensure_session_file(path: &Path) -> Result<(), AppError> {
     if let Some(parent) = path.parent() {
-        fs::create_dir_all(parent).map_err(|e| format!("Failed to create session dir: {}", e))?;
+        fs::create_dir_all(parent)
+            .map_err(|e| AppError::Io(tr!("session.create_dir_failed", error = e)))?;
     }
 
     OpenOptions::new()
         .create(true)
         .append(true)
         .open(path)
-        .map_err(|e| format!("Failed to open session file: {}", e))?;
+        .map_err(|e| AppError::Io(tr!("session.open_failed", error = e)))?;
 
     Ok(())
 }
@@ -75,7 +78,7 @@ fn now_ms() -> u64 {
 }
 
 #[tauri::command]
-pub async fn voice_session_start(state: State<'_, VoiceSessionState>) -> Result<String, String> {
+pub async fn voice_session_start(state: State<'_, VoiceSessionState>) -> Result<String, AppError> {
     ensure_session_file(state.file_path())?;
     Ok(state.session_id().to_string())
 }
@@ -86,7 +89,7 @@ pub async fn voice_session_append(
     role: String,
     text: String,
     source: String,
-) -> Result<(), String> {
+) -> Result<(), AppError> {
     let trimmed = text.trim();
     if trimmed.is_empty() {
         return Ok(());
@@ -103,17 +106,17 @@ pub async fn voice_session_append(
     };
 
     let line = serde_json::to_string(&record)
-        .map_err(|e| format!("Failed to serialize session record: {}", e))?;
+        .map_err(|e| AppError::Internal(tr!("session.serialize_failed", error = e)))?;
 
     let mut file = OpenOptions::new()
         .create(true)
         .append(true)
         .open(state.file_path())
-        .map_err(|e| format!("Failed to open session file: {}", e))?;
+        .map_err(|e| AppError::Io(tr!("session.open_failed", error = e)))?;
 
     file.write_all(line.as_bytes())
         .and_then(|_| file.write_all(b"\n"))
-        .map_err(|e| format!("Failed to write session record: {}", e))?;
+        .map_err(|e| AppError::Io(tr!("session.write_failed", error = e)))?;
 
     Ok(())
 }
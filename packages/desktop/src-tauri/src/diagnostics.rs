@@ -0,0 +1,187 @@
+//! 诊断包：打包最近的日志、脱敏后的配置与版本信息，供反馈问题时附带
+
+use crate::logging::{redact, LOG_FILE_PREFIX};
+use serde_json::Value;
+use std::io::{Seek, Write};
+use std::path::{Path, PathBuf};
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipWriter};
+
+/// 诊断包收录日志的总大小上限
+pub const MAX_LOG_BYTES: u64 = 20 * 1024 * 1024;
+
+/// 键名（忽略大小写与 `_` / `-`）包含这些词的字符串值会被遮盖
+const SECRET_KEY_PARTS: [&str; 6] = [
+    "token",
+    "apikey",
+    "accesskey",
+    "secret",
+    "password",
+    "authorization",
+];
+
+/// 遮盖配置中的密钥；空字符串保留原样，便于看出是否漏填
+pub fn sanitize_config(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            for (key, value) in map.iter_mut() {
+                let normalized = key.to_lowercase().replace(['_', '-'], "");
+                let secret = SECRET_KEY_PARTS
+                    .iter()
+                    .any(|part| normalized.contains(part));
+                match value {
+                    Value::String(text) if secret && !text.is_empty() => {
+                        *text = "***".to_string();
+                    }
+                    _ => sanitize_config(value),
+                }
+            }
+        }
+        Value::Array(items) => items.iter_mut().for_each(sanitize_config),
+        _ => {}
+    }
+}
+
+/// 脱敏配置文件内容；无法解析为 JSON 时按文本规则脱敏
+pub fn sanitize_config_text(content: &str) -> String {
+    match serde_json::from_str::<Value>(content) {
+        Ok(mut value) => {
+            sanitize_config(&mut value);
+            serde_json::to_string_pretty(&value).unwrap_or_default()
+        }
+        Err(_) => redact(content).into_owned(),
+    }
+}
+
+/// 日志目录中最近的日志文件，新的在前，总大小不超过 `max_bytes`（最新的一个总会收录）
+pub fn recent_logs(dir: &Path, max_bytes: u64) -> Vec<PathBuf> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut files: Vec<_> = entries
+        .flatten()
+        .filter(|entry| {
+            entry
+                .file_name()
+                .to_string_lossy()
+                .starts_with(LOG_FILE_PREFIX)
+        })
+        .filter_map(|entry| {
+            let metadata = entry
+                .metadata()
+                .ok()
+                .filter(|metadata| metadata.is_file())?;
+            Some((metadata.modified().ok(), metadata.len(), entry.path()))
+        })
+        .collect();
+    files.sort_by(|a, b| b.0.cmp(&a.0).then_with(|| b.2.cmp(&a.2)));
+
+    let mut total = 0;
+    files
+        .into_iter()
+        .take_while(|(_, len, _)| {
+            let keep = total == 0 || total + len <= max_bytes;
+            total += len;
+            keep
+        })
+        .map(|(_, _, path)| path)
+        .collect()
+}
+
+/// 写出诊断包：`info.json`、`config.json`（如有）与 `logs/` 下的日志文件
+///
+/// 日志写入时已脱敏，打包时再按文本规则过一遍，覆盖旧版本留下的日志。
+pub fn write_bundle<W: Write + Seek>(
+    out: W,
+    info: &Value,
+    config: Option<&str>,
+    logs: &[PathBuf],
+) -> Result<W, String> {
+    let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+    let mut zip = ZipWriter::new(out);
+    let mut add = |name: &str, content: &[u8]| -> Result<(), String> {
+        zip.start_file(name, options).map_err(|e| e.to_string())?;
+        zip.write_all(content).map_err(|e| e.to_string())
+    };
+
+    let info = serde_json::to_string_pretty(info).map_err(|e| e.to_string())?;
+    add("info.json", info.as_bytes())?;
+    if let Some(config) = config {
+        add("config.json", config.as_bytes())?;
+    }
+    for path in logs {
+        let Some(name) = path.file_name() else {
+            continue;
+        };
+        let content = std::fs::read(path).map_err(|e| e.to_string())?;
+        let content = String::from_utf8_lossy(&content);
+        add(
+            &format!("logs/{}", name.to_string_lossy()),
+            redact(&content).as_bytes(),
+        )?;
+    }
+    zip.finish().map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::io::{Cursor, Read};
+
+    #[test]
+    fn sanitizes_nested_secrets() {
+        let mut config = json!({
+            "volcengine": { "appId": "42", "accessToken": "abc", "tts": { "apiKey": "" } },
+            "providers": [{ "name": "openai", "api_key": "sk-1", "max_tokens": 100 }],
+        });
+        sanitize_config(&mut config);
+        assert_eq!(config["volcengine"]["appId"], "42");
+        assert_eq!(config["volcengine"]["accessToken"], "***");
+        assert_eq!(config["volcengine"]["tts"]["apiKey"], "");
+        assert_eq!(config["providers"][0]["api_key"], "***");
+        assert_eq!(config["providers"][0]["max_tokens"], 100);
+
+        assert!(sanitize_config_text("{ broken, \"accessToken\": \"abc\"").contains("***"));
+    }
+
+    #[test]
+    fn bundles_info_config_and_recent_logs() {
+        let dir = std::env::temp_dir().join(format!("reason-diagnostics-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let log = dir.join(format!("{}.2024-01-01.log", LOG_FILE_PREFIX));
+        std::fs::write(&log, "{\"apiKey\":\"sk-1\"}\n").unwrap();
+        std::fs::write(dir.join("other.txt"), "ignored").unwrap();
+
+        let logs = recent_logs(&dir, MAX_LOG_BYTES);
+        assert_eq!(logs, vec![log]);
+
+        let bundle = write_bundle(
+            Cursor::new(Vec::new()),
+            &json!({ "version": "0.0.1" }),
+            Some("{}"),
+            &logs,
+        )
+        .unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        let mut archive = zip::ZipArchive::new(bundle).unwrap();
+        let mut names: Vec<_> = archive.file_names().map(String::from).collect();
+        names.sort();
+        assert_eq!(
+            names,
+            [
+                "config.json",
+                "info.json",
+                "logs/reason-desktop.2024-01-01.log"
+            ]
+        );
+        let mut content = String::new();
+        archive
+            .by_name("logs/reason-desktop.2024-01-01.log")
+            .unwrap()
+            .read_to_string(&mut content)
+            .unwrap();
+        assert_eq!(content, "{\"apiKey\":\"***\"}\n");
+    }
+}
@@ -0,0 +1,207 @@
+//! 后端统一的错误模型：Tauri 命令的返回值与各个 `*-error` 事件共用
+//!
+//! 前端收到的结构为 `{code, category, message, details, retryable}`：`code` 为稳定的错误码，
+//! `category` 为粗分类，`message` 可直接展示，`details` 为原始错误信息（HTTP 正文、进程输出等）。
+
+use crate::net::{ErrorKind, VoiceError};
+use crate::tr;
+use serde::ser::{Serialize, SerializeStruct, Serializer};
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AppError {
+    /// 配置文件不存在
+    ConfigMissing,
+    /// 配置无法读取、解析，或缺少必填项
+    Config(String),
+    /// 语音接口错误，沿用其分类与可重试标记
+    Voice(VoiceError),
+    /// 参数、文本或录音本身无效
+    Input(String),
+    /// 外部进程启动失败、超时或异常退出；`details` 为进程的错误输出
+    Process {
+        message: String,
+        details: Option<String>,
+    },
+    /// 本地文件读写失败
+    Io(String),
+    /// 窗口不存在或窗口操作失败；`details` 为窗口系统返回的原始错误
+    Window {
+        message: String,
+        details: Option<String>,
+    },
+    /// 当前状态不允许该操作，如没有进行中的识别会话
+    State(String),
+    /// 其他内部错误
+    Internal(String),
+}
+
+impl AppError {
+    pub fn process(message: impl Into<String>, details: impl Into<String>) -> Self {
+        let details = details.into();
+        Self::Process {
+            message: message.into(),
+            details: (!details.trim().is_empty()).then(|| details.trim().to_string()),
+        }
+    }
+
+    pub fn window(message: impl Into<String>, details: impl Into<String>) -> Self {
+        let details = details.into();
+        Self::Window {
+            message: message.into(),
+            details: (!details.trim().is_empty()).then(|| details.trim().to_string()),
+        }
+    }
+
+    /// 主窗口不存在
+    pub fn window_not_found() -> Self {
+        Self::window(tr!("window.not_found"), "")
+    }
+
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::ConfigMissing => "config_missing",
+            Self::Config(_) => "config_invalid",
+            Self::Voice(error) => match error.kind {
+                ErrorKind::Auth => "auth_failed",
+                ErrorKind::Quota => "quota_exceeded",
+                ErrorKind::Network => "network_error",
+                ErrorKind::Protocol => "protocol_error",
+                ErrorKind::Input => "request_rejected",
+            },
+            Self::Input(_) => "invalid_input",
+            Self::Process { .. } => "process_failed",
+            Self::Io(_) => "io_error",
+            Self::Window { .. } => "window_error",
+            Self::State(_) => "invalid_state",
+            Self::Internal(_) => "internal_error",
+        }
+    }
+
+    pub fn category(&self) -> &'static str {
+        match self {
+            Self::ConfigMissing | Self::Config(_) => "config",
+            Self::Voice(error) => match error.kind {
+                ErrorKind::Auth => "auth",
+                ErrorKind::Quota => "quota",
+                ErrorKind::Network => "network",
+                ErrorKind::Protocol => "protocol",
+                ErrorKind::Input => "input",
+            },
+            Self::Input(_) => "input",
+            Self::Process { .. } => "process",
+            Self::Io(_) => "io",
+            Self::Window { .. } => "window",
+            Self::State(_) => "state",
+            Self::Internal(_) => "internal",
+        }
+    }
+
+    /// 面向用户的说明
+    pub fn message(&self) -> String {
+        match self {
+            Self::ConfigMissing => tr!("error.config_missing"),
+            Self::Voice(error) => error.kind.label(),
+            Self::Process { message, .. } | Self::Window { message, .. } => message.clone(),
+            Self::Config(message)
+            | Self::Input(message)
+            | Self::Io(message)
+            | Self::State(message)
+            | Self::Internal(message) => message.clone(),
+        }
+    }
+
+    pub fn details(&self) -> Option<&str> {
+        match self {
+            Self::Voice(error) => Some(&error.message),
+            Self::Process { details, .. } | Self::Window { details, .. } => details.as_deref(),
+            _ => None,
+        }
+    }
+
+    /// 稍后重试可能成功，目前只有网络类语音接口错误
+    pub fn retryable(&self) -> bool {
+        matches!(self, Self::Voice(error) if error.retryable)
+    }
+}
+
+impl fmt::Display for AppError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Voice(error) => write!(f, "{}", error),
+            _ => match self.details() {
+                Some(details) => write!(f, "{}: {}", self.message(), details),
+                None => f.write_str(&self.message()),
+            },
+        }
+    }
+}
+
+impl std::error::Error for AppError {}
+
+impl Serialize for AppError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("AppError", 5)?;
+        state.serialize_field("code", self.code())?;
+        state.serialize_field("category", self.category())?;
+        state.serialize_field("message", &self.message())?;
+        state.serialize_field("details", &self.details())?;
+        state.serialize_field("retryable", &self.retryable())?;
+        state.end()
+    }
+}
+
+impl From<VoiceError> for AppError {
+    fn from(error: VoiceError) -> Self {
+        Self::Voice(error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn serializes_to_flat_structure() {
+        let error = AppError::from(VoiceError::from_status("语音合成失败", 503, "busy"));
+        assert_eq!(
+            serde_json::to_value(&error).unwrap(),
+            json!({
+                "code": "network_error",
+                "category": "network",
+                "message": "网络异常",
+                "details": "语音合成失败: HTTP 503 busy",
+                "retryable": true,
+            })
+        );
+
+        let error = AppError::process("reason CLI 异常退出（退出码 2）", "  panic  \n");
+        let value = serde_json::to_value(&error).unwrap();
+        assert_eq!(value["code"], "process_failed");
+        assert_eq!(value["details"], "panic");
+        assert_eq!(value["retryable"], false);
+
+        let value =
+            serde_json::to_value(AppError::window("移动窗口失败", "not supported")).unwrap();
+        assert_eq!(value["code"], "window_error");
+        assert_eq!(value["message"], "移动窗口失败");
+        assert_eq!(value["details"], "not supported");
+
+        let value = serde_json::to_value(AppError::ConfigMissing).unwrap();
+        assert_eq!(value["category"], "config");
+        assert_eq!(value["details"], serde_json::Value::Null);
+    }
+
+    #[test]
+    fn distinguishes_auth_from_missing_config() {
+        let auth = AppError::from(VoiceError::from_status("识别", 401, ""));
+        assert_eq!((auth.code(), auth.category()), ("auth_failed", "auth"));
+        assert!(!auth.retryable());
+        assert_eq!(AppError::ConfigMissing.code(), "config_missing");
+        assert_eq!(
+            AppError::Input("录音数据为空".to_string()).to_string(),
+            "录音数据为空"
+        );
+    }
+}
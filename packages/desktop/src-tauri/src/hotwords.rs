@@ -0,0 +1,251 @@
+//! 语音识别热词：用户词表 + 从当前项目中提取的标识符与文件名
+//!
+//! 开发者口述的 `ExecutionStreamManager`、`tts_ws_test` 之类的词通用 ASR 很难识别正确，
+//! 识别前把这些词作为热词随请求发送，可以显著提高拼写准确率。
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+/// 单次请求最多携带的热词数
+pub const MAX_HOTWORDS: usize = 100;
+/// 单个热词的最大字符数
+pub const MAX_HOTWORD_CHARS: usize = 40;
+
+const MIN_IDENTIFIER_CHARS: usize = 4;
+const MAX_SCAN_DEPTH: usize = 8;
+const MAX_SCAN_FILES: usize = 2000;
+const MAX_FILE_BYTES: u64 = 256 * 1024;
+/// 包名（crate / npm package）相对普通标识符的权重
+const PACKAGE_NAME_WEIGHT: usize = 1000;
+/// 文件名相对普通标识符的权重
+const FILE_NAME_WEIGHT: usize = 5;
+
+const SOURCE_EXTENSIONS: &[&str] = &[
+    "rs", "ts", "tsx", "js", "jsx", "mjs", "py", "go", "java", "kt", "swift", "c", "cc", "cpp",
+    "h", "hpp", "cs", "rb", "vue", "svelte",
+];
+const SKIPPED_DIRS: &[&str] = &[
+    "node_modules",
+    "target",
+    "dist",
+    "build",
+    "out",
+    "vendor",
+    "coverage",
+];
+const PROJECT_MARKERS: &[&str] = &[
+    ".git",
+    "Cargo.toml",
+    "package.json",
+    "go.mod",
+    "pyproject.toml",
+];
+
+/// 目录下是否存在常见的项目标记文件，避免在 `/` 或家目录下盲目扫描
+pub fn is_project_root(path: &Path) -> bool {
+    PROJECT_MARKERS
+        .iter()
+        .any(|marker| path.join(marker).exists())
+}
+
+/// 读取用户词表：每行一个词，`#` 开头为注释
+pub fn load_vocabulary(path: &Path) -> Vec<String> {
+    let Ok(content) = fs::read_to_string(path) else {
+        return Vec::new();
+    };
+
+    content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(str::to_string)
+        .collect()
+}
+
+/// 扫描项目目录，按出现频次返回标识符、文件名和包名
+pub fn harvest_project(root: &Path) -> Vec<String> {
+    let mut scores: HashMap<String, usize> = HashMap::new();
+    let mut scanned_files = 0usize;
+    scan_dir(root, 0, &mut scanned_files, &mut scores);
+
+    for name in package_names(root) {
+        *scores.entry(name).or_default() += PACKAGE_NAME_WEIGHT;
+    }
+
+    let mut ranked: Vec<(String, usize)> = scores.into_iter().collect();
+    ranked.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    ranked.into_iter().map(|(word, _)| word).collect()
+}
+
+/// 合并词表与项目热词：用户词表优先，去重后截断到 `max_words`
+pub fn build_hotwords(
+    vocabulary: Vec<String>,
+    project: Vec<String>,
+    max_words: usize,
+) -> Vec<String> {
+    let mut hotwords = Vec::new();
+    for word in vocabulary.into_iter().chain(project) {
+        if hotwords.len() >= max_words {
+            break;
+        }
+        if word.chars().count() > MAX_HOTWORD_CHARS || hotwords.contains(&word) {
+            continue;
+        }
+        hotwords.push(word);
+    }
+    hotwords
+}
+
+fn scan_dir(
+    dir: &Path,
+    depth: usize,
+    scanned_files: &mut usize,
+    scores: &mut HashMap<String, usize>,
+) {
+    if depth > MAX_SCAN_DEPTH {
+        return;
+    }
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+
+    let mut entries: Vec<_> = entries.flatten().collect();
+    entries.sort_by_key(|entry| entry.file_name());
+
+    for entry in entries {
+        if *scanned_files >= MAX_SCAN_FILES {
+            return;
+        }
+        let path = entry.path();
+        let name = entry.file_name().to_string_lossy().to_string();
+        if name.starts_with('.') {
+            continue;
+        }
+        let Ok(file_type) = entry.file_type() else {
+            continue;
+        };
+
+        if file_type.is_dir() {
+            if !SKIPPED_DIRS.contains(&name.as_str()) {
+                scan_dir(&path, depth + 1, scanned_files, scores);
+            }
+            continue;
+        }
+
+        let is_source = path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .is_some_and(|ext| SOURCE_EXTENSIONS.contains(&ext));
+        if !file_type.is_file() || !is_source {
+            continue;
+        }
+        *scanned_files += 1;
+
+        if let Some(stem) = path.file_stem().and_then(|stem| stem.to_str()) {
+            if is_notable_identifier(stem) {
+                *scores.entry(stem.to_string()).or_default() += FILE_NAME_WEIGHT;
+            }
+        }
+
+        let too_large = entry
+            .metadata()
+            .map(|meta| meta.len() > MAX_FILE_BYTES)
+            .unwrap_or(true);
+        if too_large {
+            continue;
+        }
+        if let Ok(content) = fs::read_to_string(&path) {
+            for identifier in extract_identifiers(&content) {
+                *scores.entry(identifier.to_string()).or_default() += 1;
+            }
+        }
+    }
+}
+
+/// 从源码中切出值得作为热词的标识符
+pub fn extract_identifiers(content: &str) -> impl Iterator<Item = &str> {
+    content
+        .split(|ch: char| !(ch.is_ascii_alphanumeric() || ch == '_'))
+        .filter(|token| is_notable_identifier(token))
+}
+
+/// 驼峰（至少一处小写到大写的转折）或蛇形命名，且不是全大写常量
+fn is_notable_identifier(token: &str) -> bool {
+    let len = token.chars().count();
+    if !(MIN_IDENTIFIER_CHARS..=MAX_HOTWORD_CHARS).contains(&len) {
+        return false;
+    }
+    if !token.starts_with(|ch: char| ch.is_ascii_alphabetic()) {
+        return false;
+    }
+    if !token.chars().any(|ch| ch.is_ascii_lowercase()) {
+        return false;
+    }
+
+    let bytes = token.as_bytes();
+    let has_hump = bytes
+        .windows(2)
+        .any(|pair| pair[0].is_ascii_lowercase() && pair[1].is_ascii_uppercase());
+    let has_snake = bytes.windows(3).any(|triple| {
+        triple[0].is_ascii_alphanumeric() && triple[1] == b'_' && triple[2].is_ascii_alphanumeric()
+    });
+    has_hump || has_snake
+}
+
+/// `Cargo.toml` 与 `package.json` 中声明的包名
+fn package_names(root: &Path) -> Vec<String> {
+    let mut names = Vec::new();
+
+    if let Ok(content) = fs::read_to_string(root.join("Cargo.toml")) {
+        for line in content.lines() {
+            let line = line.trim();
+            if let Some(value) = line.strip_prefix("name") {
+                if let Some(name) = value.trim_start().strip_prefix('=') {
+                    names.push(name.trim().trim_matches('"').to_string());
+                }
+            }
+        }
+    }
+
+    if let Ok(content) = fs::read_to_string(root.join("package.json")) {
+        if let Ok(package) = serde_json::from_str::<serde_json::Value>(&content) {
+            if let Some(name) = package.get("name").and_then(|name| name.as_str()) {
+                names.push(name.to_string());
+            }
+        }
+    }
+
+    names.retain(|name| !name.is_empty() && name.chars().count() <= MAX_HOTWORD_CHARS);
+    names
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn picks_camel_and_snake_case_identifiers() {
+        let source = "struct ExecutionStreamManager; fn split_tts_text() {} const TTS_CHUNK_MAX: usize = 1; let text = String::new();";
+        let identifiers: Vec<&str> = extract_identifiers(source).collect();
+        assert_eq!(
+            identifiers,
+            vec!["ExecutionStreamManager", "split_tts_text"]
+        );
+    }
+
+    #[test]
+    fn vocabulary_wins_and_list_is_capped() {
+        let vocabulary = vec!["MonitorFileOps".to_string(), "reason".to_string()];
+        let project = vec![
+            "reason".to_string(),
+            "a".repeat(MAX_HOTWORD_CHARS + 1),
+            "tts_ws_test".to_string(),
+            "stt_ws_test".to_string(),
+        ];
+        assert_eq!(
+            build_hotwords(vocabulary, project, 3),
+            vec!["MonitorFileOps", "reason", "tts_ws_test"]
+        );
+    }
+}
@@ -0,0 +1,186 @@
+//! 后端消息的多语言目录
+//!
+//! 文案放在 `locales/*.json`，以 `模块.名称` 为键，参数写作 `{name}`。当前语言由配置中
+//! `ui.language` 或系统语言决定，进程内全局生效；缺失的键回退到简体中文，再回退到键本身。
+
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::LazyLock;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[repr(u8)]
+pub enum Locale {
+    #[default]
+    ZhCn,
+    EnUs,
+}
+
+impl Locale {
+    pub const ALL: [Locale; 2] = [Locale::ZhCn, Locale::EnUs];
+
+    /// BCP 47 语言标记
+    pub fn tag(self) -> &'static str {
+        match self {
+            Self::ZhCn => "zh-CN",
+            Self::EnUs => "en-US",
+        }
+    }
+
+    /// 解析语言标记或 POSIX locale，如 `en`、`en-GB`、`zh-Hans-CN`、`zh_CN.UTF-8`；
+    /// 只按主语言匹配，不支持的语言返回 None
+    pub fn parse(value: &str) -> Option<Self> {
+        let language = value
+            .trim()
+            .split(['-', '_', '.', '@'])
+            .next()
+            .unwrap_or_default()
+            .to_lowercase();
+        match language.as_str() {
+            "zh" => Some(Self::ZhCn),
+            "en" => Some(Self::EnUs),
+            _ => None,
+        }
+    }
+
+    /// 从 `LC_ALL` / `LC_MESSAGES` / `LANG` 推断系统语言
+    pub fn from_env() -> Option<Self> {
+        ["LC_ALL", "LC_MESSAGES", "LANG"]
+            .iter()
+            .filter_map(|name| std::env::var(name).ok())
+            .find(|value| !value.is_empty() && value != "C" && value != "POSIX")
+            .and_then(|value| Self::parse(&value))
+    }
+
+    fn catalog(self) -> &'static HashMap<String, String> {
+        match self {
+            Self::ZhCn => &ZH_CN,
+            Self::EnUs => &EN_US,
+        }
+    }
+}
+
+static ZH_CN: LazyLock<HashMap<String, String>> =
+    LazyLock::new(|| load_catalog(include_str!("../locales/zh-CN.json")));
+static EN_US: LazyLock<HashMap<String, String>> =
+    LazyLock::new(|| load_catalog(include_str!("../locales/en-US.json")));
+
+fn load_catalog(source: &str) -> HashMap<String, String> {
+    serde_json::from_str(source).expect("invalid message catalog")
+}
+
+static CURRENT: AtomicU8 = AtomicU8::new(Locale::ZhCn as u8);
+
+/// 切换当前语言，之后产生的消息使用新语言
+pub fn set_locale(locale: Locale) {
+    CURRENT.store(locale as u8, Ordering::Relaxed);
+}
+
+pub fn locale() -> Locale {
+    match CURRENT.load(Ordering::Relaxed) {
+        value if value == Locale::EnUs as u8 => Locale::EnUs,
+        _ => Locale::ZhCn,
+    }
+}
+
+/// 按当前语言取文案并填入参数
+pub fn translate(key: &str, args: &[(&str, &dyn Display)]) -> String {
+    translate_in(locale(), key, args)
+}
+
+pub fn translate_in(locale: Locale, key: &str, args: &[(&str, &dyn Display)]) -> String {
+    let template = locale
+        .catalog()
+        .get(key)
+        .or_else(|| Locale::ZhCn.catalog().get(key))
+        .map(String::as_str)
+        .unwrap_or(key);
+    args.iter()
+        .fold(template.to_string(), |text, (name, value)| {
+            text.replace(&format!("{{{}}}", name), &value.to_string())
+        })
+}
+
+/// 取当前语言的文案：`tr!("stt.no_speech")`、`tr!("net.timeout", what = what, secs = 10)`
+///
+/// 参数列表在宏内部的语句中用完即释放，结果可以跨 `.await` 使用。
+#[macro_export]
+macro_rules! tr {
+    ($key:expr) => {
+        $crate::i18n::translate($key, &[])
+    };
+    ($key:expr, $($name:ident = $value:expr),+ $(,)?) => {{
+        let text = $crate::i18n::translate(
+            $key,
+            &[$((stringify!($name), &$value as &dyn ::std::fmt::Display)),+],
+        );
+        text
+    }};
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use regex::Regex;
+    use std::collections::BTreeSet;
+
+    #[test]
+    fn parses_tags_and_posix_locales() {
+        assert_eq!(Locale::parse("en"), Some(Locale::EnUs));
+        assert_eq!(Locale::parse("en_GB.UTF-8"), Some(Locale::EnUs));
+        assert_eq!(Locale::parse("zh-Hans-CN"), Some(Locale::ZhCn));
+        assert_eq!(Locale::parse("zh_TW"), Some(Locale::ZhCn));
+        assert_eq!(Locale::parse("ja-JP"), None);
+        assert_eq!(Locale::parse(""), None);
+    }
+
+    #[test]
+    fn catalogs_share_keys_and_placeholders() {
+        let placeholder = Regex::new(r"\{(\w+)\}").unwrap();
+        let params = |text: &str| -> BTreeSet<String> {
+            placeholder
+                .captures_iter(text)
+                .map(|captures| captures[1].to_string())
+                .collect()
+        };
+
+        let reference = Locale::ZhCn.catalog();
+        for locale in Locale::ALL {
+            let catalog = locale.catalog();
+            let keys: BTreeSet<_> = catalog.keys().collect();
+            assert_eq!(keys, reference.keys().collect(), "{}", locale.tag());
+            for (key, text) in catalog {
+                assert_eq!(
+                    params(text),
+                    params(&reference[key]),
+                    "{} {}",
+                    locale.tag(),
+                    key
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn fills_arguments_and_falls_back_to_key() {
+        let seconds = 10;
+        let text = translate_in(
+            Locale::EnUs,
+            "net.timeout",
+            &[("what", &"Connecting"), ("secs", &seconds)],
+        );
+        assert_eq!(text, "Connecting timed out (10s)");
+        assert_eq!(
+            translate_in(
+                Locale::ZhCn,
+                "net.timeout",
+                &[("what", &"建连"), ("secs", &seconds)]
+            ),
+            "建连超时（10 秒）"
+        );
+        assert_eq!(
+            translate_in(Locale::EnUs, "no.such_key", &[]),
+            "no.such_key"
+        );
+    }
+}
@@ -0,0 +1,15 @@
+//! 与 Tauri 无关的共享模块，供桌面端命令和 `src/bin` 下的调试工具共同使用
+pub mod audio;
+pub mod diagnostics;
+pub mod error;
+pub mod hotwords;
+pub mod i18n;
+pub mod logging;
+pub mod metrics;
+pub mod net;
+pub mod segmenter;
+pub mod speech_text;
+pub mod subtitles;
+pub mod tts_cache;
+pub mod vad;
+pub mod volcengine;
@@ -0,0 +1,176 @@
+//! 结构化日志：按天滚动写入 JSON 行日志文件，写入前脱敏密钥
+//!
+//! 发布版没有控制台，日志只在文件中；调试版同时输出到标准输出。级别可用环境变量 `REASON_LOG`
+//! 覆盖，语法同 `tracing_subscriber::filter::Targets`，如 `info,reason_desktop=trace`。
+
+use regex::Regex;
+use std::borrow::Cow;
+use std::io::{self, Write};
+use std::path::Path;
+use std::sync::LazyLock;
+use tracing::level_filters::LevelFilter;
+use tracing_appender::non_blocking::WorkerGuard;
+use tracing_appender::rolling::{RollingFileAppender, Rotation};
+use tracing_subscriber::filter::Targets;
+use tracing_subscriber::fmt::{self, MakeWriter};
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+
+/// 日志文件名前缀，滚动后的文件形如 `reason-desktop.2024-01-01.log`
+pub const LOG_FILE_PREFIX: &str = "reason-desktop";
+
+/// 保留的日志文件数（按天滚动，即最近一周）
+const MAX_LOG_FILES: usize = 7;
+
+/// 初始化全局日志，返回的守卫需保持到进程退出，丢弃后文件日志不再落盘
+///
+/// 日志目录无法创建时只输出到控制台。
+pub fn init(dir: &Path) -> Option<WorkerGuard> {
+    let (file_layer, guard) = match file_appender(dir) {
+        Ok(appender) => {
+            let (writer, guard) = tracing_appender::non_blocking(appender);
+            let layer = fmt::layer()
+                .json()
+                .with_current_span(true)
+                .with_span_list(true)
+                .with_writer(Redacting(writer));
+            (Some(layer), Some(guard))
+        }
+        Err(e) => {
+            eprintln!("[LOG] file logging disabled: {}", e);
+            (None, None)
+        }
+    };
+    let console_layer =
+        cfg!(debug_assertions).then(|| fmt::layer().with_writer(Redacting(io::stdout)));
+
+    let installed = tracing_subscriber::registry()
+        .with(file_layer)
+        .with(console_layer)
+        .with(filter())
+        .try_init();
+    if installed.is_err() {
+        return None;
+    }
+
+    // 发布版 panic 直接终止进程，先把现场记进日志
+    let previous = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        tracing::error!(panic = %info, "panicked");
+        previous(info);
+    }));
+    guard
+}
+
+fn file_appender(dir: &Path) -> Result<RollingFileAppender, String> {
+    std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+    RollingFileAppender::builder()
+        .rotation(Rotation::DAILY)
+        .filename_prefix(LOG_FILE_PREFIX)
+        .filename_suffix("log")
+        .max_log_files(MAX_LOG_FILES)
+        .build(dir)
+        .map_err(|e| e.to_string())
+}
+
+fn filter() -> Targets {
+    std::env::var("REASON_LOG")
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .unwrap_or_else(|| {
+            Targets::new()
+                .with_default(LevelFilter::INFO)
+                .with_target("reason_desktop", LevelFilter::DEBUG)
+                .with_target("reason_desktop_lib", LevelFilter::DEBUG)
+        })
+}
+
+/// JSON 中键名含 token / key / secret / password 的字符串值，兼容嵌在字符串字段里被转义的引号
+static JSON_SECRET: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"(?i)(\\?"[\w-]*(?:token|api_?key|access_?key|secret|password|authorization)[\w-]*\\?"\s*:\s*\\?")(?:[^"\\]|\\[^"])*(\\?")"#)
+        .unwrap()
+});
+/// `key=value` / `key: value` 形式的密钥，包括 `X-Api-Access-Key` 请求头
+static KV_SECRET: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"(?i)\b((?:access[_-]?(?:token|key)|api[_-]?key|secret|password)\\?["']?\s*[=:]\s*\\?["']?)[^\s,;&"'\\]+"#)
+        .unwrap()
+});
+static BEARER: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?i)\b(bearer\s+)[\w.~+/=-]+").unwrap());
+
+/// 遮盖文本中的访问令牌、API Key 等密钥
+pub fn redact(text: &str) -> Cow<'_, str> {
+    let mut text = Cow::Borrowed(text);
+    for (pattern, replacement) in [
+        (&*JSON_SECRET, "${1}***${2}"),
+        (&*KV_SECRET, "${1}***"),
+        (&*BEARER, "${1}***"),
+    ] {
+        if let Cow::Owned(replaced) = pattern.replace_all(&text, replacement) {
+            text = Cow::Owned(replaced);
+        }
+    }
+    text
+}
+
+/// 写入前脱敏的 writer；格式化层每条日志只写一次，不会把密钥切成两段
+struct Redacting<M>(M);
+
+impl<'a, M: MakeWriter<'a>> MakeWriter<'a> for Redacting<M> {
+    type Writer = RedactingWriter<M::Writer>;
+
+    fn make_writer(&'a self) -> Self::Writer {
+        RedactingWriter(self.0.make_writer())
+    }
+}
+
+struct RedactingWriter<W>(W);
+
+impl<W: Write> Write for RedactingWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let text = String::from_utf8_lossy(buf);
+        self.0.write_all(redact(&text).as_bytes())?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.0.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn redacts_secrets_in_json_and_text() {
+        let line =
+            r#"{"fields":{"accessToken":"abc123","api_key":"sk-1","maxTokens":512,"appId":"42"}}"#;
+        assert_eq!(
+            redact(line),
+            r#"{"fields":{"accessToken":"***","api_key":"***","maxTokens":512,"appId":"42"}}"#
+        );
+        assert_eq!(
+            redact("X-Api-Access-Key: abc Authorization: Bearer sk-xyz.1 done"),
+            "X-Api-Access-Key: *** Authorization: Bearer *** done"
+        );
+        assert_eq!(redact("access_token=abc&uid=1"), "access_token=***&uid=1");
+        // JSON 日志里消息字段内的引号被转义
+        assert_eq!(
+            redact(r#"{"message":"body {\"access_token\":\"abc\",\"secret\": \"s\"}"}"#),
+            r#"{"message":"body {\"access_token\":\"***\",\"secret\": \"***\"}"}"#
+        );
+        assert_eq!(
+            redact(r#"{"message":"query access_token=\"abc\""}"#),
+            r#"{"message":"query access_token=\"***\""}"#
+        );
+        assert!(matches!(redact("access_token_set=true"), Cow::Borrowed(_)));
+    }
+
+    #[test]
+    fn writer_redacts_each_write() {
+        let mut writer = RedactingWriter(Vec::new());
+        writer.write_all(b"password=hunter2\n").unwrap();
+        assert_eq!(writer.0, b"password=***\n");
+    }
+}
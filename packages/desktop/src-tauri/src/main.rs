@@ -3,31 +3,62 @@
 
 mod commands;
 
-use commands::{agent, config, stt, tts, voice_session, window};
+use commands::{agent, config, diagnostics, stt, tts, voice_metrics, voice_session, window};
+use reason_desktop_lib::logging;
 
 fn main() {
+    //0. 初始化日志，守卫需存活到进程退出
+    let _log_guard = logging::init(&config::get_logs_dir());
+    tracing::info!(version = env!("CARGO_PKG_VERSION"), "starting");
+
     //1. 初始化语音会话
     let voice_session_state =
         voice_session::VoiceSessionState::new().expect("Failed to init voice session");
 
-    //2. 构建 Tauri 应用
+    //2. 按配置确定后端消息的语言
+    config::init_locale();
+
+    //3. 构建 Tauri 应用
     tauri::Builder::default()
         .manage(voice_session_state)
+        .manage(stt::SttStreamState::default())
+        .manage(tts::TtsSubtitleState::default())
+        .manage(tts::TtsSessionState::default())
+        .manage(tts::TtsSpeakingState::default())
+        .manage(tts::TtsConnectionPool::default())
+        .manage(voice_metrics::VoiceMetricsState::default())
         .plugin(tauri_plugin_shell::init())
         .invoke_handler(tauri::generate_handler![
             // 配置管理
             config::get_volcengine_config,
             config::save_volcengine_config,
+            config::sync_ui_language,
+            config::save_ui_language,
             // 语音识别
             stt::stt_transcribe,
+            stt::stt_transcribe_detailed,
+            stt::stt_stream_start,
+            stt::stt_stream_push,
+            stt::stt_stream_finish,
             // Agent 调用
             agent::agent_run,
             // 语音合成
             tts::tts_speak,
             tts::tts_speak_stream,
+            tts::tts_session_open,
+            tts::tts_session_push,
+            tts::tts_session_close,
+            tts::tts_cancel,
+            tts::tts_prewarm,
+            tts::tts_cache_clear,
+            tts::tts_export_subtitles,
             // 语音会话记录
             voice_session::voice_session_start,
             voice_session::voice_session_append,
+            // 语音耗时统计
+            voice_metrics::voice_stats,
+            // 诊断
+            diagnostics::diagnostics_export,
             // 窗口控制
             window::set_window_size,
             window::set_window_position,
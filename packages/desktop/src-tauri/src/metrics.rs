@@ -0,0 +1,337 @@
+//! 语音交互的耗时统计：识别、Agent、合成各环节的耗时按交互归档，存为 JSON 行文件，
+//! 按最近若干次交互计算分位数。
+//!
+//! 一次交互依次经过识别、Agent、合成三个阶段。某个阶段开始时若当前交互已走到该阶段或之后，
+//! 说明是新的一轮，先归档当前交互；合成结束时交互随即归档。
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::PathBuf;
+
+/// 交互所处的阶段，按先后排序
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Phase {
+    Stt,
+    Agent,
+    Tts,
+}
+
+/// 被计时的环节，值均为毫秒
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Stage {
+    /// 命令开始到识别会话建立
+    SttConnect,
+    /// 会话建立到最后一包音频发出（流式识别即录音时长）
+    SttUpload,
+    /// 最后一包音频发出到最终结果；整段识别的提供方为整个识别过程
+    SttFinal,
+    /// 命令开始到 Agent 进程启动
+    AgentSpawn,
+    /// 进程启动到第一行输出
+    AgentFirstOutput,
+    /// 进程启动到退出
+    AgentExit,
+    /// 建立合成连接并开启会话（复用连接时接近 0）
+    TtsConnect,
+    /// 开始合成到第一段音频
+    TtsFirstAudio,
+    /// 开始合成到合成结束
+    TtsFinish,
+}
+
+impl Stage {
+    pub const ALL: [Stage; 9] = [
+        Stage::SttConnect,
+        Stage::SttUpload,
+        Stage::SttFinal,
+        Stage::AgentSpawn,
+        Stage::AgentFirstOutput,
+        Stage::AgentExit,
+        Stage::TtsConnect,
+        Stage::TtsFirstAudio,
+        Stage::TtsFinish,
+    ];
+
+    pub fn phase(self) -> Phase {
+        match self {
+            Stage::SttConnect | Stage::SttUpload | Stage::SttFinal => Phase::Stt,
+            Stage::AgentSpawn | Stage::AgentFirstOutput | Stage::AgentExit => Phase::Agent,
+            Stage::TtsConnect | Stage::TtsFirstAudio | Stage::TtsFinish => Phase::Tts,
+        }
+    }
+}
+
+/// 一次交互的各环节耗时
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InteractionMetrics {
+    pub id: String,
+    /// 开始时间（Unix 毫秒）
+    pub started_at: u64,
+    pub stages: BTreeMap<Stage, u64>,
+}
+
+impl InteractionMetrics {
+    pub fn new(started_at: u64) -> Self {
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            started_at,
+            stages: BTreeMap::new(),
+        }
+    }
+
+    fn latest_phase(&self) -> Option<Phase> {
+        self.stages.keys().map(|stage| stage.phase()).max()
+    }
+}
+
+/// 跟踪进行中的交互，返回需要归档的交互
+#[derive(Debug, Default)]
+pub struct InteractionTracker {
+    current: Option<InteractionMetrics>,
+}
+
+impl InteractionTracker {
+    /// 某个阶段开始；当前交互已走到该阶段或之后时归档并开始新的交互
+    pub fn begin(&mut self, phase: Phase, now_ms: u64) -> Option<InteractionMetrics> {
+        let continues = self
+            .current
+            .as_ref()
+            .is_some_and(|current| current.latest_phase().is_none_or(|latest| latest < phase));
+        if continues {
+            return None;
+        }
+        let previous = self.current.replace(InteractionMetrics::new(now_ms));
+        previous.filter(|previous| !previous.stages.is_empty())
+    }
+
+    /// 记录一个环节的耗时，重试时后一次覆盖前一次
+    pub fn record(&mut self, stage: Stage, ms: u64, now_ms: u64) {
+        self.current
+            .get_or_insert_with(|| InteractionMetrics::new(now_ms))
+            .stages
+            .insert(stage, ms);
+    }
+
+    /// 结束当前交互，没有记录任何环节时返回 None
+    pub fn finish(&mut self) -> Option<InteractionMetrics> {
+        self.current
+            .take()
+            .filter(|current| !current.stages.is_empty())
+    }
+}
+
+/// 单个环节的分位数统计（毫秒）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StageStats {
+    pub count: usize,
+    pub p50: u64,
+    pub p90: u64,
+    pub p99: u64,
+    pub max: u64,
+}
+
+/// 最近若干次交互的统计，只包含有数据的环节
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VoiceStats {
+    pub interactions: usize,
+    pub stages: BTreeMap<Stage, StageStats>,
+}
+
+/// 最近邻秩分位数，`sorted` 需已升序且非空
+fn percentile(sorted: &[u64], p: f64) -> u64 {
+    let rank = (p / 100.0 * sorted.len() as f64).ceil() as usize;
+    sorted[rank.clamp(1, sorted.len()) - 1]
+}
+
+/// 计算各环节的分位数
+pub fn summarize(interactions: &[InteractionMetrics]) -> VoiceStats {
+    let stages = Stage::ALL
+        .into_iter()
+        .filter_map(|stage| {
+            let mut values: Vec<u64> = interactions
+                .iter()
+                .filter_map(|interaction| interaction.stages.get(&stage).copied())
+                .collect();
+            if values.is_empty() {
+                return None;
+            }
+            values.sort_unstable();
+            let stats = StageStats {
+                count: values.len(),
+                p50: percentile(&values, 50.0),
+                p90: percentile(&values, 90.0),
+                p99: percentile(&values, 99.0),
+                max: values[values.len() - 1],
+            };
+            Some((stage, stats))
+        })
+        .collect();
+    VoiceStats {
+        interactions: interactions.len(),
+        stages,
+    }
+}
+
+/// 交互耗时的本地存储，每行一条 JSON；超过两倍上限时压缩为最近 `max_records` 条
+#[derive(Debug, Clone)]
+pub struct MetricsStore {
+    path: PathBuf,
+    max_records: usize,
+}
+
+impl MetricsStore {
+    pub fn new(path: impl Into<PathBuf>, max_records: usize) -> Self {
+        Self {
+            path: path.into(),
+            max_records,
+        }
+    }
+
+    /// 追加一条交互记录
+    pub fn append(&self, interaction: &InteractionMetrics) -> io::Result<()> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let line = serde_json::to_string(interaction).map_err(io::Error::other)?;
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        writeln!(file, "{}", line)?;
+        drop(file);
+
+        let records = self.read_all()?;
+        if records.len() > self.max_records * 2 {
+            self.rewrite(&records[records.len() - self.max_records..])?;
+        }
+        Ok(())
+    }
+
+    /// 最近 `limit` 条交互，旧的在前；损坏的行被跳过
+    pub fn recent(&self, limit: usize) -> io::Result<Vec<InteractionMetrics>> {
+        let mut records = self.read_all()?;
+        let skip = records.len().saturating_sub(limit);
+        records.drain(..skip);
+        Ok(records)
+    }
+
+    fn read_all(&self) -> io::Result<Vec<InteractionMetrics>> {
+        let content = match fs::read_to_string(&self.path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e),
+        };
+        Ok(content
+            .lines()
+            .filter_map(|line| serde_json::from_str(line).ok())
+            .collect())
+    }
+
+    /// 先写临时文件再替换，避免压缩中途退出丢失全部记录
+    fn rewrite(&self, records: &[InteractionMetrics]) -> io::Result<()> {
+        let mut content = String::new();
+        for record in records {
+            content.push_str(&serde_json::to_string(record).map_err(io::Error::other)?);
+            content.push('\n');
+        }
+        let tmp = self.path.with_extension("tmp");
+        fs::write(&tmp, content)?;
+        fs::rename(&tmp, &self.path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn interaction(stages: &[(Stage, u64)]) -> InteractionMetrics {
+        let mut interaction = InteractionMetrics::new(0);
+        interaction.stages.extend(stages.iter().copied());
+        interaction
+    }
+
+    #[test]
+    fn tracker_splits_interactions_by_phase() {
+        let mut tracker = InteractionTracker::default();
+        assert_eq!(tracker.begin(Phase::Stt, 1), None);
+        tracker.record(Stage::SttConnect, 100, 1);
+        // 重试覆盖同一环节
+        tracker.record(Stage::SttConnect, 120, 1);
+        assert_eq!(tracker.begin(Phase::Agent, 2), None);
+        tracker.record(Stage::AgentSpawn, 5, 2);
+        assert_eq!(tracker.begin(Phase::Tts, 3), None);
+        tracker.record(Stage::TtsFirstAudio, 300, 3);
+
+        // 合成之后又开始识别：上一轮归档
+        let previous = tracker.begin(Phase::Stt, 4).unwrap();
+        assert_eq!(previous.started_at, 1);
+        assert_eq!(
+            previous.stages.into_iter().collect::<Vec<_>>(),
+            [
+                (Stage::SttConnect, 120),
+                (Stage::AgentSpawn, 5),
+                (Stage::TtsFirstAudio, 300)
+            ]
+        );
+
+        // 没有记录任何环节的交互不归档
+        assert_eq!(tracker.begin(Phase::Stt, 5), None);
+        tracker.record(Stage::AgentExit, 50, 6);
+        assert!(tracker.begin(Phase::Agent, 7).is_some());
+        tracker.record(Stage::TtsFinish, 900, 8);
+        assert_eq!(tracker.finish().unwrap().stages.len(), 1);
+        assert_eq!(tracker.finish(), None);
+    }
+
+    #[test]
+    fn summarizes_percentiles_per_stage() {
+        let interactions: Vec<_> = (1..=100)
+            .map(|ms| interaction(&[(Stage::SttFinal, ms)]))
+            .chain([interaction(&[(Stage::TtsFirstAudio, 250)])])
+            .collect();
+        let stats = summarize(&interactions);
+        assert_eq!(stats.interactions, 101);
+        assert_eq!(
+            stats.stages[&Stage::SttFinal],
+            StageStats {
+                count: 100,
+                p50: 50,
+                p90: 90,
+                p99: 99,
+                max: 100
+            }
+        );
+        assert_eq!(stats.stages[&Stage::TtsFirstAudio].p50, 250);
+        assert!(!stats.stages.contains_key(&Stage::AgentExit));
+        assert_eq!(summarize(&[]), VoiceStats::default());
+    }
+
+    #[test]
+    fn store_keeps_recent_records() {
+        let dir = std::env::temp_dir().join(format!("voice-metrics-test-{}", uuid::Uuid::new_v4()));
+        let store = MetricsStore::new(dir.join("metrics.jsonl"), 3);
+        assert!(store.recent(10).unwrap().is_empty());
+
+        for ms in 1..=7 {
+            store
+                .append(&interaction(&[(Stage::AgentExit, ms)]))
+                .unwrap();
+        }
+        let mut content = fs::read_to_string(dir.join("metrics.jsonl")).unwrap();
+        content.push_str("not json\n");
+        fs::write(dir.join("metrics.jsonl"), content).unwrap();
+
+        let recent = store.recent(10).unwrap();
+        let values: Vec<_> = recent.iter().map(|r| r.stages[&Stage::AgentExit]).collect();
+        // 第 7 条写入时超过 6 条，压缩为最近 3 条
+        assert_eq!(values, [5, 6, 7]);
+        assert_eq!(store.recent(2).unwrap()[0].stages[&Stage::AgentExit], 6);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,386 @@
+//! 语音接口的网络容错：连接 / 读取 / 整体超时、带抖动的退避重试与错误分类
+//!
+//! 识别与合成的各个提供方共用这一层。错误按鉴权、额度、网络、协议、输入归类，
+//! 只有网络类（DNS、连接重置、超时、服务端 5xx / 繁忙）会被重试。
+
+use crate::tr;
+use serde::Serialize;
+use std::collections::hash_map::RandomState;
+use std::fmt;
+use std::future::Future;
+use std::hash::{BuildHasher, Hasher};
+use std::sync::LazyLock;
+use std::time::Duration;
+use tokio_tungstenite::tungstenite::error::Error as WsError;
+
+/// 建立 TCP / TLS / WebSocket 连接的期限
+pub const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+/// 等待服务端下一条消息或下一段响应的期限
+pub const READ_TIMEOUT: Duration = Duration::from_secs(20);
+/// 单次 HTTP 请求（含上传与下载）的整体期限
+pub const REQUEST_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// 共用的 HTTP 客户端，复用连接并带上各项超时
+static HTTP_CLIENT: LazyLock<reqwest::Client> = LazyLock::new(|| {
+    reqwest::Client::builder()
+        .connect_timeout(CONNECT_TIMEOUT)
+        .read_timeout(READ_TIMEOUT)
+        .timeout(REQUEST_TIMEOUT)
+        .build()
+        .unwrap_or_default()
+});
+
+pub fn http_client() -> reqwest::Client {
+    HTTP_CLIENT.clone()
+}
+
+/// 错误类别
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ErrorKind {
+    /// 鉴权失败：凭据缺失、错误或没有开通对应资源
+    Auth,
+    /// 额度或并发超限
+    Quota,
+    /// 网络不可达、连接中断、超时或服务端临时故障
+    Network,
+    /// 服务端响应不符合协议
+    Protocol,
+    /// 请求本身有误：参数、文本或音频格式
+    Input,
+}
+
+impl ErrorKind {
+    /// 面向用户的类别说明
+    pub fn label(self) -> String {
+        match self {
+            Self::Auth => tr!("error.kind.auth"),
+            Self::Quota => tr!("error.kind.quota"),
+            Self::Network => tr!("error.kind.network"),
+            Self::Protocol => tr!("error.kind.protocol"),
+            Self::Input => tr!("error.kind.input"),
+        }
+    }
+}
+
+/// 已分类的语音接口错误
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VoiceError {
+    pub kind: ErrorKind,
+    pub message: String,
+    /// 换一次连接或稍后重试可能成功
+    pub retryable: bool,
+}
+
+impl VoiceError {
+    /// 网络类默认可重试，其余类别不重试
+    pub fn new(kind: ErrorKind, message: impl Into<String>) -> Self {
+        Self {
+            kind,
+            message: message.into(),
+            retryable: kind == ErrorKind::Network,
+        }
+    }
+
+    pub fn auth(message: impl Into<String>) -> Self {
+        Self::new(ErrorKind::Auth, message)
+    }
+
+    pub fn network(message: impl Into<String>) -> Self {
+        Self::new(ErrorKind::Network, message)
+    }
+
+    pub fn protocol(message: impl Into<String>) -> Self {
+        Self::new(ErrorKind::Protocol, message)
+    }
+
+    pub fn input(message: impl Into<String>) -> Self {
+        Self::new(ErrorKind::Input, message)
+    }
+
+    /// 标记为不可重试，如已向前端下发过音频的会话
+    pub fn fatal(mut self) -> Self {
+        self.retryable = false;
+        self
+    }
+
+    /// 按 HTTP 状态码分类，`detail` 为响应正文
+    pub fn from_status(context: &str, status: u16, detail: &str) -> Self {
+        let kind = match status {
+            401 | 403 => ErrorKind::Auth,
+            429 => ErrorKind::Quota,
+            408 | 500..=599 => ErrorKind::Network,
+            400..=499 => ErrorKind::Input,
+            _ => ErrorKind::Protocol,
+        };
+        let detail = detail.trim();
+        let message = if detail.is_empty() {
+            format!("{}: HTTP {}", context, status)
+        } else {
+            format!("{}: HTTP {} {}", context, status, detail)
+        };
+        Self::new(kind, message)
+    }
+
+    pub fn from_reqwest(context: &str, error: &reqwest::Error) -> Self {
+        if let Some(status) = error.status() {
+            return Self::from_status(context, status.as_u16(), "");
+        }
+        let message = format!("{}: {}", context, error);
+        if error.is_timeout() || error.is_connect() || error.is_request() {
+            Self::network(message)
+        } else if error.is_builder() {
+            Self::input(message)
+        } else {
+            Self::protocol(message)
+        }
+    }
+
+    pub fn from_ws(context: &str, error: &WsError) -> Self {
+        match error {
+            WsError::Http(response) => {
+                let body = response
+                    .body()
+                    .as_deref()
+                    .map(String::from_utf8_lossy)
+                    .unwrap_or_default();
+                Self::from_status(context, response.status().as_u16(), &body)
+            }
+            WsError::ConnectionClosed
+            | WsError::AlreadyClosed
+            | WsError::Io(_)
+            | WsError::Tls(_) => Self::network(format!("{}: {}", context, error)),
+            WsError::Url(_) => Self::input(format!("{}: {}", context, error)),
+            _ => Self::protocol(format!("{}: {}", context, error)),
+        }
+    }
+
+    /// 按火山引擎的业务错误码与错误信息分类
+    ///
+    /// HTTP 合成接口为 30xx，大模型识别与双向合成为 4500xxxx（请求错误）/ 5500xxxx（服务端错误）。
+    pub fn from_service(context: &str, code: Option<i64>, detail: &str) -> Self {
+        let lower = detail.to_lowercase();
+        let mentions = |words: &[&str]| words.iter().any(|word| lower.contains(word));
+        let kind = if mentions(&["quota", "concurrency", "rate limit", "too many"]) {
+            ErrorKind::Quota
+        } else if mentions(&["unauthorized", "permission", "not granted", "access denied"])
+            || mentions(&["invalid token", "invalid appid", "authenticate"])
+        {
+            ErrorKind::Auth
+        } else {
+            match code {
+                Some(3003) => ErrorKind::Quota,
+                Some(3005 | 3006 | 3030 | 3031 | 3032 | 3040) => ErrorKind::Network,
+                Some(3001 | 3010 | 3011 | 3050) => ErrorKind::Input,
+                Some(45_000_081) => ErrorKind::Network,
+                Some(45_000_000..=45_999_999) => ErrorKind::Input,
+                Some(55_000_000..=55_999_999) => ErrorKind::Network,
+                _ => ErrorKind::Protocol,
+            }
+        };
+        let detail = detail.trim();
+        let message = match code {
+            Some(code) => format!("{} (code {}): {}", context, code, detail),
+            None => format!("{}: {}", context, detail),
+        };
+        Self::new(kind, message)
+    }
+}
+
+impl fmt::Display for VoiceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let text = tr!(
+            "error.with_details",
+            message = self.kind.label(),
+            details = self.message
+        );
+        f.write_str(&text)
+    }
+}
+
+impl std::error::Error for VoiceError {}
+
+/// 超时视为可重试的网络错误
+pub async fn with_timeout<T>(
+    duration: Duration,
+    what: &str,
+    future: impl Future<Output = Result<T, VoiceError>>,
+) -> Result<T, VoiceError> {
+    tokio::time::timeout(duration, future).await.map_err(|_| {
+        VoiceError::network(tr!("net.timeout", what = what, secs = duration.as_secs()))
+    })?
+}
+
+/// 指数退避重试策略
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// 最多尝试次数（含第一次）
+    pub attempts: u32,
+    pub base_delay: Duration,
+    pub max_delay: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            attempts: 3,
+            base_delay: Duration::from_millis(300),
+            max_delay: Duration::from_secs(3),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// 第 `retry` 次重试（从 0 开始）前的等待：在退避上限的一半到全部之间随机取值，
+    /// 避免多个请求同时失败后又同时重试
+    pub fn delay(&self, retry: u32) -> Duration {
+        let ceiling = self
+            .base_delay
+            .saturating_mul(1 << retry.min(16))
+            .min(self.max_delay);
+        let half = ceiling / 2;
+        let jitter = random_u64() % (half.as_millis() as u64 + 1);
+        half + Duration::from_millis(jitter)
+    }
+}
+
+fn random_u64() -> u64 {
+    RandomState::new().build_hasher().finish()
+}
+
+/// 执行 `operation`，可重试的错误按策略退避后重试
+pub async fn retry<T, F, Fut>(
+    policy: RetryPolicy,
+    what: &str,
+    mut operation: F,
+) -> Result<T, VoiceError>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, VoiceError>>,
+{
+    let mut attempt = 1;
+    loop {
+        match operation().await {
+            Err(error) if error.retryable && attempt < policy.attempts => {
+                let delay = policy.delay(attempt - 1);
+                tracing::warn!(
+                    attempt,
+                    attempts = policy.attempts,
+                    delay_ms = delay.as_millis() as u64,
+                    error = %error.message,
+                    "{} failed, retrying",
+                    what
+                );
+                tokio::time::sleep(delay).await;
+                attempt += 1;
+            }
+            result => return result,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    #[test]
+    fn classifies_statuses_and_service_codes() {
+        let kind = |status| VoiceError::from_status("TTS", status, "").kind;
+        assert_eq!(kind(401), ErrorKind::Auth);
+        assert_eq!(kind(429), ErrorKind::Quota);
+        assert_eq!(kind(400), ErrorKind::Input);
+        assert_eq!(kind(503), ErrorKind::Network);
+        assert!(VoiceError::from_status("TTS", 502, "").retryable);
+        assert!(!VoiceError::from_status("TTS", 403, "").retryable);
+
+        let service = |code, detail| VoiceError::from_service("识别", code, detail);
+        assert_eq!(service(Some(3003), "").kind, ErrorKind::Quota);
+        assert_eq!(service(Some(3010), "text too long").kind, ErrorKind::Input);
+        assert_eq!(
+            service(Some(55_000_031), "server busy").kind,
+            ErrorKind::Network
+        );
+        assert_eq!(
+            service(Some(45_000_151), "bad audio").kind,
+            ErrorKind::Input
+        );
+        assert_eq!(
+            service(Some(45_000_000), "requested resource not granted").kind,
+            ErrorKind::Auth
+        );
+        assert_eq!(
+            service(None, "quota exceeded for types: concurrency").kind,
+            ErrorKind::Quota
+        );
+        assert_eq!(service(None, "???").kind, ErrorKind::Protocol);
+
+        let error = service(Some(3050), "voice not found");
+        assert_eq!(error.kind, ErrorKind::Input);
+        assert_eq!(
+            error.to_string(),
+            "请求无效（识别 (code 3050): voice not found）"
+        );
+    }
+
+    #[test]
+    fn backoff_is_jittered_and_capped() {
+        let policy = RetryPolicy::default();
+        for retry in 0..8 {
+            let ceiling = (policy.base_delay * (1 << retry)).min(policy.max_delay);
+            for _ in 0..20 {
+                let delay = policy.delay(retry);
+                assert!(delay >= ceiling / 2 && delay <= ceiling, "{:?}", delay);
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn retries_only_transient_errors() {
+        let policy = RetryPolicy {
+            attempts: 3,
+            base_delay: Duration::from_millis(1),
+            max_delay: Duration::from_millis(2),
+        };
+
+        let calls = AtomicU32::new(0);
+        let result = retry(policy, "connect", || async {
+            match calls.fetch_add(1, Ordering::SeqCst) {
+                0 => Err(VoiceError::network("reset")),
+                n => Ok(n),
+            }
+        })
+        .await;
+        assert_eq!(result, Ok(1));
+
+        let calls = AtomicU32::new(0);
+        let result: Result<(), _> = retry(policy, "connect", || async {
+            calls.fetch_add(1, Ordering::SeqCst);
+            Err(VoiceError::auth("bad token"))
+        })
+        .await;
+        assert_eq!(result.unwrap_err().kind, ErrorKind::Auth);
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+        let calls = AtomicU32::new(0);
+        let result: Result<(), _> = retry(policy, "connect", || async {
+            calls.fetch_add(1, Ordering::SeqCst);
+            Err(VoiceError::network("dns"))
+        })
+        .await;
+        assert!(result.is_err());
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn timeouts_are_retryable_network_errors() {
+        let error = with_timeout(Duration::from_millis(5), "等待确认", async {
+            tokio::time::sleep(Duration::from_secs(1)).await;
+            Ok(())
+        })
+        .await
+        .unwrap_err();
+        assert_eq!(error.kind, ErrorKind::Network);
+        assert!(error.retryable);
+    }
+}
@@ -0,0 +1,404 @@
+//! TTS 分段：把流式到达的中英混合文本切成适合逐段合成的句子
+//!
+//! 句末标点凑够 `minChars` 就切分，超过 `maxChars` 时在最近的安全位置强制切分。
+//! 英文标点要看后一个字符才能确定是否断句，因此 `3.14`、`v1.2.0`、`e.g.`、`1,000`
+//! 都不会被切开；强制切分只发生在空白或中日韩字符旁，不会切进单词、数字和引号 / 括号内部。
+
+use serde::{Deserialize, Serialize};
+
+/// 分段长度配置（按字符计）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SegmenterConfig {
+    /// 遇到标点时至少凑够的长度，过短的分段会拖慢合成节奏
+    #[serde(default = "default_min_chars")]
+    pub min_chars: usize,
+    /// 没有合适标点时的强制切分长度；在引号内时放宽到两倍
+    #[serde(default = "default_max_chars")]
+    pub max_chars: usize,
+}
+
+fn default_min_chars() -> usize {
+    12
+}
+
+fn default_max_chars() -> usize {
+    60
+}
+
+impl Default for SegmenterConfig {
+    fn default() -> Self {
+        Self {
+            min_chars: default_min_chars(),
+            max_chars: default_max_chars(),
+        }
+    }
+}
+
+/// 不以句号结尾的英文缩写（小写、不含末尾的点）
+const ABBREVIATIONS: &[&str] = &[
+    "e.g", "i.e", "vs", "mr", "mrs", "ms", "dr", "prof", "st", "jr", "sr", "no", "fig", "cf",
+    "approx", "inc", "ltd", "co", "dept", "est",
+];
+
+/// 后面紧跟小写字母时才视为缩写的词
+const SOFT_ABBREVIATIONS: &[&str] = &["etc", "al"];
+
+/// 等待确认的断句点
+#[derive(Debug)]
+enum Pending {
+    /// 中文标点或换行：一定断句，只等收进后面的右引号 / 右括号
+    Strong,
+    /// 英文逗号、分号、问号、叹号：后面是空白或中文时断句
+    Ascii,
+    /// 英文句号：要看到下一个非空白字符才能排除小数、缩写
+    Period { word: String, seen_space: bool },
+}
+
+/// 流式分段器
+#[derive(Debug)]
+pub struct Segmenter {
+    min_chars: usize,
+    max_chars: usize,
+    chars: Vec<char>,
+    /// 可以强制切分的位置（字符下标）及该处的引号深度
+    safe_cuts: Vec<(usize, usize)>,
+    quote_depth: usize,
+    ascii_quote_open: bool,
+    pending: Option<(Pending, usize)>,
+}
+
+impl Default for Segmenter {
+    fn default() -> Self {
+        Self::new(SegmenterConfig::default())
+    }
+}
+
+impl Segmenter {
+    pub fn new(config: SegmenterConfig) -> Self {
+        let min_chars = config.min_chars.max(1);
+        Self {
+            min_chars,
+            max_chars: config.max_chars.max(min_chars),
+            chars: Vec::new(),
+            safe_cuts: Vec::new(),
+            quote_depth: 0,
+            ascii_quote_open: false,
+            pending: None,
+        }
+    }
+
+    /// 追加文本，返回已完整的分段
+    pub fn push(&mut self, text: &str) -> Vec<String> {
+        let mut segments = Vec::new();
+        for ch in text.chars() {
+            self.push_char(ch, &mut segments);
+        }
+        segments
+    }
+
+    /// 取出剩余文本
+    pub fn take(&mut self) -> Option<String> {
+        self.pending = None;
+        let len = self.chars.len();
+        let segment = self.cut(len);
+        self.quote_depth = 0;
+        self.ascii_quote_open = false;
+        segment
+    }
+
+    fn push_char(&mut self, ch: char, segments: &mut Vec<String>) {
+        if let Some((pending, end)) = self.pending.take() {
+            match self.resolve(pending, end, ch) {
+                Resolution::Wait(pending, end) => {
+                    self.pending = Some((pending, end));
+                    self.append(ch);
+                    return;
+                }
+                Resolution::Split(end) => {
+                    if self.quote_depth == 0 && end >= self.min_chars {
+                        segments.extend(self.cut(end));
+                    }
+                }
+                Resolution::Continue => {}
+            }
+        }
+
+        if self.chars.is_empty() && ch.is_whitespace() {
+            return;
+        }
+        self.append(ch);
+
+        if ch == '\n' {
+            // 换行总是断句，同时清掉未闭合的引号，避免一个孤立的引号影响后文
+            self.quote_depth = 0;
+            self.ascii_quote_open = false;
+            let end = self.chars.len();
+            if end >= self.min_chars {
+                segments.extend(self.cut(end));
+            }
+            return;
+        }
+
+        let end = self.chars.len();
+        self.pending = match ch {
+            '。' | '！' | '？' | '；' | '，' | '、' => Some((Pending::Strong, end)),
+            ',' | ';' | '!' | '?' => Some((Pending::Ascii, end)),
+            '.' => Some((
+                Pending::Period {
+                    word: self.word_before(end - 1),
+                    seen_space: false,
+                },
+                end,
+            )),
+            _ => None,
+        };
+
+        if self.pending.is_none() {
+            self.force_cut(segments);
+        }
+    }
+
+    /// 根据断句点后的字符决定是否切分
+    fn resolve(&self, pending: Pending, end: usize, ch: char) -> Resolution {
+        // 右引号、右括号属于前一句
+        if self.is_closer(ch) {
+            return Resolution::Wait(pending, end + 1);
+        }
+        // 换行本身就会断句
+        if ch == '\n' {
+            return Resolution::Continue;
+        }
+        match pending {
+            Pending::Strong => Resolution::Split(end),
+            Pending::Ascii if ch.is_whitespace() || is_cjk(ch) => Resolution::Split(end),
+            Pending::Ascii => Resolution::Continue,
+            // 省略号
+            Pending::Period { word, .. } if ch == '.' => Resolution::Wait(
+                Pending::Period {
+                    word,
+                    seen_space: false,
+                },
+                end + 1,
+            ),
+            Pending::Period { word, .. } if ch.is_whitespace() => Resolution::Wait(
+                Pending::Period {
+                    word,
+                    seen_space: true,
+                },
+                end,
+            ),
+            Pending::Period { word, seen_space } => {
+                if (seen_space || is_cjk(ch)) && !is_abbreviation(&word, ch) {
+                    Resolution::Split(end)
+                } else {
+                    Resolution::Continue
+                }
+            }
+        }
+    }
+
+    fn append(&mut self, ch: char) {
+        if let Some(&prev) = self.chars.last() {
+            if prev.is_whitespace() || ch.is_whitespace() || is_cjk(prev) || is_cjk(ch) {
+                self.safe_cuts.push((self.chars.len(), self.quote_depth));
+            }
+        }
+        self.chars.push(ch);
+
+        if ch == '"' {
+            if self.ascii_quote_open {
+                self.quote_depth = self.quote_depth.saturating_sub(1);
+            } else {
+                self.quote_depth += 1;
+            }
+            self.ascii_quote_open = !self.ascii_quote_open;
+        } else if is_opener(ch) {
+            self.quote_depth += 1;
+        } else if is_closer_char(ch) {
+            self.quote_depth = self.quote_depth.saturating_sub(1);
+        }
+    }
+
+    fn is_closer(&self, ch: char) -> bool {
+        is_closer_char(ch) || (ch == '"' && self.ascii_quote_open)
+    }
+
+    /// 句号前紧挨着的英文单词（含内部的点，如 `e.g`），小写
+    fn word_before(&self, end: usize) -> String {
+        let start = self.chars[..end]
+            .iter()
+            .rposition(|ch| !(ch.is_ascii_alphanumeric() || *ch == '.'))
+            .map_or(0, |index| index + 1);
+        self.chars[start..end]
+            .iter()
+            .collect::<String>()
+            .to_ascii_lowercase()
+    }
+
+    /// 超过长度上限时在最近的安全位置切分；引号内的位置要到两倍上限才使用
+    fn force_cut(&mut self, segments: &mut Vec<String>) {
+        let len = self.chars.len();
+        if len < self.max_chars {
+            return;
+        }
+        let outside_quote = self
+            .safe_cuts
+            .iter()
+            .rev()
+            .find(|(_, depth)| *depth == 0)
+            .map(|(index, _)| *index);
+        let cut = match outside_quote {
+            Some(index) => Some(index),
+            None if len >= self.max_chars * 2 => {
+                Some(self.safe_cuts.last().map_or(len, |(index, _)| *index))
+            }
+            None => None,
+        };
+        if let Some(index) = cut {
+            segments.extend(self.cut(index));
+        }
+    }
+
+    /// 切出前 `index` 个字符作为一段
+    fn cut(&mut self, index: usize) -> Option<String> {
+        let head: String = self.chars.drain(..index).collect();
+        let leading = self
+            .chars
+            .iter()
+            .take_while(|ch| ch.is_whitespace())
+            .count();
+        self.chars.drain(..leading);
+        let shift = index + leading;
+        self.safe_cuts.retain(|(position, _)| *position > shift);
+        for (position, _) in &mut self.safe_cuts {
+            *position -= shift;
+        }
+
+        let segment = head.trim();
+        (!segment.is_empty()).then(|| segment.to_string())
+    }
+}
+
+enum Resolution {
+    Wait(Pending, usize),
+    Split(usize),
+    Continue,
+}
+
+/// 一次性切分整段文本
+pub fn split(text: &str, config: SegmenterConfig) -> Vec<String> {
+    let mut segmenter = Segmenter::new(config);
+    let mut segments = segmenter.push(text);
+    segments.extend(segmenter.take());
+    segments
+}
+
+fn is_abbreviation(word: &str, next: char) -> bool {
+    if word.is_empty() || word.chars().all(|ch| ch.is_ascii_digit()) {
+        return false;
+    }
+    // 单个字母多为姓名缩写（J. K. Rowling），`I.` 除外
+    if word.len() == 1 {
+        return word != "i";
+    }
+    if ABBREVIATIONS.contains(&word) || word.contains('.') {
+        return true;
+    }
+    SOFT_ABBREVIATIONS.contains(&word) && next.is_ascii_lowercase()
+}
+
+fn is_cjk(ch: char) -> bool {
+    matches!(
+        ch,
+        '\u{3000}'..='\u{303f}'
+            | '\u{3040}'..='\u{30ff}'
+            | '\u{3400}'..='\u{4dbf}'
+            | '\u{4e00}'..='\u{9fff}'
+            | '\u{ac00}'..='\u{d7af}'
+            | '\u{f900}'..='\u{faff}'
+            | '\u{ff00}'..='\u{ffef}'
+    )
+}
+
+fn is_opener(ch: char) -> bool {
+    matches!(ch, '“' | '‘' | '「' | '『' | '《' | '(' | '（' | '[' | '【')
+}
+
+fn is_closer_char(ch: char) -> bool {
+    matches!(ch, '”' | '’' | '」' | '』' | '》' | ')' | '）' | ']' | '】')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 用例格式见 `testdata/segmenter_corpus.txt`
+    fn corpus() -> Vec<(SegmenterConfig, String, Vec<String>)> {
+        let mut cases = Vec::new();
+        let mut config = SegmenterConfig::default();
+        let mut current: Option<(SegmenterConfig, String, Vec<String>)> = None;
+
+        for line in include_str!("testdata/segmenter_corpus.txt").lines() {
+            if let Some(limits) = line.strip_prefix("config:") {
+                let limits: Vec<usize> = limits
+                    .split_whitespace()
+                    .map(|value| value.parse().unwrap())
+                    .collect();
+                config = SegmenterConfig {
+                    min_chars: limits[0],
+                    max_chars: limits[1],
+                };
+            } else if let Some(input) = line.strip_prefix("input: ") {
+                cases.extend(current.take());
+                current = Some((config, input.replace("\\n", "\n"), Vec::new()));
+            } else if let Some(segment) = line.strip_prefix("| ") {
+                current.as_mut().unwrap().2.push(segment.to_string());
+            }
+        }
+        cases.extend(current);
+        cases
+    }
+
+    #[test]
+    fn corpus_one_shot() {
+        let cases = corpus();
+        assert!(cases.len() >= 15);
+        for (config, input, expected) in cases {
+            assert_eq!(split(&input, config), expected, "input: {:?}", input);
+        }
+    }
+
+    #[test]
+    fn corpus_streaming_matches_one_shot() {
+        for (config, input, expected) in corpus() {
+            let chars: Vec<char> = input.chars().collect();
+            for piece_len in 1..=7 {
+                let mut segmenter = Segmenter::new(config);
+                let mut segments = Vec::new();
+                for piece in chars.chunks(piece_len) {
+                    segments.extend(segmenter.push(&piece.iter().collect::<String>()));
+                }
+                segments.extend(segmenter.take());
+                assert_eq!(
+                    segments, expected,
+                    "input: {:?} piece: {}",
+                    input, piece_len
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn segments_stay_within_hard_limit() {
+        let text = "word ".repeat(100) + &"中文".repeat(100);
+        let config = SegmenterConfig {
+            min_chars: 5,
+            max_chars: 20,
+        };
+        for segment in split(&text, config) {
+            assert!(segment.chars().count() <= 20, "{:?}", segment);
+            assert!(!segment.starts_with("ord"), "{:?}", segment);
+        }
+    }
+}
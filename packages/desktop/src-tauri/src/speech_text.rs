@@ -0,0 +1,603 @@
+//! 朗读前的文本规范化：把 Agent 输出的 Markdown 转成适合朗读的口语文本
+//!
+//! 代码块和表格替换为一句概述，标题与列表项转为句子，链接、URL、路径与哈希只读出关键部分；
+//! `full` 级别再把单位与符号展开为读法。概述语句按上下文语言使用中文或英文。
+
+use regex::{Captures, Regex};
+use serde::{Deserialize, Serialize};
+use std::sync::LazyLock;
+
+/// 段落超过该长度仍未换行时，先在句末标点处输出，避免一直等到换行才开始朗读
+const FLUSH_CHARS: usize = 40;
+/// 行内代码超过该长度不再逐字朗读
+const MAX_INLINE_CODE_CHARS: usize = 32;
+
+/// 规范化级别
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NormalizeLevel {
+    /// 原样朗读
+    Off,
+    /// 去除 Markdown 标记，概述代码块与表格，缩短链接、路径与哈希
+    Basic,
+    /// 在 basic 基础上展开单位与符号
+    #[default]
+    Full,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Lang {
+    Zh,
+    En,
+}
+
+impl Lang {
+    /// 中文字符占比不低的文本按中文处理
+    fn detect(text: &str) -> Option<Self> {
+        let cjk = text
+            .chars()
+            .filter(|ch| ('\u{4e00}'..='\u{9fff}').contains(ch))
+            .count();
+        let latin = text.chars().filter(char::is_ascii_alphabetic).count();
+        match (cjk, latin) {
+            (0, 0) => None,
+            (cjk, latin) if cjk * 3 >= latin => Some(Self::Zh),
+            _ => Some(Self::En),
+        }
+    }
+
+    fn sentence_end(self) -> &'static str {
+        match self {
+            Self::Zh => "。",
+            Self::En => ".",
+        }
+    }
+}
+
+/// 跨行的块结构
+enum Block {
+    Prose,
+    Fence {
+        marker: &'static str,
+        language: String,
+        lines: usize,
+    },
+    Table {
+        rows: usize,
+    },
+}
+
+/// 流式规范化：按行处理，代码块与表格结束后整体输出一句概述
+pub struct SpeechNormalizer {
+    level: NormalizeLevel,
+    pending: String,
+    block: Block,
+    lang: Lang,
+}
+
+impl SpeechNormalizer {
+    pub fn new(level: NormalizeLevel) -> Self {
+        Self {
+            level,
+            pending: String::new(),
+            block: Block::Prose,
+            lang: Lang::Zh,
+        }
+    }
+
+    /// 追加一段文本，返回已可朗读的部分；未结束的行留待后续文本
+    pub fn push(&mut self, text: &str) -> String {
+        if self.level == NormalizeLevel::Off {
+            return text.to_string();
+        }
+        self.pending.push_str(text);
+
+        let mut output = String::new();
+        while let Some(end) = self.pending.find('\n') {
+            let line: String = self.pending.drain(..=end).collect();
+            self.line(line.trim_end(), &mut output);
+        }
+
+        if matches!(self.block, Block::Prose) && self.pending.chars().count() >= FLUSH_CHARS {
+            if let Some(cut) = prose_cut(&self.pending) {
+                let head: String = self.pending.drain(..cut).collect();
+                self.prose(&head, &mut output);
+            }
+        }
+        output
+    }
+
+    /// 文本结束：处理剩余内容并补齐未闭合的代码块或表格
+    pub fn finish(&mut self) -> String {
+        if self.level == NormalizeLevel::Off {
+            return String::new();
+        }
+        let mut output = String::new();
+        let rest = std::mem::take(&mut self.pending);
+        if !rest.trim().is_empty() {
+            self.line(rest.trim_end(), &mut output);
+        }
+        self.close_block(&mut output);
+        output
+    }
+
+    fn line(&mut self, line: &str, output: &mut String) {
+        match &mut self.block {
+            Block::Fence { marker, lines, .. } => {
+                if line.trim_start().starts_with(*marker) {
+                    self.close_block(output);
+                } else {
+                    *lines += 1;
+                }
+                return;
+            }
+            Block::Table { rows } => {
+                if is_table_row(line) {
+                    if !is_table_separator(line) {
+                        *rows += 1;
+                    }
+                    return;
+                }
+                self.close_block(output);
+            }
+            Block::Prose => {}
+        }
+
+        let trimmed = line.trim();
+        for marker in ["```", "~~~"] {
+            if let Some(info) = trimmed.strip_prefix(marker) {
+                self.block = Block::Fence {
+                    marker,
+                    language: info.split_whitespace().next().unwrap_or("").to_string(),
+                    lines: 0,
+                };
+                return;
+            }
+        }
+        if is_table_row(trimmed) {
+            // 首行为表头，不计入行数
+            self.block = Block::Table { rows: 0 };
+            return;
+        }
+        if trimmed.is_empty() {
+            output.push('\n');
+            return;
+        }
+        if is_horizontal_rule(trimmed) {
+            return;
+        }
+        self.prose(trimmed, output);
+    }
+
+    /// 普通文本行：去掉行首标记，标题和列表项补上句末标点
+    fn prose(&mut self, line: &str, output: &mut String) {
+        let (text, is_item) = strip_line_marker(line.trim());
+        // 按去掉链接、路径等内容后的文字判断语言，避免 URL 把中文句子判成英文
+        let plain = normalize_inline(text, NormalizeLevel::Basic, self.lang);
+        if let Some(lang) = Lang::detect(&plain) {
+            self.lang = lang;
+        }
+        let text = normalize_inline(text, self.level, self.lang);
+        let text = text.trim();
+        if text.is_empty() {
+            return;
+        }
+
+        output.push_str(text);
+        if is_item && !text.ends_with(is_sentence_end) {
+            output.push_str(self.lang.sentence_end());
+        }
+        output.push('\n');
+    }
+
+    fn close_block(&mut self, output: &mut String) {
+        let summary = match std::mem::replace(&mut self.block, Block::Prose) {
+            Block::Prose => return,
+            Block::Fence {
+                language, lines, ..
+            } => code_summary(&language, lines, self.lang),
+            Block::Table { rows } => table_summary(rows, self.lang),
+        };
+        output.push_str(&summary);
+        output.push('\n');
+    }
+}
+
+/// 一次性规范化整段文本
+pub fn normalize(text: &str, level: NormalizeLevel) -> String {
+    let mut normalizer = SpeechNormalizer::new(level);
+    let mut output = normalizer.push(text);
+    output.push_str(&normalizer.finish());
+    output
+}
+
+fn is_sentence_end(ch: char) -> bool {
+    matches!(
+        ch,
+        '。' | '！' | '？' | '；' | '：' | '.' | '!' | '?' | ';' | ':'
+    )
+}
+
+/// 在最后一个句末标点之后切开；行内代码或链接未闭合时不切
+fn prose_cut(text: &str) -> Option<usize> {
+    let trimmed = text.trim_start();
+    if trimmed.starts_with(['`', '~', '|']) {
+        return None;
+    }
+    let (index, ch) = text
+        .char_indices()
+        .rfind(|(_, ch)| matches!(ch, '。' | '！' | '？' | '!' | '?'))?;
+    let head = &text[..index + ch.len_utf8()];
+    let balanced = head.matches('`').count().is_multiple_of(2)
+        && head.matches('[').count() == head.matches(']').count()
+        && head.matches('(').count() == head.matches(')').count();
+    balanced.then_some(head.len())
+}
+
+fn is_table_row(line: &str) -> bool {
+    let line = line.trim();
+    line.len() > 1 && line.starts_with('|')
+}
+
+fn is_table_separator(line: &str) -> bool {
+    line.trim()
+        .chars()
+        .all(|ch| matches!(ch, '|' | '-' | ':' | ' '))
+}
+
+fn is_horizontal_rule(line: &str) -> bool {
+    let compact: String = line.chars().filter(|ch| !ch.is_whitespace()).collect();
+    compact.len() >= 3
+        && ['-', '*', '_']
+            .iter()
+            .any(|marker| compact.chars().all(|ch| ch == *marker))
+}
+
+/// 去掉标题、引用、列表与任务列表标记，返回正文及是否为标题 / 列表项
+fn strip_line_marker(line: &str) -> (&str, bool) {
+    let mut text = line;
+    while let Some(rest) = text.strip_prefix('>') {
+        text = rest.trim_start();
+    }
+
+    let heading = text.trim_start_matches('#');
+    if heading.len() < text.len() && text.len() - heading.len() <= 6 && heading.starts_with(' ') {
+        return (heading.trim(), true);
+    }
+
+    let mut item = None;
+    for bullet in ["- ", "* ", "+ "] {
+        if let Some(rest) = text.strip_prefix(bullet) {
+            item = Some(rest);
+        }
+    }
+    if item.is_none() {
+        let digits = text.chars().take_while(char::is_ascii_digit).count();
+        if digits > 0 {
+            let rest = &text[digits..];
+            item = rest.strip_prefix(". ").or_else(|| rest.strip_prefix(") "));
+        }
+    }
+
+    match item {
+        Some(rest) => {
+            let rest = rest.trim_start();
+            let rest = ["[ ] ", "[x] ", "[X] "]
+                .iter()
+                .find_map(|task| rest.strip_prefix(task))
+                .unwrap_or(rest);
+            (rest.trim(), true)
+        }
+        None => (text, false),
+    }
+}
+
+fn code_summary(language: &str, lines: usize, lang: Lang) -> String {
+    let language = display_language(language);
+    match lang {
+        Lang::Zh => match language {
+            Some(language) => format!("这里有一段 {} 行的 {} 代码。", lines, language),
+            None => format!("这里有一段 {} 行的代码。", lines),
+        },
+        Lang::En => match language {
+            Some(language) => format!("Here is a {}-line {} snippet.", lines, language),
+            None => format!("Here is a {}-line code snippet.", lines),
+        },
+    }
+}
+
+fn table_summary(rows: usize, lang: Lang) -> String {
+    match lang {
+        Lang::Zh => format!("这里有一个 {} 行的表格。", rows),
+        Lang::En if rows == 1 => "Here is a table with 1 row.".to_string(),
+        Lang::En => format!("Here is a table with {} rows.", rows),
+    }
+}
+
+/// 代码块语言标记的读法
+fn display_language(language: &str) -> Option<&str> {
+    let name = match language.to_ascii_lowercase().as_str() {
+        "" | "text" | "plain" | "txt" => return None,
+        "rs" | "rust" => "Rust",
+        "ts" | "typescript" => "TypeScript",
+        "tsx" => "TSX",
+        "js" | "javascript" => "JavaScript",
+        "jsx" => "JSX",
+        "py" | "python" => "Python",
+        "sh" | "bash" | "shell" | "zsh" | "console" => "Shell",
+        "json" => "JSON",
+        "toml" => "TOML",
+        "yaml" | "yml" => "YAML",
+        "go" => "Go",
+        "java" => "Java",
+        "sql" => "SQL",
+        "diff" => "diff",
+        "html" => "HTML",
+        "css" => "CSS",
+        _ => language,
+    };
+    Some(name)
+}
+
+static IMAGE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"!\[([^\]]*)\]\([^)]*\)").unwrap());
+static LINK: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\[([^\]]+)\]\([^)]*\)").unwrap());
+static INLINE_CODE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"`+([^`]+)`+").unwrap());
+static URL: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)\b(?:https?|wss?|ftp)://(?:www\.)?([a-z0-9.-]+)[^\s<>()\]）】，。]*").unwrap()
+});
+static PATH: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(?:[A-Za-z]:\\|~|\.{1,2})?(?:[/\\]?[\w.@-]+)?(?:[/\\][\w.@-]+){2,}[/\\]?|[A-Za-z]:\\[\w.@\\-]+",
+    )
+    .unwrap()
+});
+static HASH: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\b[0-9a-fA-F]{12,}\b").unwrap());
+static HTML_TAG: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"</?[A-Za-z][A-Za-z0-9]*(?:\s[^<>]*)?/?>").unwrap());
+static EMPHASIS: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"\*\*([^*]+)\*\*|__([^_]+)__|~~([^~]+)~~|\*([^*\s][^*]*)\*").unwrap()
+});
+static UNIT: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(\d+(?:\.\d+)?)\s?(%|ms|KB|kB|MB|GB|TB|km|kg|cm|mm|kHz|MHz|GHz|Hz|°C|℃)([^A-Za-z]|$)",
+    )
+    .unwrap()
+});
+static RANGE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(\d)\s?[~～]\s?(\d)").unwrap());
+static ISSUE_NUMBER: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(^|\s)#(\d+)\b").unwrap());
+
+/// 行内规范化：链接、代码、URL、路径、哈希与强调标记，full 级别再展开单位与符号
+fn normalize_inline(text: &str, level: NormalizeLevel, lang: Lang) -> String {
+    let text = IMAGE.replace_all(text, "$1");
+    let text = LINK.replace_all(&text, "$1");
+    let text = INLINE_CODE.replace_all(&text, |caps: &Captures| {
+        let code = caps[1].trim();
+        if code.chars().count() <= MAX_INLINE_CODE_CHARS {
+            code.to_string()
+        } else {
+            match lang {
+                Lang::Zh => "一段代码".to_string(),
+                Lang::En => "some code".to_string(),
+            }
+        }
+    });
+    let text = URL.replace_all(&text, "$1");
+    let text = PATH.replace_all(&text, |caps: &Captures| {
+        let path = caps[0].trim_end_matches(['/', '\\']);
+        // 2024/01/02 之类的日期不是路径
+        if !path.chars().any(|ch| ch.is_ascii_alphabetic()) {
+            return caps[0].to_string();
+        }
+        path.rsplit(['/', '\\']).next().unwrap_or(path).to_string()
+    });
+    let text = HASH.replace_all(&text, |caps: &Captures| {
+        let hash = &caps[0];
+        // 纯数字或纯字母的长串不是哈希
+        if hash.chars().any(|ch| ch.is_ascii_digit())
+            && hash.chars().any(|ch| ch.is_ascii_alphabetic())
+        {
+            match lang {
+                Lang::Zh => "一个哈希值".to_string(),
+                Lang::En => "a hash".to_string(),
+            }
+        } else {
+            hash.to_string()
+        }
+    });
+    let text = HTML_TAG.replace_all(&text, " ");
+    let text = EMPHASIS.replace_all(&text, |caps: &Captures| {
+        (1..=4)
+            .find_map(|index| caps.get(index))
+            .map(|inner| inner.as_str().to_string())
+            .unwrap_or_default()
+    });
+
+    let text = if level == NormalizeLevel::Full {
+        expand_symbols(&text, lang)
+    } else {
+        text.into_owned()
+    };
+    text.replace(['`', '*'], "")
+}
+
+fn expand_symbols(text: &str, lang: Lang) -> String {
+    let text = UNIT.replace_all(text, |caps: &Captures| {
+        let (number, unit, tail) = (&caps[1], &caps[2], &caps[3]);
+        let spoken = match lang {
+            Lang::Zh => match unit {
+                "%" => format!("百分之{}", number),
+                unit => format!("{}{}", number, unit_name_zh(unit)),
+            },
+            Lang::En => format!("{} {}", number, unit_name_en(unit)),
+        };
+        format!("{}{}", spoken, tail)
+    });
+    let text = RANGE.replace_all(&text, |caps: &Captures| match lang {
+        Lang::Zh => format!("{}到{}", &caps[1], &caps[2]),
+        Lang::En => format!("{} to {}", &caps[1], &caps[2]),
+    });
+    let text = ISSUE_NUMBER.replace_all(&text, |caps: &Captures| match lang {
+        Lang::Zh => format!("{}第{}号", &caps[1], &caps[2]),
+        Lang::En => format!("{}number {}", &caps[1], &caps[2]),
+    });
+
+    let symbols: &[(&str, &str)] = match lang {
+        Lang::Zh => &[
+            ("->", "到"),
+            ("=>", "到"),
+            ("→", "到"),
+            (">=", "大于等于"),
+            ("≥", "大于等于"),
+            ("<=", "小于等于"),
+            ("≤", "小于等于"),
+            ("!=", "不等于"),
+            ("≠", "不等于"),
+            ("&&", "和"),
+            ("&", "和"),
+            ("|", " "),
+        ],
+        Lang::En => &[
+            ("->", " to "),
+            ("=>", " to "),
+            ("→", " to "),
+            (">=", " at least "),
+            ("≥", " at least "),
+            ("<=", " at most "),
+            ("≤", " at most "),
+            ("!=", " is not "),
+            ("≠", " is not "),
+            ("&&", " and "),
+            ("&", " and "),
+            ("|", " "),
+        ],
+    };
+    let mut text = text.into_owned();
+    for (symbol, spoken) in symbols {
+        text = text.replace(symbol, spoken);
+    }
+    collapse_spaces(&text)
+}
+
+fn collapse_spaces(text: &str) -> String {
+    let mut output = String::with_capacity(text.len());
+    for ch in text.chars() {
+        if ch == ' ' && (output.is_empty() || output.ends_with(' ')) {
+            continue;
+        }
+        output.push(ch);
+    }
+    output.trim_end().to_string()
+}
+
+fn unit_name_zh(unit: &str) -> &'static str {
+    match unit {
+        "ms" => "毫秒",
+        "KB" | "kB" => "千字节",
+        "MB" => "兆字节",
+        "GB" => "吉字节",
+        "TB" => "太字节",
+        "km" => "公里",
+        "kg" => "公斤",
+        "cm" => "厘米",
+        "mm" => "毫米",
+        "Hz" => "赫兹",
+        "kHz" => "千赫兹",
+        "MHz" => "兆赫兹",
+        "GHz" => "吉赫兹",
+        _ => "摄氏度",
+    }
+}
+
+fn unit_name_en(unit: &str) -> &'static str {
+    match unit {
+        "%" => "percent",
+        "ms" => "milliseconds",
+        "KB" | "kB" => "kilobytes",
+        "MB" => "megabytes",
+        "GB" => "gigabytes",
+        "TB" => "terabytes",
+        "km" => "kilometers",
+        "kg" => "kilograms",
+        "cm" => "centimeters",
+        "mm" => "millimeters",
+        "Hz" => "hertz",
+        "kHz" => "kilohertz",
+        "MHz" => "megahertz",
+        "GHz" => "gigahertz",
+        _ => "degrees Celsius",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn summarizes_code_blocks_and_tables() {
+        let text = "修改如下：\n```rust\nfn main() {\n    println!(\"hi\");\n}\n```\n| 文件 | 行数 |\n|---|---|\n| a.rs | 10 |\n| b.rs | 20 |\n完成。";
+        assert_eq!(
+            normalize(text, NormalizeLevel::Basic),
+            "修改如下：\n这里有一段 3 行的 Rust 代码。\n这里有一个 2 行的表格。\n完成。\n"
+        );
+
+        let english = "Run this:\n```\nls\n";
+        assert_eq!(
+            normalize(english, NormalizeLevel::Basic),
+            "Run this:\nHere is a 1-line code snippet.\n"
+        );
+    }
+
+    #[test]
+    fn turns_headings_and_lists_into_sentences() {
+        let text = "## 变更\n- 修复 **缓存** 失效\n- [x] 更新文档。\n1. Run `cargo test`";
+        assert_eq!(
+            normalize(text, NormalizeLevel::Basic),
+            "变更。\n修复 缓存 失效。\n更新文档。\nRun cargo test.\n"
+        );
+    }
+
+    #[test]
+    fn shortens_links_paths_and_hashes() {
+        let text = "见 [文档](https://example.com/a) 和 https://www.github.com/org/repo/pull/1 ，改动在 src/commands/tts.rs 的提交 3f9c2a1b4d5e6f70 中";
+        assert_eq!(
+            normalize(text, NormalizeLevel::Basic),
+            "见 文档 和 github.com ，改动在 tts.rs 的提交 一个哈希值 中\n"
+        );
+    }
+
+    #[test]
+    fn full_level_expands_units_and_symbols() {
+        assert_eq!(
+            normalize(
+                "延迟从 120ms 降到 80 ms，提升 30%，3~5 次",
+                NormalizeLevel::Full
+            ),
+            "延迟从 120毫秒 降到 80毫秒，提升 百分之30，3到5 次\n"
+        );
+        assert_eq!(
+            normalize("Size went from 2MB -> 512KB, see #42", NormalizeLevel::Full),
+            "Size went from 2 megabytes to 512 kilobytes, see number 42\n"
+        );
+        assert_eq!(normalize("提升 30%", NormalizeLevel::Basic), "提升 30%\n");
+    }
+
+    #[test]
+    fn streaming_matches_one_shot() {
+        let text = "先看 `SpeechNormalizer`。\n```ts\nconst a = 1;\n```\n- 第一项\n- 第二项\n这是一个很长很长的段落，用来测试没有换行时也能在句末标点处先行输出，不必等待整段结束。后面还有很多内容，一直没有换行，直到最后才会结束！然后继续说下去";
+        let expected = normalize(text, NormalizeLevel::Full);
+
+        let mut normalizer = SpeechNormalizer::new(NormalizeLevel::Full);
+        let mut streamed = String::new();
+        let chars: Vec<char> = text.chars().collect();
+        for piece in chars.chunks(3) {
+            streamed.push_str(&normalizer.push(&piece.iter().collect::<String>()));
+        }
+        let before_finish = streamed.clone();
+        streamed.push_str(&normalizer.finish());
+
+        assert!(before_finish.contains("不必等待整段结束。"));
+        // 流式输出可能在更早的句末处换行，朗读内容一致即可
+        assert_eq!(streamed.replace('\n', ""), expected.replace('\n', ""));
+        assert_eq!(normalize(text, NormalizeLevel::Off), text);
+    }
+}
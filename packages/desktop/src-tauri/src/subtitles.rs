@@ -0,0 +1,497 @@
+//! TTS 分句字幕：解析 TTSSentenceStart / TTSSentenceEnd 事件，按音频字节位置对齐，
+//! 并导出 SRT / WebVTT。
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// 字级时间戳，单位毫秒（相对整段音频开头）
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SubtitleWord {
+    pub text: String,
+    pub start_ms: u64,
+    pub end_ms: u64,
+}
+
+/// 一句字幕；字节位置为该句音频在整段音频流中的区间
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SubtitleCue {
+    pub index: usize,
+    pub text: String,
+    pub start_byte: usize,
+    pub end_byte: usize,
+    pub start_ms: u64,
+    pub end_ms: u64,
+    pub words: Vec<SubtitleWord>,
+}
+
+/// 句子事件中的文本与字词时间（秒，相对本句开头）
+#[derive(Debug, Default, PartialEq)]
+struct SentencePayload {
+    text: String,
+    words: Vec<(String, f64, f64)>,
+}
+
+/// 兼容顶层字段与 `res_params` 内嵌两种写法
+fn parse_sentence_payload(payload: &[u8]) -> SentencePayload {
+    let Ok(value) = serde_json::from_slice::<Value>(payload) else {
+        return SentencePayload::default();
+    };
+    let body = match value.get("res_params") {
+        Some(inner) if inner.is_object() => inner,
+        _ => &value,
+    };
+
+    let text = body["text"].as_str().unwrap_or_default().to_string();
+    let words = body["words"]
+        .as_array()
+        .map(|words| {
+            words
+                .iter()
+                .filter_map(|word| {
+                    let text = word["word"].as_str().or_else(|| word["text"].as_str())?;
+                    let start = word["startTime"]
+                        .as_f64()
+                        .or_else(|| word["start_time"].as_f64())?;
+                    let end = word["endTime"]
+                        .as_f64()
+                        .or_else(|| word["end_time"].as_f64())?;
+                    Some((text.to_string(), start, end))
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    SentencePayload { text, words }
+}
+
+/// 字幕计时依据的音频编码，与合成请求的输出格式对应
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AudioEncoding {
+    Mp3,
+    OggOpus,
+    /// 16 位单声道 PCM
+    Pcm {
+        sample_rate: u32,
+    },
+    /// 44 字节文件头 + 16 位单声道 PCM
+    Wav {
+        sample_rate: u32,
+    },
+}
+
+impl AudioEncoding {
+    /// 由输出格式名（mp3 / ogg_opus / pcm / wav）解析
+    pub fn from_format(format: &str, sample_rate: u32) -> Option<Self> {
+        match format {
+            "mp3" => Some(Self::Mp3),
+            "ogg_opus" => Some(Self::OggOpus),
+            "pcm" => Some(Self::Pcm { sample_rate }),
+            "wav" => Some(Self::Wav { sample_rate }),
+            _ => None,
+        }
+    }
+}
+
+/// 按编码累计已接收音频的时长
+#[derive(Debug)]
+enum AudioClock {
+    Mp3(Mp3Clock),
+    Ogg(OggClock),
+    Pcm {
+        bytes_per_second: u64,
+        header_left: usize,
+        data_bytes: u64,
+    },
+}
+
+impl Default for AudioClock {
+    fn default() -> Self {
+        Self::Mp3(Mp3Clock::default())
+    }
+}
+
+impl AudioClock {
+    fn new(encoding: AudioEncoding) -> Self {
+        let pcm = |sample_rate: u32, header_left| Self::Pcm {
+            bytes_per_second: sample_rate as u64 * 2,
+            header_left,
+            data_bytes: 0,
+        };
+        match encoding {
+            AudioEncoding::Mp3 => Self::Mp3(Mp3Clock::default()),
+            AudioEncoding::OggOpus => Self::Ogg(OggClock::default()),
+            AudioEncoding::Pcm { sample_rate } => pcm(sample_rate, 0),
+            AudioEncoding::Wav { sample_rate } => pcm(sample_rate, 44),
+        }
+    }
+
+    fn push(&mut self, data: &[u8]) {
+        match self {
+            Self::Mp3(clock) => clock.push(data),
+            Self::Ogg(clock) => clock.push(data),
+            Self::Pcm {
+                header_left,
+                data_bytes,
+                ..
+            } => {
+                let skipped = (*header_left).min(data.len());
+                *header_left -= skipped;
+                *data_bytes += (data.len() - skipped) as u64;
+            }
+        }
+    }
+
+    fn elapsed_ms(&self) -> u64 {
+        match self {
+            Self::Mp3(clock) => clock.elapsed_ms(),
+            Self::Ogg(clock) => clock.elapsed_ms(),
+            Self::Pcm {
+                bytes_per_second,
+                data_bytes,
+                ..
+            } => data_bytes * 1000 / (*bytes_per_second).max(1),
+        }
+    }
+}
+
+/// 按 Ogg 页头的 granule position 计时（Opus 固定以 48kHz 计采样数）
+#[derive(Debug, Default)]
+struct OggClock {
+    pending: Vec<u8>,
+    granule: u64,
+}
+
+impl OggClock {
+    fn push(&mut self, data: &[u8]) {
+        self.pending.extend_from_slice(data);
+
+        let mut offset = 0;
+        while offset + 27 <= self.pending.len() {
+            let page = &self.pending[offset..];
+            if !page.starts_with(b"OggS") {
+                offset += 1;
+                continue;
+            }
+            let segments = page[26] as usize;
+            if page.len() < 27 + segments {
+                break;
+            }
+            let body_len: usize = page[27..27 + segments]
+                .iter()
+                .map(|len| *len as usize)
+                .sum();
+            if page.len() < 27 + segments + body_len {
+                break;
+            }
+            let granule = i64::from_le_bytes(page[6..14].try_into().unwrap_or_default());
+            // -1 表示该页没有完整的包结束
+            if granule >= 0 {
+                self.granule = self.granule.max(granule as u64);
+            }
+            offset += 27 + segments + body_len;
+        }
+        self.pending.drain(..offset);
+    }
+
+    fn elapsed_ms(&self) -> u64 {
+        self.granule / 48
+    }
+}
+
+/// 按 MP3 帧头累计已接收音频的时长，跨分块拼接不完整的帧
+#[derive(Debug, Default)]
+struct Mp3Clock {
+    pending: Vec<u8>,
+    elapsed_us: u64,
+}
+
+impl Mp3Clock {
+    fn push(&mut self, data: &[u8]) {
+        self.pending.extend_from_slice(data);
+
+        let mut offset = 0;
+        while offset + 4 <= self.pending.len() {
+            let rest = &self.pending[offset..];
+            if rest.starts_with(b"ID3") {
+                if rest.len() < 10 {
+                    break;
+                }
+                // ID3v2 标签长度为 4 个 7 位的 syncsafe 整数
+                let size = rest[6..10]
+                    .iter()
+                    .fold(0usize, |size, byte| (size << 7) | (*byte & 0x7f) as usize);
+                if rest.len() < 10 + size {
+                    break;
+                }
+                offset += 10 + size;
+                continue;
+            }
+
+            match parse_mp3_header(rest) {
+                Some((frame_len, duration_us)) => {
+                    if rest.len() < frame_len {
+                        break;
+                    }
+                    offset += frame_len;
+                    self.elapsed_us += duration_us;
+                }
+                None => offset += 1,
+            }
+        }
+        self.pending.drain(..offset);
+    }
+
+    fn elapsed_ms(&self) -> u64 {
+        self.elapsed_us / 1000
+    }
+}
+
+/// 解析 Layer III 帧头，返回帧长（字节）与帧时长（微秒）
+fn parse_mp3_header(header: &[u8]) -> Option<(usize, u64)> {
+    const MPEG1_BITRATES: [u32; 15] = [
+        0, 32, 40, 48, 56, 64, 80, 96, 112, 128, 160, 192, 224, 256, 320,
+    ];
+    const MPEG2_BITRATES: [u32; 15] =
+        [0, 8, 16, 24, 32, 40, 48, 56, 64, 80, 96, 112, 128, 144, 160];
+
+    if header[0] != 0xff || header[1] & 0xe0 != 0xe0 {
+        return None;
+    }
+    let version = (header[1] >> 3) & 0x03;
+    let layer = (header[1] >> 1) & 0x03;
+    let bitrate_index = (header[2] >> 4) as usize;
+    let sample_rate_index = ((header[2] >> 2) & 0x03) as usize;
+    let padding = ((header[2] >> 1) & 0x01) as usize;
+    if version == 1 || layer != 1 || bitrate_index == 0 || bitrate_index == 15 {
+        return None;
+    }
+
+    let (sample_rates, bitrates, samples_per_frame) = match version {
+        3 => ([44100, 48000, 32000], &MPEG1_BITRATES, 1152),
+        2 => ([22050, 24000, 16000], &MPEG2_BITRATES, 576),
+        _ => ([11025, 12000, 8000], &MPEG2_BITRATES, 576),
+    };
+    let sample_rate = *sample_rates.get(sample_rate_index)?;
+    let bitrate = bitrates[bitrate_index] * 1000;
+
+    let frame_len = (samples_per_frame / 8 * bitrate / sample_rate) as usize + padding;
+    let duration_us = samples_per_frame as u64 * 1_000_000 / sample_rate as u64;
+    Some((frame_len, duration_us))
+}
+
+/// 跟踪一次流式合成的音频与句子事件，生成字幕
+#[derive(Debug, Default)]
+pub struct SubtitleTrack {
+    clock: AudioClock,
+    bytes: usize,
+    cues: Vec<SubtitleCue>,
+    open: Option<SubtitleCue>,
+}
+
+impl SubtitleTrack {
+    /// MP3 音频的字幕跟踪
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_encoding(encoding: AudioEncoding) -> Self {
+        Self {
+            clock: AudioClock::new(encoding),
+            ..Self::default()
+        }
+    }
+
+    /// 是否尚未记录任何音频或句子
+    pub fn is_empty(&self) -> bool {
+        self.bytes == 0 && self.cues.is_empty() && self.open.is_none()
+    }
+
+    /// 记录一段已下发给前端的音频
+    pub fn push_audio(&mut self, audio: &[u8]) {
+        self.bytes += audio.len();
+        self.clock.push(audio);
+    }
+
+    /// 句子开始：以当前音频位置作为起点，返回尚未结束的字幕
+    pub fn start_sentence(&mut self, payload: &[u8]) -> SubtitleCue {
+        if self.open.is_some() {
+            self.finish_open();
+        }
+        let parsed = parse_sentence_payload(payload);
+        let cue = SubtitleCue {
+            index: self.cues.len() + 1,
+            text: parsed.text,
+            start_byte: self.bytes,
+            end_byte: self.bytes,
+            start_ms: self.clock.elapsed_ms(),
+            end_ms: self.clock.elapsed_ms(),
+            words: Vec::new(),
+        };
+        self.open = Some(cue.clone());
+        cue
+    }
+
+    /// 句子结束：补全结束位置与字词时间，返回完整字幕
+    pub fn end_sentence(&mut self, payload: &[u8]) -> Option<SubtitleCue> {
+        let parsed = parse_sentence_payload(payload);
+        let mut cue = self.open.take().unwrap_or_else(|| SubtitleCue {
+            index: self.cues.len() + 1,
+            text: String::new(),
+            start_byte: self.bytes,
+            end_byte: self.bytes,
+            start_ms: self.clock.elapsed_ms(),
+            end_ms: self.clock.elapsed_ms(),
+            words: Vec::new(),
+        });
+        if cue.text.is_empty() {
+            cue.text = parsed.text;
+        }
+        cue.words = parsed
+            .words
+            .into_iter()
+            .map(|(text, start, end)| SubtitleWord {
+                text,
+                start_ms: cue.start_ms + (start.max(0.0) * 1000.0).round() as u64,
+                end_ms: cue.start_ms + (end.max(0.0) * 1000.0).round() as u64,
+            })
+            .collect();
+        cue.end_byte = self.bytes;
+        let words_end = cue.words.iter().map(|word| word.end_ms).max().unwrap_or(0);
+        cue.end_ms = self.clock.elapsed_ms().max(words_end).max(cue.start_ms);
+
+        if cue.text.is_empty() {
+            return None;
+        }
+        self.cues.push(cue.clone());
+        Some(cue)
+    }
+
+    fn finish_open(&mut self) {
+        let _ = self.end_sentence(b"{}");
+    }
+
+    /// 结束跟踪，未收到结束事件的句子以当前位置收尾
+    pub fn finish(mut self) -> Vec<SubtitleCue> {
+        if self.open.is_some() {
+            self.finish_open();
+        }
+        self.cues
+    }
+}
+
+fn format_timestamp(ms: u64, separator: char) -> String {
+    format!(
+        "{:02}:{:02}:{:02}{}{:03}",
+        ms / 3_600_000,
+        ms / 60_000 % 60,
+        ms / 1000 % 60,
+        separator,
+        ms % 1000
+    )
+}
+
+pub fn to_srt(cues: &[SubtitleCue]) -> String {
+    cues.iter()
+        .enumerate()
+        .map(|(index, cue)| {
+            format!(
+                "{}\n{} --> {}\n{}\n",
+                index + 1,
+                format_timestamp(cue.start_ms, ','),
+                format_timestamp(cue.end_ms, ','),
+                cue.text
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+pub fn to_webvtt(cues: &[SubtitleCue]) -> String {
+    let mut output = String::from("WEBVTT\n");
+    for cue in cues {
+        output.push_str(&format!(
+            "\n{} --> {}\n{}\n",
+            format_timestamp(cue.start_ms, '.'),
+            format_timestamp(cue.end_ms, '.'),
+            cue.text
+        ));
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// MPEG-2 Layer III, 32kbps, 24kHz：每帧 96 字节、24 毫秒
+    fn mp3_frames(count: usize) -> Vec<u8> {
+        let mut frame = [0u8; 96];
+        frame[..4].copy_from_slice(&[0xff, 0xf3, 0x44, 0xc4]);
+        frame.repeat(count)
+    }
+
+    #[test]
+    fn mp3_clock_counts_frames_across_chunks() {
+        let mut audio = b"ID3\x03\x00\x00\x00\x00\x00\x04tag!".to_vec();
+        audio.extend(mp3_frames(10));
+
+        let mut clock = Mp3Clock::default();
+        for chunk in audio.chunks(37) {
+            clock.push(chunk);
+        }
+        assert_eq!(clock.elapsed_ms(), 240);
+    }
+
+    #[test]
+    fn pcm_and_ogg_clocks() {
+        let mut wav = AudioClock::new(AudioEncoding::Wav { sample_rate: 16000 });
+        wav.push(&[0; 30]);
+        wav.push(&[0; 14 + 3200]);
+        assert_eq!(wav.elapsed_ms(), 100);
+
+        // 两个 Ogg 页，granule 分别为 960 与 -1（后者不推进时钟）
+        let page = |granule: i64| {
+            let mut page = b"OggS\x00\x00".to_vec();
+            page.extend_from_slice(&granule.to_le_bytes());
+            page.extend_from_slice(&[0; 12]);
+            page.extend_from_slice(&[1, 5, 1, 2, 3, 4, 5]);
+            page
+        };
+        let mut audio = page(960);
+        audio.extend(page(-1));
+        let mut ogg = AudioClock::new(AudioEncoding::OggOpus);
+        for chunk in audio.chunks(7) {
+            ogg.push(chunk);
+        }
+        assert_eq!(ogg.elapsed_ms(), 20);
+    }
+
+    #[test]
+    fn sentences_align_with_audio_and_export() {
+        let mut track = SubtitleTrack::new();
+        let first = track.start_sentence(r#"{"text":"你好。"}"#.as_bytes());
+        assert_eq!((first.start_byte, first.start_ms), (0, 0));
+        track.push_audio(&mp3_frames(50));
+        let words = r#"{"words":[{"word":"你","startTime":0.1,"endTime":0.5},{"word":"好","startTime":0.5,"endTime":1.0}]}"#;
+        let first = track.end_sentence(words.as_bytes()).unwrap();
+        assert_eq!((first.end_byte, first.end_ms), (4800, 1200));
+        assert_eq!(first.words[1].end_ms, 1000);
+
+        track.start_sentence(r#"{"res_params":{"text":"再见"}}"#.as_bytes());
+        track.push_audio(&mp3_frames(25));
+        let cues = track.finish();
+        assert_eq!(cues.len(), 2);
+        assert_eq!((cues[1].start_ms, cues[1].end_ms), (1200, 1800));
+
+        assert_eq!(
+            to_srt(&cues),
+            "1\n00:00:00,000 --> 00:00:01,200\n你好。\n\n2\n00:00:01,200 --> 00:00:01,800\n再见\n"
+        );
+        assert_eq!(
+            to_webvtt(&cues),
+            "WEBVTT\n\n00:00:00.000 --> 00:00:01.200\n你好。\n\n00:00:01.200 --> 00:00:01.800\n再见\n"
+        );
+    }
+}
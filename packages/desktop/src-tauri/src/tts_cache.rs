@@ -0,0 +1,390 @@
+//! TTS 音频磁盘缓存：以文本、音色、音频参数与资源 ID 的 SHA-256 摘要寻址，
+//! 超出容量或过期的条目按最近使用时间淘汰。
+//!
+//! 每条缓存的音频为 `<摘要>.bin`（格式由合成参数决定，已计入摘要），流式合成的字幕另存为同名 `.json`。
+
+use crate::subtitles::SubtitleCue;
+use serde::Serialize;
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+const AUDIO_EXT: &str = "bin";
+const CUES_EXT: &str = "json";
+/// 写入用的临时文件，写入中途退出时会残留
+const TEMP_EXT: &str = "tmp";
+/// 超过这个时间的临时文件视为残留；更新的可能正被另一次写入使用
+const STALE_TEMP_AGE: Duration = Duration::from_secs(60);
+
+/// 决定合成结果的全部输入
+pub struct TtsCacheKey<'a> {
+    pub text: &'a str,
+    pub voice_type: &'a str,
+    /// 影响音频内容的参数（格式、采样率、语速等）
+    pub audio_params: &'a Value,
+    pub resource_id: &'a str,
+}
+
+impl TtsCacheKey<'_> {
+    /// 十六进制摘要，作为缓存文件名
+    pub fn digest(&self) -> String {
+        let audio_params = self.audio_params.to_string();
+        let mut hasher = Sha256::new();
+        for part in [
+            self.resource_id,
+            self.voice_type,
+            audio_params.as_str(),
+            self.text,
+        ] {
+            // 带上长度，避免字段拼接产生歧义
+            hasher.update((part.len() as u64).to_le_bytes());
+            hasher.update(part.as_bytes());
+        }
+        hasher
+            .finalize()
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect()
+    }
+}
+
+/// 一条缓存的合成结果
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CachedSpeech {
+    pub audio: Vec<u8>,
+    pub cues: Vec<SubtitleCue>,
+}
+
+/// 缓存条目数与占用字节数
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TtsCacheStats {
+    pub entries: usize,
+    pub bytes: u64,
+}
+
+struct CacheEntry {
+    key: String,
+    bytes: u64,
+    last_used: SystemTime,
+}
+
+#[derive(Debug, Clone)]
+pub struct TtsCache {
+    dir: PathBuf,
+    max_bytes: u64,
+    max_age: Duration,
+}
+
+impl TtsCache {
+    pub fn new(dir: impl Into<PathBuf>, max_bytes: u64, max_age: Duration) -> Self {
+        Self {
+            dir: dir.into(),
+            max_bytes,
+            max_age,
+        }
+    }
+
+    fn path(&self, key: &str, ext: &str) -> PathBuf {
+        self.dir.join(format!("{}.{}", key, ext))
+    }
+
+    fn expired(&self, last_used: SystemTime) -> bool {
+        SystemTime::now()
+            .duration_since(last_used)
+            .is_ok_and(|age| age > self.max_age)
+    }
+
+    /// 读取缓存；命中时刷新最近使用时间，过期条目直接删除
+    pub fn get(&self, key: &str) -> Option<CachedSpeech> {
+        let audio_path = self.path(key, AUDIO_EXT);
+        let last_used = fs::metadata(&audio_path)
+            .and_then(|metadata| metadata.modified())
+            .ok()?;
+        if self.expired(last_used) {
+            self.remove(key);
+            return None;
+        }
+
+        let audio = fs::read(&audio_path).ok()?;
+        let cues = fs::read(self.path(key, CUES_EXT))
+            .ok()
+            .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+            .unwrap_or_default();
+        let _ = fs::File::options()
+            .write(true)
+            .open(&audio_path)
+            .and_then(|file| file.set_modified(SystemTime::now()));
+
+        Some(CachedSpeech { audio, cues })
+    }
+
+    /// 写入缓存并按容量与时长淘汰旧条目
+    pub fn put(&self, key: &str, speech: &CachedSpeech) -> io::Result<()> {
+        if speech.audio.is_empty() {
+            return Ok(());
+        }
+        fs::create_dir_all(&self.dir)?;
+
+        if !speech.cues.is_empty() {
+            let cues = serde_json::to_vec(&speech.cues)?;
+            write_atomic(&self.path(key, CUES_EXT), &cues)?;
+        }
+        // 音频最后写入：音频文件存在即表示条目完整
+        write_atomic(&self.path(key, AUDIO_EXT), &speech.audio)?;
+
+        self.prune().map(|_| ())
+    }
+
+    /// 删除过期条目，再从最久未使用的开始删除直到不超过容量，返回剩余统计
+    pub fn prune(&self) -> io::Result<TtsCacheStats> {
+        self.remove_temp_files(Some(STALE_TEMP_AGE))?;
+        let mut entries = self.entries()?;
+        entries.sort_by_key(|entry| entry.last_used);
+
+        let mut total: u64 = entries.iter().map(|entry| entry.bytes).sum();
+        let mut remaining = entries.len();
+        for entry in &entries {
+            if total <= self.max_bytes && !self.expired(entry.last_used) {
+                continue;
+            }
+            self.remove(&entry.key);
+            total -= entry.bytes;
+            remaining -= 1;
+        }
+
+        Ok(TtsCacheStats {
+            entries: remaining,
+            bytes: total,
+        })
+    }
+
+    pub fn stats(&self) -> io::Result<TtsCacheStats> {
+        let entries = self.entries()?;
+        Ok(TtsCacheStats {
+            entries: entries.len(),
+            bytes: entries.iter().map(|entry| entry.bytes).sum(),
+        })
+    }
+
+    /// 清空缓存，返回被删除的条目统计
+    pub fn clear(&self) -> io::Result<TtsCacheStats> {
+        let stats = self.stats()?;
+        for entry in self.entries()? {
+            self.remove(&entry.key);
+        }
+        self.remove_temp_files(None)?;
+        Ok(stats)
+    }
+
+    fn remove(&self, key: &str) {
+        let _ = fs::remove_file(self.path(key, AUDIO_EXT));
+        let _ = fs::remove_file(self.path(key, CUES_EXT));
+    }
+
+    /// 删除写入中途残留的临时文件，`min_age` 为 None 时全部删除
+    fn remove_temp_files(&self, min_age: Option<Duration>) -> io::Result<()> {
+        for path in self.files_with_ext(TEMP_EXT)? {
+            let stale = min_age.is_none_or(|min_age| {
+                fs::metadata(&path)
+                    .and_then(|metadata| metadata.modified())
+                    .is_ok_and(|modified| {
+                        SystemTime::now()
+                            .duration_since(modified)
+                            .is_ok_and(|age| age > min_age)
+                    })
+            });
+            if stale {
+                let _ = fs::remove_file(&path);
+            }
+        }
+        Ok(())
+    }
+
+    /// 缓存目录下指定扩展名的文件，目录不存在时为空
+    fn files_with_ext(&self, ext: &str) -> io::Result<Vec<PathBuf>> {
+        let read_dir = match fs::read_dir(&self.dir) {
+            Ok(read_dir) => read_dir,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e),
+        };
+
+        let mut paths = Vec::new();
+        for dir_entry in read_dir {
+            let path = dir_entry?.path();
+            if path.extension().and_then(|path_ext| path_ext.to_str()) == Some(ext) {
+                paths.push(path);
+            }
+        }
+        Ok(paths)
+    }
+
+    /// 扫描缓存目录；字幕文件计入同名音频条目的占用
+    fn entries(&self) -> io::Result<Vec<CacheEntry>> {
+        let mut entries = Vec::new();
+        for path in self.files_with_ext(AUDIO_EXT)? {
+            let Some(key) = path.file_stem().and_then(|stem| stem.to_str()) else {
+                continue;
+            };
+            let Ok(metadata) = fs::metadata(&path) else {
+                continue;
+            };
+            let cues_bytes = fs::metadata(self.path(key, CUES_EXT))
+                .map(|metadata| metadata.len())
+                .unwrap_or(0);
+            entries.push(CacheEntry {
+                key: key.to_string(),
+                bytes: metadata.len() + cues_bytes,
+                last_used: metadata.modified()?,
+            });
+        }
+        Ok(entries)
+    }
+}
+
+/// 先写临时文件再改名，避免读到写了一半的条目；临时文件名各不相同，并发写入互不覆盖
+fn write_atomic(path: &Path, data: &[u8]) -> io::Result<()> {
+    let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+    let tmp_path = path.with_file_name(format!(
+        "{}.{}.{}",
+        file_name,
+        uuid::Uuid::new_v4().simple(),
+        TEMP_EXT
+    ));
+    fs::write(&tmp_path, data)?;
+    fs::rename(&tmp_path, path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn temp_cache(max_bytes: u64, max_age: Duration) -> TtsCache {
+        let dir = std::env::temp_dir().join(format!("tts-cache-test-{}", uuid::Uuid::new_v4()));
+        TtsCache::new(dir, max_bytes, max_age)
+    }
+
+    fn speech(len: usize) -> CachedSpeech {
+        CachedSpeech {
+            audio: vec![7; len],
+            cues: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn key_covers_every_input() {
+        let params = json!({ "format": "mp3", "sample_rate": 24000 });
+        let key = TtsCacheKey {
+            text: "你好",
+            voice_type: "voice_a",
+            audio_params: &params,
+            resource_id: "seed-tts-1.0",
+        };
+        assert_eq!(key.digest().len(), 64);
+        assert_eq!(key.digest(), key.digest());
+
+        let other_voice = TtsCacheKey {
+            voice_type: "voice_b",
+            ..key
+        };
+        assert_ne!(key.digest(), other_voice.digest());
+
+        let faster = json!({ "format": "mp3", "sample_rate": 24000, "speech_rate": 10 });
+        let other_params = TtsCacheKey {
+            audio_params: &faster,
+            ..key
+        };
+        assert_ne!(key.digest(), other_params.digest());
+    }
+
+    #[test]
+    fn round_trips_audio_and_cues() {
+        let cache = temp_cache(1 << 20, Duration::from_secs(3600));
+        let cue = SubtitleCue {
+            index: 1,
+            text: "你好".to_string(),
+            start_byte: 0,
+            end_byte: 3,
+            start_ms: 0,
+            end_ms: 24,
+            words: Vec::new(),
+        };
+        let stored = CachedSpeech {
+            audio: vec![1, 2, 3],
+            cues: vec![cue],
+        };
+
+        assert_eq!(cache.get("abc"), None);
+        cache.put("abc", &stored).unwrap();
+        assert_eq!(cache.get("abc"), Some(stored));
+
+        let removed = cache.clear().unwrap();
+        assert_eq!(removed.entries, 1);
+        assert_eq!(cache.get("abc"), None);
+    }
+
+    #[test]
+    fn evicts_least_recently_used_over_capacity() {
+        let cache = temp_cache(250, Duration::from_secs(3600));
+        cache.put("old", &speech(100)).unwrap();
+        let past = SystemTime::now() - Duration::from_secs(60);
+        fs::File::options()
+            .write(true)
+            .open(cache.path("old", AUDIO_EXT))
+            .and_then(|file| file.set_modified(past))
+            .unwrap();
+        cache.put("mid", &speech(100)).unwrap();
+        cache.put("new", &speech(100)).unwrap();
+
+        assert_eq!(cache.get("old"), None);
+        assert!(cache.get("mid").is_some());
+        assert_eq!(
+            cache.stats().unwrap(),
+            TtsCacheStats {
+                entries: 2,
+                bytes: 200
+            }
+        );
+        cache.clear().unwrap();
+    }
+
+    #[test]
+    fn expired_entries_are_dropped() {
+        let cache = temp_cache(1 << 20, Duration::ZERO);
+        cache.put("stale", &speech(10)).unwrap();
+        std::thread::sleep(Duration::from_millis(20));
+        assert_eq!(cache.get("stale"), None);
+        assert_eq!(cache.stats().unwrap().entries, 0);
+    }
+
+    #[test]
+    fn leftover_temp_files_are_removed() {
+        let cache = temp_cache(1 << 20, Duration::from_secs(3600));
+        cache.put("abc", &speech(10)).unwrap();
+        let leftover = cache
+            .dir
+            .join(format!("abc.{}.1234.{}", AUDIO_EXT, TEMP_EXT));
+        let in_flight = cache
+            .dir
+            .join(format!("abc.{}.5678.{}", CUES_EXT, TEMP_EXT));
+        fs::write(&leftover, b"partial").unwrap();
+        fs::write(&in_flight, b"partial").unwrap();
+        fs::File::options()
+            .write(true)
+            .open(&leftover)
+            .and_then(|file| file.set_modified(SystemTime::now() - STALE_TEMP_AGE * 2))
+            .unwrap();
+
+        // 淘汰只删除残留已久的临时文件，不影响进行中的写入
+        assert_eq!(cache.prune().unwrap().entries, 1);
+        assert!(!leftover.exists());
+        assert!(in_flight.exists());
+
+        cache.clear().unwrap();
+        assert_eq!(fs::read_dir(&cache.dir).unwrap().count(), 0);
+    }
+}
@@ -0,0 +1,294 @@
+//! 基于能量的语音活动检测（VAD），输入为 16 位单声道 PCM
+//!
+//! 按 20ms 分帧计算能量（dBFS），底噪随安静帧跟踪；能量高出底噪 `marginDb` 的帧视为语音。
+//! 连续语音达到 `minSpeechMs` 才算开口，开口后静音超过 `endSilenceMs` 判定一句话结束。
+
+use crate::audio::pcm16_samples;
+use serde::{Deserialize, Serialize};
+use std::ops::Range;
+
+const FRAME_MS: u64 = 20;
+/// 安静帧更新底噪的平滑系数
+const NOISE_SMOOTHING: f32 = 0.1;
+/// 未开口时疑似语音的帧也缓慢抬高底噪，持续的背景噪声约 2 秒后不再被当作语音
+const NOISE_CREEP: f32 = 0.02;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VadConfig {
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    /// 高出底噪多少 dB 视为语音
+    #[serde(default = "default_margin_db")]
+    pub margin_db: f32,
+    /// 绝对门限（dBFS），低于此能量一律视为静音
+    #[serde(default = "default_min_db")]
+    pub min_db: f32,
+    #[serde(default = "default_min_speech_ms")]
+    pub min_speech_ms: u64,
+    #[serde(default = "default_end_silence_ms")]
+    pub end_silence_ms: u64,
+    /// 裁剪静音时在语音前后保留的时长
+    #[serde(default = "default_padding_ms")]
+    pub padding_ms: u64,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+fn default_margin_db() -> f32 {
+    12.0
+}
+
+fn default_min_db() -> f32 {
+    -50.0
+}
+
+fn default_min_speech_ms() -> u64 {
+    120
+}
+
+fn default_end_silence_ms() -> u64 {
+    800
+}
+
+fn default_padding_ms() -> u64 {
+    200
+}
+
+impl Default for VadConfig {
+    fn default() -> Self {
+        Self {
+            enabled: default_enabled(),
+            margin_db: default_margin_db(),
+            min_db: default_min_db(),
+            min_speech_ms: default_min_speech_ms(),
+            end_silence_ms: default_end_silence_ms(),
+            padding_ms: default_padding_ms(),
+        }
+    }
+}
+
+/// 语音起止事件，时间为语音本身的起止位置（相对输入开头，毫秒）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VadEvent {
+    SpeechStart { at_ms: u64 },
+    SpeechEnd { at_ms: u64 },
+}
+
+/// 流式 VAD，按到达顺序喂入采样
+#[derive(Debug)]
+pub struct Vad {
+    config: VadConfig,
+    frame_len: usize,
+    /// 不足一帧的剩余采样
+    pending: Vec<i16>,
+    /// 奇数字节输入留下的半个采样
+    pending_byte: Option<u8>,
+    frames: u64,
+    noise_db: f32,
+    speaking: bool,
+    /// 当前连续语音的起始帧与帧数
+    voiced_start: u64,
+    voiced_run: u64,
+    /// 开口后连续静音的帧数
+    silent_run: u64,
+}
+
+impl Vad {
+    pub fn new(config: VadConfig, sample_rate: u32) -> Self {
+        Self {
+            config,
+            frame_len: (sample_rate as u64 * FRAME_MS / 1000).max(1) as usize,
+            pending: Vec::new(),
+            pending_byte: None,
+            frames: 0,
+            noise_db: config.min_db,
+            speaking: false,
+            voiced_start: 0,
+            voiced_run: 0,
+            silent_run: 0,
+        }
+    }
+
+    /// 是否处于开口状态
+    pub fn is_speaking(&self) -> bool {
+        self.speaking
+    }
+
+    /// 喂入 16 位小端 PCM 字节
+    pub fn push_bytes(&mut self, bytes: &[u8]) -> Vec<VadEvent> {
+        let mut joined;
+        let bytes = match self.pending_byte.take() {
+            Some(first) => {
+                joined = vec![first];
+                joined.extend_from_slice(bytes);
+                &joined[..]
+            }
+            None => bytes,
+        };
+        let samples = pcm16_samples(bytes);
+        if bytes.len() % 2 == 1 {
+            self.pending_byte = bytes.last().copied();
+        }
+        self.push(&samples)
+    }
+
+    pub fn push(&mut self, samples: &[i16]) -> Vec<VadEvent> {
+        let mut events = Vec::new();
+        self.pending.extend_from_slice(samples);
+        let frames = self.pending.len() / self.frame_len;
+        for index in 0..frames {
+            let start = index * self.frame_len;
+            let db = frame_db(&self.pending[start..start + self.frame_len]);
+            events.extend(self.process_frame(db));
+        }
+        self.pending.drain(..frames * self.frame_len);
+        events
+    }
+
+    fn process_frame(&mut self, db: f32) -> Option<VadEvent> {
+        let frame = self.frames;
+        self.frames += 1;
+
+        let voiced = db > self.config.min_db && db > self.noise_db + self.config.margin_db;
+        if !voiced {
+            self.noise_db = if db < self.noise_db {
+                db.max(self.config.min_db - 30.0)
+            } else {
+                self.noise_db + (db - self.noise_db) * NOISE_SMOOTHING
+            };
+        } else if !self.speaking {
+            self.noise_db += (db - self.noise_db) * NOISE_CREEP;
+        }
+
+        if voiced {
+            if self.voiced_run == 0 {
+                self.voiced_start = frame;
+            }
+            self.voiced_run += 1;
+            self.silent_run = 0;
+        } else {
+            self.voiced_run = 0;
+            if self.speaking {
+                self.silent_run += 1;
+            }
+        }
+
+        if !self.speaking && self.voiced_run * FRAME_MS >= self.config.min_speech_ms {
+            self.speaking = true;
+            return Some(VadEvent::SpeechStart {
+                at_ms: self.voiced_start * FRAME_MS,
+            });
+        }
+        if self.speaking && self.silent_run * FRAME_MS >= self.config.end_silence_ms {
+            self.speaking = false;
+            self.silent_run = 0;
+            return Some(VadEvent::SpeechEnd {
+                at_ms: (self.frames - self.config.end_silence_ms / FRAME_MS) * FRAME_MS,
+            });
+        }
+        None
+    }
+}
+
+/// 一帧的能量（dBFS）
+fn frame_db(frame: &[i16]) -> f32 {
+    let energy: f64 = frame
+        .iter()
+        .map(|&sample| {
+            let sample = sample as f64 / 32768.0;
+            sample * sample
+        })
+        .sum();
+    let rms = (energy / frame.len().max(1) as f64).sqrt();
+    20.0 * rms.max(1e-9).log10() as f32
+}
+
+/// 整段录音中语音所在的采样区间（含前后留白），没有语音时返回 None
+pub fn speech_range(samples: &[i16], sample_rate: u32, config: &VadConfig) -> Option<Range<usize>> {
+    let mut vad = Vad::new(*config, sample_rate);
+    let (mut start_ms, mut end_ms) = (None, None);
+    for event in vad.push(samples) {
+        match event {
+            VadEvent::SpeechStart { at_ms } => {
+                start_ms.get_or_insert(at_ms);
+                end_ms = None;
+            }
+            VadEvent::SpeechEnd { at_ms } => end_ms = Some(at_ms),
+        }
+    }
+    let start_ms = start_ms?;
+
+    let to_sample = |ms: u64| (ms * sample_rate as u64 / 1000) as usize;
+    let start = to_sample(start_ms.saturating_sub(config.padding_ms));
+    let end = match end_ms {
+        Some(end_ms) if !vad.is_speaking() => to_sample(end_ms + config.padding_ms),
+        _ => samples.len(),
+    };
+    Some(start..end.min(samples.len()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RATE: u32 = 16000;
+
+    /// 指定时长的正弦音（语音替身）或低电平噪声
+    fn signal(ms: u64, amplitude: f64) -> Vec<i16> {
+        let len = (RATE as u64 * ms / 1000) as usize;
+        (0..len)
+            .map(|index| {
+                let phase = index as f64 * 2.0 * std::f64::consts::PI * 220.0 / RATE as f64;
+                (phase.sin() * amplitude * 32767.0) as i16
+            })
+            .collect()
+    }
+
+    fn clip(parts: &[(u64, f64)]) -> Vec<i16> {
+        parts
+            .iter()
+            .flat_map(|&(ms, amplitude)| signal(ms, amplitude))
+            .collect()
+    }
+
+    #[test]
+    fn rejects_silence_and_short_clicks() {
+        let config = VadConfig::default();
+        assert_eq!(speech_range(&clip(&[(2000, 0.001)]), RATE, &config), None);
+        // 60ms 的咔哒声短于 minSpeechMs
+        let click = clip(&[(500, 0.001), (60, 0.5), (500, 0.001)]);
+        assert_eq!(speech_range(&click, RATE, &config), None);
+    }
+
+    #[test]
+    fn trims_leading_and_trailing_silence() {
+        let config = VadConfig::default();
+        let samples = clip(&[(1000, 0.002), (1500, 0.3), (2000, 0.002)]);
+        let range = speech_range(&samples, RATE, &config).unwrap();
+
+        let ms = |sample: usize| sample as u64 * 1000 / RATE as u64;
+        assert_eq!(ms(range.start), 800);
+        assert_eq!(ms(range.end), 2700);
+    }
+
+    #[test]
+    fn streaming_emits_start_and_end_once() {
+        let mut vad = Vad::new(VadConfig::default(), RATE);
+        let samples = clip(&[(300, 0.002), (1000, 0.3), (1500, 0.002)]);
+        let bytes: Vec<u8> = samples.iter().flat_map(|s| s.to_le_bytes()).collect();
+
+        // 奇数字节的分块也能正确拼接
+        let events: Vec<VadEvent> = bytes.chunks(333).flat_map(|c| vad.push_bytes(c)).collect();
+        assert_eq!(
+            events,
+            vec![
+                VadEvent::SpeechStart { at_ms: 300 },
+                VadEvent::SpeechEnd { at_ms: 1300 },
+            ]
+        );
+        assert!(!vad.is_speaking());
+    }
+}
@@ -0,0 +1,904 @@
+//! 本地模拟火山引擎语音服务，用于无凭证的端到端测试
+//!
+//! 同一端口同时提供：
+//! - `GET /api/v3/tts/bidirection`：双向流式 TTS 事件协议
+//! - `GET /api/v3/sauc/*`：SAUC 大模型流式识别
+//! - `POST /api/v1/tts`：HTTP 一次性合成
+//!
+//! 行为由 [`MockScenario`] 控制（可从 JSON 文件加载）。以 `mock` 特性构建的客户端设置
+//! `VOLC_MOCK_ADDR` 后即会连到这里，见 [`super::endpoint`]。发布构建不包含本模块。
+
+use super::protocol::{Compression, Event, Frame, MessageType, Serialization};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use futures_util::{SinkExt, StreamExt};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::task::JoinHandle;
+use tokio::time::{sleep, Duration};
+use tokio_tungstenite::tungstenite::handshake::derive_accept_key;
+use tokio_tungstenite::tungstenite::protocol::Role;
+use tokio_tungstenite::tungstenite::Message;
+use tokio_tungstenite::WebSocketStream;
+use uuid::Uuid;
+
+const MAX_HEAD_BYTES: usize = 16 * 1024;
+const MAX_BODY_BYTES: usize = 1024 * 1024;
+/// 非法请求对应的错误码
+const INVALID_REQUEST_CODE: u32 = 45_000_001;
+
+type MockSocket = WebSocketStream<TcpStream>;
+
+/// 模拟服务的行为
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct MockScenario {
+    /// 每段合成文本返回的音频块数
+    pub audio_chunks: usize,
+    /// 每个音频块的字节数
+    pub chunk_bytes: usize,
+    /// 每个音频块 / 识别结果发送前的延迟（毫秒）
+    pub chunk_delay_ms: u64,
+    /// 识别结果文本，随音频包逐字揭示
+    pub transcript: String,
+    /// 要求的 App ID，为空时不校验
+    pub app_id: Option<String>,
+    /// 要求的 Access Token，为空时不校验
+    pub access_token: Option<String>,
+    /// 注入的故障
+    pub fault: Option<MockFault>,
+}
+
+impl Default for MockScenario {
+    fn default() -> Self {
+        Self {
+            audio_chunks: 3,
+            chunk_bytes: 1024,
+            chunk_delay_ms: 0,
+            transcript: "你好，世界。".to_string(),
+            app_id: None,
+            access_token: None,
+            fault: None,
+        }
+    }
+}
+
+impl MockScenario {
+    async fn delay(&self) {
+        if self.chunk_delay_ms > 0 {
+            sleep(Duration::from_millis(self.chunk_delay_ms)).await;
+        }
+    }
+}
+
+/// 可注入的故障；`afterFrames` 按单个连接已发送的音频块 / 识别结果计数
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(
+    tag = "kind",
+    rename_all = "camelCase",
+    rename_all_fields = "camelCase"
+)]
+pub enum MockFault {
+    /// 握手（或 HTTP 请求）直接返回该状态码
+    RejectHandshake { status: u16 },
+    /// 发送若干帧后返回错误帧并断开
+    ErrorFrame {
+        after_frames: usize,
+        code: u32,
+        message: String,
+    },
+    /// 发送若干帧后直接断开 TCP，不发送 Close 帧
+    CloseMidStream { after_frames: usize },
+    /// HTTP 合成接口返回业务错误
+    HttpError { code: i32, message: String },
+}
+
+/// 模拟服务收到的一条 JSON 请求
+#[derive(Debug, Clone, PartialEq)]
+pub struct MockRequest {
+    pub path: String,
+    /// TTS 事件号，SAUC / HTTP 请求为空
+    pub event: Option<i32>,
+    pub payload: Value,
+}
+
+/// 运行中的模拟服务，drop 时停止监听
+pub struct MockServer {
+    addr: SocketAddr,
+    requests: Arc<Mutex<Vec<MockRequest>>>,
+    task: JoinHandle<()>,
+}
+
+struct Shared {
+    scenario: MockScenario,
+    requests: Arc<Mutex<Vec<MockRequest>>>,
+}
+
+impl Shared {
+    fn record(&self, path: &str, event: Option<Event>, payload: &Value) {
+        self.requests
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .push(MockRequest {
+                path: path.to_string(),
+                event: event.map(Event::code),
+                payload: payload.clone(),
+            });
+    }
+}
+
+impl MockServer {
+    /// 在 `addr` 上启动，端口为 0 时随机分配
+    pub async fn start(addr: &str, scenario: MockScenario) -> std::io::Result<Self> {
+        let listener = TcpListener::bind(addr).await?;
+        let addr = listener.local_addr()?;
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let shared = Arc::new(Shared {
+            scenario,
+            requests: requests.clone(),
+        });
+
+        let task = tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                let shared = shared.clone();
+                tokio::spawn(async move {
+                    if let Err(e) = handle_connection(stream, &shared).await {
+                        eprintln!("[volc-mock] {}", e);
+                    }
+                });
+            }
+        });
+
+        Ok(Self {
+            addr,
+            requests,
+            task,
+        })
+    }
+
+    pub fn addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    /// 目前为止收到的全部 JSON 请求
+    pub fn requests(&self) -> Vec<MockRequest> {
+        self.requests
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+}
+
+impl Drop for MockServer {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+struct RequestHead {
+    method: String,
+    path: String,
+    headers: Vec<(String, String)>,
+}
+
+impl RequestHead {
+    fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+/// 期望值为空时不校验
+fn credential_matches(expected: &Option<String>, actual: Option<&str>) -> bool {
+    match expected {
+        Some(expected) => actual == Some(expected.as_str()),
+        None => true,
+    }
+}
+
+async fn handle_connection(mut stream: TcpStream, shared: &Shared) -> Result<(), String> {
+    let head = read_head(&mut stream).await?;
+    let scenario = &shared.scenario;
+
+    if let Some(MockFault::RejectHandshake { status }) = &scenario.fault {
+        return write_response(
+            &mut stream,
+            *status,
+            r#"{"message":"rejected by scenario"}"#,
+        )
+        .await;
+    }
+
+    if head.method == "POST" && head.path == "/api/v1/tts" {
+        return handle_http_tts(stream, &head, shared).await;
+    }
+
+    let is_tts = head.path.starts_with("/api/v3/tts/bidirection");
+    let is_sauc = head.path.starts_with("/api/v3/sauc/");
+    if head.method != "GET" || !(is_tts || is_sauc) {
+        return write_response(&mut stream, 404, r#"{"message":"not found"}"#).await;
+    }
+    if !credential_matches(&scenario.app_id, head.header("X-Api-App-Key"))
+        || !credential_matches(&scenario.access_token, head.header("X-Api-Access-Key"))
+    {
+        return write_response(&mut stream, 401, r#"{"message":"invalid credentials"}"#).await;
+    }
+
+    let ws = accept_websocket(stream, &head).await?;
+    if is_tts {
+        tts_session(ws, &head, shared).await
+    } else {
+        sauc_session(ws, &head, shared).await
+    }
+}
+
+async fn read_head(stream: &mut TcpStream) -> Result<RequestHead, String> {
+    // 逐字节读取，保证不会吞掉请求头之后的数据
+    let mut buf = Vec::new();
+    let mut byte = [0u8; 1];
+    while !buf.ends_with(b"\r\n\r\n") {
+        if buf.len() >= MAX_HEAD_BYTES {
+            return Err("request head too large".to_string());
+        }
+        let read = stream
+            .read(&mut byte)
+            .await
+            .map_err(|e| format!("failed to read request: {}", e))?;
+        if read == 0 {
+            return Err("connection closed before request head".to_string());
+        }
+        buf.push(byte[0]);
+    }
+
+    let text = String::from_utf8_lossy(&buf);
+    let mut lines = text.split("\r\n");
+    let mut request_line = lines.next().unwrap_or_default().split_whitespace();
+    let method = request_line.next().unwrap_or_default().to_string();
+    let path = request_line.next().unwrap_or_default().to_string();
+    let headers = lines
+        .filter_map(|line| line.split_once(':'))
+        .map(|(name, value)| (name.trim().to_string(), value.trim().to_string()))
+        .collect();
+
+    Ok(RequestHead {
+        method,
+        path,
+        headers,
+    })
+}
+
+async fn write_response(stream: &mut TcpStream, status: u16, body: &str) -> Result<(), String> {
+    let reason = match status {
+        200 => "OK",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        404 => "Not Found",
+        413 => "Payload Too Large",
+        429 => "Too Many Requests",
+        500 => "Internal Server Error",
+        503 => "Service Unavailable",
+        _ => "Mock Status",
+    };
+    let response = format!(
+        "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        reason,
+        body.len(),
+        body
+    );
+    stream
+        .write_all(response.as_bytes())
+        .await
+        .map_err(|e| format!("failed to write response: {}", e))
+}
+
+async fn accept_websocket(mut stream: TcpStream, head: &RequestHead) -> Result<MockSocket, String> {
+    let Some(key) = head.header("Sec-WebSocket-Key") else {
+        write_response(
+            &mut stream,
+            400,
+            r#"{"message":"missing Sec-WebSocket-Key"}"#,
+        )
+        .await?;
+        return Err("missing Sec-WebSocket-Key".to_string());
+    };
+    let response = format!(
+        "HTTP/1.1 101 Switching Protocols\r\nUpgrade: websocket\r\nConnection: Upgrade\r\nSec-WebSocket-Accept: {}\r\n\r\n",
+        derive_accept_key(key.as_bytes())
+    );
+    stream
+        .write_all(response.as_bytes())
+        .await
+        .map_err(|e| format!("failed to accept websocket: {}", e))?;
+
+    Ok(WebSocketStream::from_raw_socket(stream, Role::Server, None).await)
+}
+
+/// 读取下一条客户端二进制帧，返回解压后的帧与客户端使用的压缩方式
+async fn next_client_frame(ws: &mut MockSocket) -> Result<Option<(Frame, Compression)>, String> {
+    while let Some(message) = ws.next().await {
+        match message.map_err(|e| format!("failed to read message: {}", e))? {
+            Message::Binary(data) => {
+                let frame = Frame::decode(&data).map_err(|e| e.to_string())?;
+                let compression = frame.compression;
+                let frame = frame.decompress().map_err(|e| e.to_string())?;
+                return Ok(Some((frame, compression)));
+            }
+            Message::Close(_) => return Ok(None),
+            _ => {}
+        }
+    }
+    Ok(None)
+}
+
+/// JSON 帧按客户端的压缩方式返回，音频帧不压缩
+async fn send_frame(
+    ws: &mut MockSocket,
+    frame: Frame,
+    compression: Compression,
+) -> Result<(), String> {
+    let compression = if frame.serialization == Serialization::Json {
+        compression
+    } else {
+        Compression::None
+    };
+    let data = frame
+        .compress(compression)
+        .and_then(|frame| frame.encode())
+        .map_err(|e| e.to_string())?;
+    ws.send(Message::Binary(data))
+        .await
+        .map_err(|e| format!("failed to send frame: {}", e))
+}
+
+async fn send_error(ws: &mut MockSocket, code: u32, message: &str) -> Result<(), String> {
+    let payload = json!({ "error": message }).to_string().into_bytes();
+    send_frame(ws, Frame::error(code, payload), Compression::None).await
+}
+
+/// 单个连接上的故障注入进度
+struct FaultPlan<'a> {
+    fault: Option<&'a MockFault>,
+    sent: usize,
+}
+
+impl<'a> FaultPlan<'a> {
+    fn new(fault: Option<&'a MockFault>) -> Self {
+        Self { fault, sent: 0 }
+    }
+
+    /// 发送一个数据帧；命中故障时改为注入错误帧或断开，返回 `false` 表示连接应结束
+    async fn send(
+        &mut self,
+        ws: &mut MockSocket,
+        frame: Frame,
+        compression: Compression,
+    ) -> Result<bool, String> {
+        let sent = self.sent;
+        self.sent += 1;
+        match self.fault {
+            Some(MockFault::ErrorFrame {
+                after_frames,
+                code,
+                message,
+            }) if *after_frames == sent => {
+                send_error(ws, *code, message).await?;
+                Ok(false)
+            }
+            Some(MockFault::CloseMidStream { after_frames }) if *after_frames == sent => Ok(false),
+            _ => {
+                send_frame(ws, frame, compression).await?;
+                Ok(true)
+            }
+        }
+    }
+}
+
+fn fake_audio(len: usize, seed: usize) -> Vec<u8> {
+    (0..len).map(|i| ((i + seed) % 251) as u8).collect()
+}
+
+async fn tts_session(
+    mut ws: MockSocket,
+    head: &RequestHead,
+    shared: &Shared,
+) -> Result<(), String> {
+    let scenario = &shared.scenario;
+    let connect_id = head
+        .header("X-Api-Connect-Id")
+        .map(str::to_string)
+        .unwrap_or_else(|| Uuid::new_v4().to_string());
+    let mut faults = FaultPlan::new(scenario.fault.as_ref());
+
+    loop {
+        let (frame, compression) = match next_client_frame(&mut ws).await {
+            Ok(Some(next)) => next,
+            Ok(None) => return Ok(()),
+            Err(e) => {
+                let _ = send_error(&mut ws, INVALID_REQUEST_CODE, &e).await;
+                return Err(e);
+            }
+        };
+        let payload: Value = serde_json::from_slice(&frame.payload).unwrap_or(Value::Null);
+        shared.record(&head.path, frame.event, &payload);
+
+        let session_id = frame.id.clone().unwrap_or_default();
+        let reply = |event: Event| Frame::server_event(event, Some(&session_id), b"{}".to_vec());
+        match frame.event {
+            Some(Event::StartConnection) => {
+                let started = Frame::server_event(
+                    Event::ConnectionStarted,
+                    Some(&connect_id),
+                    b"{}".to_vec(),
+                );
+                send_frame(&mut ws, started, compression).await?;
+            }
+            Some(Event::FinishConnection) => {
+                let finished = Frame::server_event(
+                    Event::ConnectionFinished,
+                    Some(&connect_id),
+                    b"{}".to_vec(),
+                );
+                send_frame(&mut ws, finished, compression).await?;
+                let _ = ws.close(None).await;
+                return Ok(());
+            }
+            Some(Event::StartSession) => {
+                send_frame(&mut ws, reply(Event::SessionStarted), compression).await?;
+            }
+            Some(Event::CancelSession) => {
+                send_frame(&mut ws, reply(Event::SessionCanceled), compression).await?;
+            }
+            Some(Event::FinishSession) => {
+                send_frame(&mut ws, reply(Event::SessionFinished), compression).await?;
+            }
+            Some(Event::TaskRequest) => {
+                let text = payload["req_params"]["text"].as_str().unwrap_or_default();
+                let sentence = json!({ "text": text }).to_string().into_bytes();
+
+                let start = Frame::server_event(
+                    Event::TtsSentenceStart,
+                    Some(&session_id),
+                    sentence.clone(),
+                );
+                send_frame(&mut ws, start, compression).await?;
+                for index in 0..scenario.audio_chunks {
+                    scenario.delay().await;
+                    let audio = Frame::server_audio(
+                        Event::TtsResponse,
+                        Some(&session_id),
+                        fake_audio(scenario.chunk_bytes, index),
+                    );
+                    if !faults.send(&mut ws, audio, compression).await? {
+                        return Ok(());
+                    }
+                }
+                let end = Frame::server_event(Event::TtsSentenceEnd, Some(&session_id), sentence);
+                send_frame(&mut ws, end, compression).await?;
+            }
+            other => {
+                let message = format!("unsupported event: {:?}", other);
+                send_error(&mut ws, INVALID_REQUEST_CODE, &message).await?;
+                return Err(message);
+            }
+        }
+    }
+}
+
+/// 按请求中的采样参数估算已收到音频的时长（毫秒）
+fn audio_duration_ms(request: &Value, audio_bytes: usize) -> u64 {
+    let audio = &request["audio"];
+    let rate = audio["rate"].as_u64().unwrap_or(16000);
+    let bits = audio["bits"].as_u64().unwrap_or(16);
+    let channel = audio["channel"].as_u64().unwrap_or(1);
+    let bytes_per_second = (rate * bits / 8 * channel).max(1);
+    audio_bytes as u64 * 1000 / bytes_per_second
+}
+
+/// 识别结果：中间结果每收到一个音频包多揭示一个字，最后一包返回完整文本与字级时间
+fn recognition_result(transcript: &str, packets: usize, duration_ms: u64, is_last: bool) -> Value {
+    let chars: Vec<char> = transcript.chars().collect();
+    let shown = if is_last {
+        chars.len()
+    } else {
+        packets.min(chars.len())
+    };
+    let text: String = chars[..shown].iter().collect();
+
+    let spoken: Vec<char> = chars[..shown]
+        .iter()
+        .copied()
+        .filter(|ch| ch.is_alphanumeric())
+        .collect();
+    let step = duration_ms / spoken.len().max(1) as u64;
+    let words: Vec<Value> = spoken
+        .iter()
+        .enumerate()
+        .map(|(index, ch)| {
+            json!({
+                "text": ch.to_string(),
+                "start_time": index as u64 * step,
+                "end_time": (index as u64 + 1) * step,
+                "blank_duration": 0
+            })
+        })
+        .collect();
+
+    json!({
+        "audio_info": { "duration": duration_ms },
+        "result": {
+            "text": text,
+            "utterances": [{
+                "text": text,
+                "start_time": 0,
+                "end_time": duration_ms,
+                "definite": is_last,
+                "words": words
+            }]
+        }
+    })
+}
+
+async fn sauc_session(
+    mut ws: MockSocket,
+    head: &RequestHead,
+    shared: &Shared,
+) -> Result<(), String> {
+    let scenario = &shared.scenario;
+    let mut faults = FaultPlan::new(scenario.fault.as_ref());
+    let mut request: Option<Value> = None;
+    let mut audio_bytes = 0usize;
+    let mut packets = 0usize;
+
+    loop {
+        let (frame, compression) = match next_client_frame(&mut ws).await {
+            Ok(Some(next)) => next,
+            Ok(None) => return Ok(()),
+            Err(e) => {
+                let _ = send_error(&mut ws, INVALID_REQUEST_CODE, &e).await;
+                return Err(e);
+            }
+        };
+
+        match (frame.message_type, &request) {
+            (MessageType::FullClientRequest, None) => {
+                let payload: Value = serde_json::from_slice(&frame.payload).unwrap_or(Value::Null);
+                shared.record(&head.path, None, &payload);
+                request = Some(payload);
+
+                let ack = Frame::server_response(b"{}".to_vec())
+                    .with_sequence(frame.sequence.unwrap_or(1));
+                send_frame(&mut ws, ack, compression).await?;
+            }
+            (MessageType::AudioOnlyRequest, Some(request)) => {
+                packets += 1;
+                audio_bytes += frame.payload.len();
+                let is_last = frame.is_last();
+                // 首包占用序号 1，音频包从 2 开始
+                let sequence = frame.sequence.map(i32::abs).unwrap_or(packets as i32 + 1);
+                let sequence = if is_last { -sequence } else { sequence };
+
+                let duration_ms = audio_duration_ms(request, audio_bytes);
+                let result =
+                    recognition_result(&scenario.transcript, packets, duration_ms, is_last);
+                scenario.delay().await;
+                let response =
+                    Frame::server_response(result.to_string().into_bytes()).with_sequence(sequence);
+                if !faults.send(&mut ws, response, compression).await? {
+                    return Ok(());
+                }
+                if is_last {
+                    let _ = ws.close(None).await;
+                    return Ok(());
+                }
+            }
+            (message_type, _) => {
+                let message = format!("unexpected {:?} in SAUC session", message_type);
+                send_error(&mut ws, INVALID_REQUEST_CODE, &message).await?;
+                return Err(message);
+            }
+        }
+    }
+}
+
+async fn handle_http_tts(
+    mut stream: TcpStream,
+    head: &RequestHead,
+    shared: &Shared,
+) -> Result<(), String> {
+    let scenario = &shared.scenario;
+    let length: usize = head
+        .header("Content-Length")
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(0);
+    if length > MAX_BODY_BYTES {
+        return write_response(&mut stream, 413, r#"{"message":"body too large"}"#).await;
+    }
+    let mut body = vec![0u8; length];
+    stream
+        .read_exact(&mut body)
+        .await
+        .map_err(|e| format!("failed to read body: {}", e))?;
+    let payload: Value = serde_json::from_slice(&body).unwrap_or(Value::Null);
+    shared.record(&head.path, None, &payload);
+
+    let token = head
+        .header("Authorization")
+        .and_then(|value| value.strip_prefix("Bearer;"));
+    if !credential_matches(&scenario.access_token, token)
+        || !credential_matches(&scenario.app_id, payload["app"]["appid"].as_str())
+    {
+        return write_response(&mut stream, 401, r#"{"message":"invalid credentials"}"#).await;
+    }
+
+    let response = match &scenario.fault {
+        Some(MockFault::HttpError { code, message }) => json!({ "code": code, "message": message }),
+        _ => {
+            scenario.delay().await;
+            let audio: Vec<u8> = (0..scenario.audio_chunks)
+                .flat_map(|index| fake_audio(scenario.chunk_bytes, index))
+                .collect();
+            json!({
+                "reqid": payload["request"]["reqid"],
+                "code": 3000,
+                "message": "Success",
+                "data": STANDARD.encode(audio)
+            })
+        }
+    };
+    write_response(&mut stream, 200, &response.to_string()).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio_tungstenite::connect_async;
+    use tokio_tungstenite::tungstenite::error::Error as WsError;
+    use tokio_tungstenite::{MaybeTlsStream, WebSocketStream};
+
+    type Client = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
+    async fn connect(server: &MockServer, path: &str) -> Client {
+        let url = format!("ws://{}{}", server.addr(), path);
+        connect_async(url).await.expect("connect to mock").0
+    }
+
+    async fn send(client: &mut Client, frame: Frame) {
+        let data = frame.encode().unwrap();
+        client.send(Message::Binary(data)).await.unwrap();
+    }
+
+    async fn recv(client: &mut Client) -> Option<Frame> {
+        while let Some(message) = client.next().await {
+            match message {
+                Ok(Message::Binary(data)) => {
+                    return Some(Frame::decode(&data).and_then(Frame::decompress).unwrap())
+                }
+                Ok(_) => {}
+                Err(_) => return None,
+            }
+        }
+        None
+    }
+
+    fn task(text: &str) -> Vec<u8> {
+        json!({ "req_params": { "text": text } })
+            .to_string()
+            .into_bytes()
+    }
+
+    #[tokio::test]
+    async fn tts_session_streams_sentences_and_audio() {
+        let server = MockServer::start("127.0.0.1:0", MockScenario::default())
+            .await
+            .unwrap();
+        let mut client = connect(&server, "/api/v3/tts/bidirection").await;
+
+        send(
+            &mut client,
+            Frame::client_event(Event::StartConnection, None, b"{}".to_vec()),
+        )
+        .await;
+        assert_eq!(
+            recv(&mut client).await.unwrap().event,
+            Some(Event::ConnectionStarted)
+        );
+
+        send(
+            &mut client,
+            Frame::client_event(Event::StartSession, Some("s1"), b"{}".to_vec()),
+        )
+        .await;
+        let started = recv(&mut client).await.unwrap();
+        assert_eq!(started.event, Some(Event::SessionStarted));
+        assert_eq!(started.id.as_deref(), Some("s1"));
+
+        send(
+            &mut client,
+            Frame::client_event(Event::TaskRequest, Some("s1"), task("你好")),
+        )
+        .await;
+        assert_eq!(
+            recv(&mut client).await.unwrap().event,
+            Some(Event::TtsSentenceStart)
+        );
+        for _ in 0..3 {
+            let audio = recv(&mut client).await.unwrap();
+            assert_eq!(audio.message_type, MessageType::AudioOnlyResponse);
+            assert_eq!(audio.payload.len(), 1024);
+        }
+        assert_eq!(
+            recv(&mut client).await.unwrap().event,
+            Some(Event::TtsSentenceEnd)
+        );
+
+        send(
+            &mut client,
+            Frame::client_event(Event::FinishSession, Some("s1"), b"{}".to_vec()),
+        )
+        .await;
+        assert_eq!(
+            recv(&mut client).await.unwrap().event,
+            Some(Event::SessionFinished)
+        );
+
+        let requests = server.requests();
+        let task_request = requests
+            .iter()
+            .find(|request| request.event == Some(Event::TaskRequest.code()))
+            .unwrap();
+        assert_eq!(task_request.payload["req_params"]["text"], "你好");
+    }
+
+    #[tokio::test]
+    async fn sauc_session_returns_final_transcript_over_gzip() {
+        let scenario = MockScenario {
+            transcript: "打开终端".to_string(),
+            ..MockScenario::default()
+        };
+        let server = MockServer::start("127.0.0.1:0", scenario).await.unwrap();
+        let mut client = connect(&server, "/api/v3/sauc/bigmodel_async").await;
+
+        let request = json!({ "audio": { "rate": 16000, "bits": 16, "channel": 1 } });
+        let first = Frame::full_client_request(request.to_string().into_bytes())
+            .with_sequence(1)
+            .compress(Compression::Gzip)
+            .unwrap();
+        send(&mut client, first).await;
+        assert_eq!(recv(&mut client).await.unwrap().sequence, Some(1));
+
+        send(
+            &mut client,
+            Frame::audio_only_request(vec![0; 6400], false).with_sequence(2),
+        )
+        .await;
+        let partial = recv(&mut client).await.unwrap();
+        let partial: Value = serde_json::from_slice(&partial.payload).unwrap();
+        assert_eq!(partial["result"]["text"], "打");
+
+        send(
+            &mut client,
+            Frame::audio_only_request(vec![0; 6400], true).with_sequence(-3),
+        )
+        .await;
+        let last = recv(&mut client).await.unwrap();
+        assert!(last.is_last());
+        let last: Value = serde_json::from_slice(&last.payload).unwrap();
+        assert_eq!(last["result"]["text"], "打开终端");
+        assert_eq!(last["audio_info"]["duration"], 400);
+        assert_eq!(
+            last["result"]["utterances"][0]["words"]
+                .as_array()
+                .unwrap()
+                .len(),
+            4
+        );
+    }
+
+    #[tokio::test]
+    async fn error_frame_fault_interrupts_stream() {
+        let scenario = MockScenario {
+            fault: Some(MockFault::ErrorFrame {
+                after_frames: 1,
+                code: 45_000_292,
+                message: "quota exceeded".to_string(),
+            }),
+            ..MockScenario::default()
+        };
+        let server = MockServer::start("127.0.0.1:0", scenario).await.unwrap();
+        let mut client = connect(&server, "/api/v3/tts/bidirection").await;
+
+        send(
+            &mut client,
+            Frame::client_event(Event::TaskRequest, Some("s1"), task("hi")),
+        )
+        .await;
+        assert_eq!(
+            recv(&mut client).await.unwrap().event,
+            Some(Event::TtsSentenceStart)
+        );
+        assert_eq!(
+            recv(&mut client).await.unwrap().event,
+            Some(Event::TtsResponse)
+        );
+        let error = recv(&mut client).await.unwrap();
+        assert!(error.is_error());
+        assert_eq!(error.error_code, Some(45_000_292));
+        assert!(recv(&mut client).await.is_none());
+    }
+
+    #[tokio::test]
+    async fn close_mid_stream_drops_connection_without_close_frame() {
+        let scenario = MockScenario {
+            fault: Some(MockFault::CloseMidStream { after_frames: 0 }),
+            ..MockScenario::default()
+        };
+        let server = MockServer::start("127.0.0.1:0", scenario).await.unwrap();
+        let mut client = connect(&server, "/api/v3/sauc/bigmodel_async").await;
+
+        send(
+            &mut client,
+            Frame::full_client_request(b"{}".to_vec()).with_sequence(1),
+        )
+        .await;
+        assert!(recv(&mut client).await.is_some());
+        send(
+            &mut client,
+            Frame::audio_only_request(vec![0; 320], true).with_sequence(-2),
+        )
+        .await;
+        assert!(matches!(client.next().await, None | Some(Err(_))));
+    }
+
+    #[tokio::test]
+    async fn rejected_handshake_and_credentials() {
+        let scenario = MockScenario {
+            fault: Some(MockFault::RejectHandshake { status: 503 }),
+            ..MockScenario::default()
+        };
+        let server = MockServer::start("127.0.0.1:0", scenario).await.unwrap();
+        let url = format!("ws://{}/api/v3/tts/bidirection", server.addr());
+        match connect_async(url).await {
+            Err(WsError::Http(response)) => assert_eq!(response.status(), 503),
+            other => panic!("unexpected handshake result: {:?}", other.map(|_| ())),
+        }
+
+        let scenario = MockScenario {
+            app_id: Some("app".to_string()),
+            ..MockScenario::default()
+        };
+        let server = MockServer::start("127.0.0.1:0", scenario).await.unwrap();
+        let url = format!("ws://{}/api/v3/sauc/bigmodel", server.addr());
+        match connect_async(url).await {
+            Err(WsError::Http(response)) => assert_eq!(response.status(), 401),
+            other => panic!("unexpected handshake result: {:?}", other.map(|_| ())),
+        }
+    }
+
+    #[tokio::test]
+    async fn http_tts_returns_base64_audio() {
+        let server = MockServer::start("127.0.0.1:0", MockScenario::default())
+            .await
+            .unwrap();
+        let response: Value = reqwest::Client::new()
+            .post(format!("http://{}/api/v1/tts", server.addr()))
+            .header("Authorization", "Bearer;token")
+            .json(&json!({ "app": { "appid": "app" }, "request": { "reqid": "r1", "text": "hi" } }))
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+
+        assert_eq!(response["code"], 3000);
+        assert_eq!(response["reqid"], "r1");
+        let audio = STANDARD.decode(response["data"].as_str().unwrap()).unwrap();
+        assert_eq!(audio.len(), 3 * 1024);
+    }
+}
@@ -0,0 +1,65 @@
+#[cfg(any(test, feature = "mock"))]
+pub mod mock;
+pub mod protocol;
+
+use crate::net::VoiceError;
+use protocol::Frame;
+
+/// 设置后（如 `127.0.0.1:18080`）所有火山引擎请求改发到本地模拟服务，见 [`mock`]
+///
+/// 改写会把凭证以明文发往该地址，只在启用 `mock` 特性的构建中生效。
+#[cfg(feature = "mock")]
+pub const MOCK_ADDR_ENV: &str = "VOLC_MOCK_ADDR";
+
+/// 实际请求地址：启用 `mock` 特性且设置了 `VOLC_MOCK_ADDR` 时把官方域名替换为本地明文地址
+#[cfg(feature = "mock")]
+pub fn endpoint(url: &str) -> String {
+    match std::env::var(MOCK_ADDR_ENV) {
+        Ok(addr) if !addr.trim().is_empty() => rewrite_endpoint(url, addr.trim()),
+        _ => url.to_string(),
+    }
+}
+
+/// 实际请求地址，发布构建始终为官方地址
+#[cfg(not(feature = "mock"))]
+pub fn endpoint(url: &str) -> String {
+    url.to_string()
+}
+
+/// 服务端错误帧转为已分类的错误，`context` 说明出错的接口
+pub fn frame_error(context: &str, frame: &Frame) -> VoiceError {
+    let payload = String::from_utf8_lossy(&frame.payload);
+    VoiceError::from_service(context, frame.error_code.map(i64::from), &payload)
+}
+
+#[cfg(any(test, feature = "mock"))]
+fn rewrite_endpoint(url: &str, addr: &str) -> String {
+    let (scheme, rest) = url.split_once("://").unwrap_or(("https", url));
+    let scheme = match scheme {
+        "wss" => "ws",
+        "https" => "http",
+        other => other,
+    };
+    let path = rest.find('/').map(|index| &rest[index..]).unwrap_or("/");
+    format!("{}://{}{}", scheme, addr, path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rewrites_to_plaintext_mock_address() {
+        assert_eq!(
+            rewrite_endpoint(
+                "wss://openspeech.bytedance.com/api/v3/sauc/bigmodel_async",
+                "127.0.0.1:18080"
+            ),
+            "ws://127.0.0.1:18080/api/v3/sauc/bigmodel_async"
+        );
+        assert_eq!(
+            rewrite_endpoint("https://openspeech.bytedance.com/api/v1/tts", "localhost:9"),
+            "http://localhost:9/api/v1/tts"
+        );
+    }
+}
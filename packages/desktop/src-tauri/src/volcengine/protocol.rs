@@ -0,0 +1,819 @@
+//! 火山引擎语音 WebSocket 二进制协议（TTS 双向流式 / SAUC 大模型识别共用）
+//!
+//! 帧结构：
+//! ```text
+//! | version(4) | header_size(4) | message_type(4) | flags(4) |
+//! | serialization(4) | compression(4) | reserved(8) | [header extension] |
+//! | [sequence | error_code] | [event] | [id_size | id] | payload_size | payload |
+//! ```
+//! 所有整数均为大端序，`header_size` 以 4 字节为单位。
+
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use std::fmt;
+use std::io::{Read, Write};
+
+pub const PROTOCOL_VERSION: u8 = 0x1;
+pub const DEFAULT_HEADER_SIZE: u8 = 0x1;
+/// 解压后 payload 的大小上限，防止服务端（或中间人）发来的压缩炸弹耗尽内存
+pub const MAX_DECOMPRESSED_BYTES: u64 = 16 * 1024 * 1024;
+
+/// 协议解析 / 编码错误
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ProtocolError {
+    /// 数据不足以读取某个字段
+    Truncated {
+        field: &'static str,
+        needed: usize,
+        available: usize,
+    },
+    UnsupportedVersion(u8),
+    InvalidHeaderSize(u8),
+    UnknownMessageType(u8),
+    UnknownFlags(u8),
+    UnknownSerialization(u8),
+    UnknownCompression(u8),
+    InvalidId,
+    /// payload 之后仍有多余字节
+    TrailingBytes(usize),
+    /// 编码时缺少 message_type / flags 要求的字段
+    MissingField(&'static str),
+    /// 编码时提供了 message_type / flags 不允许的字段
+    UnexpectedField(&'static str),
+    FieldTooLarge {
+        field: &'static str,
+        len: usize,
+    },
+    /// payload 压缩 / 解压失败
+    Compression(String),
+    UnsupportedCompression(Compression),
+}
+
+impl fmt::Display for ProtocolError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Truncated {
+                field,
+                needed,
+                available,
+            } => write!(
+                f,
+                "Frame truncated at {}: need {} bytes, {} available",
+                field, needed, available
+            ),
+            Self::UnsupportedVersion(version) => {
+                write!(f, "Unsupported protocol version: {}", version)
+            }
+            Self::InvalidHeaderSize(size) => write!(f, "Invalid header size: {}", size),
+            Self::UnknownMessageType(value) => write!(f, "Unknown message type: {:#x}", value),
+            Self::UnknownFlags(value) => write!(f, "Unknown message flags: {:#x}", value),
+            Self::UnknownSerialization(value) => {
+                write!(f, "Unknown serialization method: {:#x}", value)
+            }
+            Self::UnknownCompression(value) => {
+                write!(f, "Unknown compression method: {:#x}", value)
+            }
+            Self::InvalidId => write!(f, "Frame id is not valid UTF-8"),
+            Self::TrailingBytes(count) => {
+                write!(f, "Frame has {} unexpected trailing bytes", count)
+            }
+            Self::MissingField(field) => write!(f, "Frame is missing field: {}", field),
+            Self::UnexpectedField(field) => write!(f, "Frame has unexpected field: {}", field),
+            Self::FieldTooLarge { field, len } => {
+                write!(f, "Frame field {} is too large: {} bytes", field, len)
+            }
+            Self::Compression(message) => write!(f, "Payload compression failed: {}", message),
+            Self::UnsupportedCompression(compression) => {
+                write!(f, "Unsupported payload compression: {:?}", compression)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ProtocolError {}
+
+/// 消息类型（header 第 2 字节高 4 位）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MessageType {
+    FullClientRequest,
+    AudioOnlyRequest,
+    FullServerResponse,
+    AudioOnlyResponse,
+    FrontEndResult,
+    Error,
+}
+
+impl MessageType {
+    pub fn to_bits(self) -> u8 {
+        match self {
+            Self::FullClientRequest => 0b0001,
+            Self::AudioOnlyRequest => 0b0010,
+            Self::FullServerResponse => 0b1001,
+            Self::AudioOnlyResponse => 0b1011,
+            Self::FrontEndResult => 0b1100,
+            Self::Error => 0b1111,
+        }
+    }
+
+    pub fn from_bits(bits: u8) -> Result<Self, ProtocolError> {
+        match bits {
+            0b0001 => Ok(Self::FullClientRequest),
+            0b0010 => Ok(Self::AudioOnlyRequest),
+            0b1001 => Ok(Self::FullServerResponse),
+            0b1011 => Ok(Self::AudioOnlyResponse),
+            0b1100 => Ok(Self::FrontEndResult),
+            0b1111 => Ok(Self::Error),
+            other => Err(ProtocolError::UnknownMessageType(other)),
+        }
+    }
+}
+
+/// 消息标志位（header 第 2 字节低 4 位）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Flags {
+    NoSequence,
+    /// 携带正序号
+    PositiveSequence,
+    /// 最后一包，不带序号
+    LastNoSequence,
+    /// 最后一包，携带负序号
+    NegativeSequence,
+    /// 携带事件号
+    WithEvent,
+}
+
+impl Flags {
+    pub fn to_bits(self) -> u8 {
+        match self {
+            Self::NoSequence => 0b0000,
+            Self::PositiveSequence => 0b0001,
+            Self::LastNoSequence => 0b0010,
+            Self::NegativeSequence => 0b0011,
+            Self::WithEvent => 0b0100,
+        }
+    }
+
+    pub fn from_bits(bits: u8) -> Result<Self, ProtocolError> {
+        match bits {
+            0b0000 => Ok(Self::NoSequence),
+            0b0001 => Ok(Self::PositiveSequence),
+            0b0010 => Ok(Self::LastNoSequence),
+            0b0011 => Ok(Self::NegativeSequence),
+            0b0100 => Ok(Self::WithEvent),
+            other => Err(ProtocolError::UnknownFlags(other)),
+        }
+    }
+
+    pub fn has_sequence(self) -> bool {
+        matches!(self, Self::PositiveSequence | Self::NegativeSequence)
+    }
+
+    pub fn is_last(self) -> bool {
+        matches!(self, Self::LastNoSequence | Self::NegativeSequence)
+    }
+}
+
+/// payload 序列化方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Serialization {
+    None,
+    Json,
+    Thrift,
+    Custom,
+}
+
+impl Serialization {
+    pub fn to_bits(self) -> u8 {
+        match self {
+            Self::None => 0b0000,
+            Self::Json => 0b0001,
+            Self::Thrift => 0b0011,
+            Self::Custom => 0b1111,
+        }
+    }
+
+    pub fn from_bits(bits: u8) -> Result<Self, ProtocolError> {
+        match bits {
+            0b0000 => Ok(Self::None),
+            0b0001 => Ok(Self::Json),
+            0b0011 => Ok(Self::Thrift),
+            0b1111 => Ok(Self::Custom),
+            other => Err(ProtocolError::UnknownSerialization(other)),
+        }
+    }
+}
+
+/// payload 压缩方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Compression {
+    None,
+    Gzip,
+    Custom,
+}
+
+impl Compression {
+    pub fn to_bits(self) -> u8 {
+        match self {
+            Self::None => 0b0000,
+            Self::Gzip => 0b0001,
+            Self::Custom => 0b1111,
+        }
+    }
+
+    pub fn from_bits(bits: u8) -> Result<Self, ProtocolError> {
+        match bits {
+            0b0000 => Ok(Self::None),
+            0b0001 => Ok(Self::Gzip),
+            0b1111 => Ok(Self::Custom),
+            other => Err(ProtocolError::UnknownCompression(other)),
+        }
+    }
+}
+
+/// 双向流式 TTS 事件号，未收录的事件保留为 `Unknown`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Event {
+    StartConnection,
+    FinishConnection,
+    ConnectionStarted,
+    ConnectionFailed,
+    ConnectionFinished,
+    StartSession,
+    CancelSession,
+    FinishSession,
+    SessionStarted,
+    SessionCanceled,
+    SessionFinished,
+    SessionFailed,
+    TaskRequest,
+    TtsSentenceStart,
+    TtsSentenceEnd,
+    TtsResponse,
+    Unknown(i32),
+}
+
+impl Event {
+    pub fn code(self) -> i32 {
+        match self {
+            Self::StartConnection => 1,
+            Self::FinishConnection => 2,
+            Self::ConnectionStarted => 50,
+            Self::ConnectionFailed => 51,
+            Self::ConnectionFinished => 52,
+            Self::StartSession => 100,
+            Self::CancelSession => 101,
+            Self::FinishSession => 102,
+            Self::SessionStarted => 150,
+            Self::SessionCanceled => 151,
+            Self::SessionFinished => 152,
+            Self::SessionFailed => 153,
+            Self::TaskRequest => 200,
+            Self::TtsSentenceStart => 350,
+            Self::TtsSentenceEnd => 351,
+            Self::TtsResponse => 352,
+            Self::Unknown(code) => code,
+        }
+    }
+
+    pub fn from_code(code: i32) -> Self {
+        match code {
+            1 => Self::StartConnection,
+            2 => Self::FinishConnection,
+            50 => Self::ConnectionStarted,
+            51 => Self::ConnectionFailed,
+            52 => Self::ConnectionFinished,
+            100 => Self::StartSession,
+            101 => Self::CancelSession,
+            102 => Self::FinishSession,
+            150 => Self::SessionStarted,
+            151 => Self::SessionCanceled,
+            152 => Self::SessionFinished,
+            153 => Self::SessionFailed,
+            200 => Self::TaskRequest,
+            350 => Self::TtsSentenceStart,
+            351 => Self::TtsSentenceEnd,
+            352 => Self::TtsResponse,
+            other => Self::Unknown(other),
+        }
+    }
+
+    /// 是否携带 id 字段：连接级响应带 connect_id，会话级事件带 session_id，
+    /// 只有客户端的 StartConnection / FinishConnection 不带 id
+    pub fn carries_id(self) -> bool {
+        !matches!(self, Self::StartConnection | Self::FinishConnection)
+    }
+}
+
+/// 一帧完整消息
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Frame {
+    pub message_type: MessageType,
+    pub flags: Flags,
+    pub serialization: Serialization,
+    pub compression: Compression,
+    /// `flags` 为 PositiveSequence / NegativeSequence 时存在
+    pub sequence: Option<i32>,
+    /// `message_type` 为 Error 时存在
+    pub error_code: Option<u32>,
+    /// `flags` 为 WithEvent 时存在
+    pub event: Option<Event>,
+    /// `event.carries_id()` 为真时存在（connect_id 或 session_id）
+    pub id: Option<String>,
+    pub payload: Vec<u8>,
+}
+
+impl Frame {
+    /// TTS 客户端事件帧（JSON）
+    pub fn client_event(event: Event, id: Option<&str>, payload: Vec<u8>) -> Self {
+        Self {
+            message_type: MessageType::FullClientRequest,
+            flags: Flags::WithEvent,
+            serialization: Serialization::Json,
+            compression: Compression::None,
+            sequence: None,
+            error_code: None,
+            event: Some(event),
+            id: id.map(str::to_string),
+            payload,
+        }
+    }
+
+    /// SAUC 首包：完整客户端请求（JSON）
+    pub fn full_client_request(payload: Vec<u8>) -> Self {
+        Self {
+            message_type: MessageType::FullClientRequest,
+            flags: Flags::NoSequence,
+            serialization: Serialization::Json,
+            compression: Compression::None,
+            sequence: None,
+            error_code: None,
+            event: None,
+            id: None,
+            payload,
+        }
+    }
+
+    /// SAUC 音频包
+    pub fn audio_only_request(audio: Vec<u8>, is_last: bool) -> Self {
+        Self {
+            message_type: MessageType::AudioOnlyRequest,
+            flags: if is_last {
+                Flags::LastNoSequence
+            } else {
+                Flags::NoSequence
+            },
+            serialization: Serialization::None,
+            compression: Compression::None,
+            sequence: None,
+            error_code: None,
+            event: None,
+            id: None,
+            payload: audio,
+        }
+    }
+
+    /// 服务端事件帧（JSON）
+    pub fn server_event(event: Event, id: Option<&str>, payload: Vec<u8>) -> Self {
+        Self {
+            message_type: MessageType::FullServerResponse,
+            ..Self::client_event(event, id, payload)
+        }
+    }
+
+    /// 服务端事件音频帧（TTSResponse）
+    pub fn server_audio(event: Event, id: Option<&str>, audio: Vec<u8>) -> Self {
+        Self {
+            message_type: MessageType::AudioOnlyResponse,
+            serialization: Serialization::None,
+            ..Self::client_event(event, id, audio)
+        }
+    }
+
+    /// SAUC 服务端识别结果（JSON）
+    pub fn server_response(payload: Vec<u8>) -> Self {
+        Self {
+            message_type: MessageType::FullServerResponse,
+            ..Self::full_client_request(payload)
+        }
+    }
+
+    /// 服务端错误帧
+    pub fn error(code: u32, payload: Vec<u8>) -> Self {
+        Self {
+            message_type: MessageType::Error,
+            error_code: Some(code),
+            ..Self::full_client_request(payload)
+        }
+    }
+
+    /// 携带序号：正数为中间包，负数表示最后一包
+    pub fn with_sequence(mut self, sequence: i32) -> Self {
+        self.flags = if sequence < 0 {
+            Flags::NegativeSequence
+        } else {
+            Flags::PositiveSequence
+        };
+        self.sequence = Some(sequence);
+        self
+    }
+
+    pub fn is_error(&self) -> bool {
+        self.message_type == MessageType::Error
+    }
+
+    pub fn is_last(&self) -> bool {
+        self.flags.is_last()
+    }
+
+    /// 按指定方式压缩 payload（要求当前 payload 未压缩）
+    pub fn compress(mut self, compression: Compression) -> Result<Self, ProtocolError> {
+        if self.compression != Compression::None {
+            return Err(ProtocolError::UnsupportedCompression(self.compression));
+        }
+        match compression {
+            Compression::None => {}
+            Compression::Gzip => {
+                let mut encoder = GzEncoder::new(Vec::new(), flate2::Compression::default());
+                encoder
+                    .write_all(&self.payload)
+                    .map_err(|e| ProtocolError::Compression(e.to_string()))?;
+                self.payload = encoder
+                    .finish()
+                    .map_err(|e| ProtocolError::Compression(e.to_string()))?;
+            }
+            Compression::Custom => return Err(ProtocolError::UnsupportedCompression(compression)),
+        }
+        self.compression = compression;
+        Ok(self)
+    }
+
+    /// 解压 payload，返回 compression 为 None 的帧
+    pub fn decompress(mut self) -> Result<Self, ProtocolError> {
+        match self.compression {
+            Compression::None => {}
+            Compression::Gzip => {
+                // 多读一个字节以区分恰好达到上限与超出上限
+                let mut payload = Vec::new();
+                GzDecoder::new(self.payload.as_slice())
+                    .take(MAX_DECOMPRESSED_BYTES + 1)
+                    .read_to_end(&mut payload)
+                    .map_err(|e| ProtocolError::Compression(e.to_string()))?;
+                if payload.len() as u64 > MAX_DECOMPRESSED_BYTES {
+                    return Err(ProtocolError::Compression(format!(
+                        "decompressed payload exceeds {} bytes",
+                        MAX_DECOMPRESSED_BYTES
+                    )));
+                }
+                self.payload = payload;
+            }
+            Compression::Custom => {
+                return Err(ProtocolError::UnsupportedCompression(self.compression))
+            }
+        }
+        self.compression = Compression::None;
+        Ok(self)
+    }
+
+    /// 编码为二进制帧；字段与 message_type / flags 不一致时返回错误
+    pub fn encode(&self) -> Result<Vec<u8>, ProtocolError> {
+        let mut frame = Vec::with_capacity(16 + self.payload.len());
+        frame.push((PROTOCOL_VERSION << 4) | DEFAULT_HEADER_SIZE);
+        frame.push((self.message_type.to_bits() << 4) | self.flags.to_bits());
+        frame.push((self.serialization.to_bits() << 4) | self.compression.to_bits());
+        frame.push(0x00);
+
+        match (self.flags.has_sequence(), self.sequence) {
+            (true, Some(sequence)) => frame.extend_from_slice(&sequence.to_be_bytes()),
+            (true, None) => return Err(ProtocolError::MissingField("sequence")),
+            (false, Some(_)) => return Err(ProtocolError::UnexpectedField("sequence")),
+            (false, None) => {}
+        }
+
+        match (self.message_type == MessageType::Error, self.error_code) {
+            (true, Some(code)) => frame.extend_from_slice(&code.to_be_bytes()),
+            (true, None) => return Err(ProtocolError::MissingField("error_code")),
+            (false, Some(_)) => return Err(ProtocolError::UnexpectedField("error_code")),
+            (false, None) => {}
+        }
+
+        match (self.flags == Flags::WithEvent, self.event) {
+            (true, Some(event)) => {
+                frame.extend_from_slice(&event.code().to_be_bytes());
+                match (event.carries_id(), &self.id) {
+                    (true, Some(id)) => write_sized(&mut frame, "id", id.as_bytes())?,
+                    (true, None) => return Err(ProtocolError::MissingField("id")),
+                    (false, Some(_)) => return Err(ProtocolError::UnexpectedField("id")),
+                    (false, None) => {}
+                }
+            }
+            (true, None) => return Err(ProtocolError::MissingField("event")),
+            (false, Some(_)) => return Err(ProtocolError::UnexpectedField("event")),
+            (false, None) => {
+                if self.id.is_some() {
+                    return Err(ProtocolError::UnexpectedField("id"));
+                }
+            }
+        }
+
+        write_sized(&mut frame, "payload", &self.payload)?;
+        Ok(frame)
+    }
+
+    /// 解析二进制帧；任何长度不符都会返回错误，不会 panic 也不会截断
+    pub fn decode(data: &[u8]) -> Result<Self, ProtocolError> {
+        let mut reader = Reader::new(data);
+        let header = reader.take("header", 4)?;
+
+        let version = header[0] >> 4;
+        if version != PROTOCOL_VERSION {
+            return Err(ProtocolError::UnsupportedVersion(version));
+        }
+        let header_size = header[0] & 0x0f;
+        if header_size == 0 {
+            return Err(ProtocolError::InvalidHeaderSize(header_size));
+        }
+        let message_type = MessageType::from_bits(header[1] >> 4)?;
+        let flags = Flags::from_bits(header[1] & 0x0f)?;
+        let serialization = Serialization::from_bits(header[2] >> 4)?;
+        let compression = Compression::from_bits(header[2] & 0x0f)?;
+
+        // 跳过 header 扩展字段
+        reader.take("header extension", (header_size as usize - 1) * 4)?;
+
+        let sequence = if flags.has_sequence() {
+            Some(reader.read_i32("sequence")?)
+        } else {
+            None
+        };
+
+        let error_code = if message_type == MessageType::Error {
+            Some(reader.read_u32("error_code")?)
+        } else {
+            None
+        };
+
+        let mut event = None;
+        let mut id = None;
+        if flags == Flags::WithEvent {
+            let parsed = Event::from_code(reader.read_i32("event")?);
+            if parsed.carries_id() {
+                let bytes = reader.read_sized("id")?;
+                id = Some(String::from_utf8(bytes.to_vec()).map_err(|_| ProtocolError::InvalidId)?);
+            }
+            event = Some(parsed);
+        }
+
+        let payload = reader.read_sized("payload")?.to_vec();
+
+        if reader.remaining() > 0 {
+            return Err(ProtocolError::TrailingBytes(reader.remaining()));
+        }
+
+        Ok(Self {
+            message_type,
+            flags,
+            serialization,
+            compression,
+            sequence,
+            error_code,
+            event,
+            id,
+            payload,
+        })
+    }
+}
+
+fn write_sized(
+    frame: &mut Vec<u8>,
+    field: &'static str,
+    bytes: &[u8],
+) -> Result<(), ProtocolError> {
+    let len = u32::try_from(bytes.len()).map_err(|_| ProtocolError::FieldTooLarge {
+        field,
+        len: bytes.len(),
+    })?;
+    frame.extend_from_slice(&len.to_be_bytes());
+    frame.extend_from_slice(bytes);
+    Ok(())
+}
+
+struct Reader<'a> {
+    data: &'a [u8],
+    offset: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Self { data, offset: 0 }
+    }
+
+    fn remaining(&self) -> usize {
+        self.data.len() - self.offset
+    }
+
+    fn take(&mut self, field: &'static str, len: usize) -> Result<&'a [u8], ProtocolError> {
+        if len > self.remaining() {
+            return Err(ProtocolError::Truncated {
+                field,
+                needed: len,
+                available: self.remaining(),
+            });
+        }
+        let bytes = &self.data[self.offset..self.offset + len];
+        self.offset += len;
+        Ok(bytes)
+    }
+
+    fn read_u32(&mut self, field: &'static str) -> Result<u32, ProtocolError> {
+        let bytes = self.take(field, 4)?;
+        Ok(u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+
+    fn read_i32(&mut self, field: &'static str) -> Result<i32, ProtocolError> {
+        self.read_u32(field).map(|value| value as i32)
+    }
+
+    fn read_sized(&mut self, field: &'static str) -> Result<&'a [u8], ProtocolError> {
+        let len = self.read_u32(field)? as usize;
+        self.take(field, len)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    fn message_type() -> impl Strategy<Value = MessageType> {
+        prop_oneof![
+            Just(MessageType::FullClientRequest),
+            Just(MessageType::AudioOnlyRequest),
+            Just(MessageType::FullServerResponse),
+            Just(MessageType::AudioOnlyResponse),
+            Just(MessageType::FrontEndResult),
+            Just(MessageType::Error),
+        ]
+    }
+
+    fn flags() -> impl Strategy<Value = Flags> {
+        prop_oneof![
+            Just(Flags::NoSequence),
+            Just(Flags::PositiveSequence),
+            Just(Flags::LastNoSequence),
+            Just(Flags::NegativeSequence),
+            Just(Flags::WithEvent),
+        ]
+    }
+
+    fn serialization() -> impl Strategy<Value = Serialization> {
+        prop_oneof![
+            Just(Serialization::None),
+            Just(Serialization::Json),
+            Just(Serialization::Thrift),
+            Just(Serialization::Custom),
+        ]
+    }
+
+    fn compression() -> impl Strategy<Value = Compression> {
+        prop_oneof![
+            Just(Compression::None),
+            Just(Compression::Gzip),
+            Just(Compression::Custom),
+        ]
+    }
+
+    /// 生成字段与 message_type / flags 保持一致的合法帧
+    fn frame() -> impl Strategy<Value = Frame> {
+        (
+            message_type(),
+            flags(),
+            serialization(),
+            compression(),
+            any::<i32>(),
+            any::<u32>(),
+            any::<i32>(),
+            "[a-z0-9-]{0,36}",
+            proptest::collection::vec(any::<u8>(), 0..256),
+        )
+            .prop_map(
+                |(message_type, flags, serialization, compression, seq, code, ev, id, payload)| {
+                    let event = (flags == Flags::WithEvent).then(|| Event::from_code(ev));
+                    Frame {
+                        message_type,
+                        flags,
+                        serialization,
+                        compression,
+                        sequence: flags.has_sequence().then_some(seq),
+                        error_code: (message_type == MessageType::Error).then_some(code),
+                        event,
+                        id: event.filter(|e| e.carries_id()).map(|_| id),
+                        payload,
+                    }
+                },
+            )
+    }
+
+    proptest! {
+        #[test]
+        fn round_trip(frame in frame()) {
+            let bytes = frame.encode().unwrap();
+            prop_assert_eq!(Frame::decode(&bytes).unwrap(), frame);
+        }
+
+        #[test]
+        fn decode_never_panics(data in proptest::collection::vec(any::<u8>(), 0..512)) {
+            let _ = Frame::decode(&data);
+        }
+
+        #[test]
+        fn truncated_frames_are_rejected(frame in frame(), cut in 1usize..64) {
+            let bytes = frame.encode().unwrap();
+            let end = bytes.len().saturating_sub(cut);
+            prop_assert!(Frame::decode(&bytes[..end]).is_err());
+        }
+
+        #[test]
+        fn gzip_round_trip(frame in frame()) {
+            let mut plain = frame;
+            plain.compression = Compression::None;
+            let bytes = plain.clone().compress(Compression::Gzip).unwrap().encode().unwrap();
+            let decoded = Frame::decode(&bytes).unwrap();
+            prop_assert_eq!(decoded.compression, Compression::Gzip);
+            prop_assert_eq!(decoded.decompress().unwrap(), plain);
+        }
+
+        #[test]
+        fn event_codes_round_trip(code in any::<i32>()) {
+            prop_assert_eq!(Event::from_code(code).code(), code);
+        }
+    }
+
+    #[test]
+    fn matches_legacy_sauc_layout() {
+        let bytes = Frame::audio_only_request(vec![1, 2, 3], true)
+            .encode()
+            .unwrap();
+        assert_eq!(bytes, vec![0x11, 0x22, 0x00, 0x00, 0, 0, 0, 3, 1, 2, 3]);
+    }
+
+    #[test]
+    fn negative_sequence_marks_last_packet() {
+        let frame = Frame::audio_only_request(vec![0; 4], false).with_sequence(-3);
+        let decoded = Frame::decode(&frame.encode().unwrap()).unwrap();
+        assert_eq!(decoded.sequence, Some(-3));
+        assert!(decoded.is_last());
+    }
+
+    #[test]
+    fn rejects_oversized_payload_length() {
+        let mut bytes = Frame::client_event(Event::StartConnection, None, b"{}".to_vec())
+            .encode()
+            .unwrap();
+        bytes.push(0xff);
+        assert_eq!(Frame::decode(&bytes), Err(ProtocolError::TrailingBytes(1)));
+        bytes.truncate(bytes.len() - 2);
+        assert!(matches!(
+            Frame::decode(&bytes),
+            Err(ProtocolError::Truncated {
+                field: "payload",
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn corrupt_gzip_payload_is_an_error() {
+        let mut frame = Frame::full_client_request(b"not gzip".to_vec());
+        frame.compression = Compression::Gzip;
+        assert!(matches!(
+            frame.decompress(),
+            Err(ProtocolError::Compression(_))
+        ));
+    }
+
+    #[test]
+    fn oversized_gzip_payload_is_an_error() {
+        let bomb = Frame::full_client_request(vec![0; MAX_DECOMPRESSED_BYTES as usize + 1])
+            .compress(Compression::Gzip)
+            .unwrap();
+        assert!(bomb.payload.len() < 64 * 1024);
+        assert!(matches!(
+            bomb.decompress(),
+            Err(ProtocolError::Compression(_))
+        ));
+
+        let fits = Frame::full_client_request(vec![0; 1024])
+            .compress(Compression::Gzip)
+            .unwrap();
+        assert_eq!(fits.decompress().unwrap().payload.len(), 1024);
+    }
+
+    #[test]
+    fn mismatched_fields_fail_to_encode() {
+        let mut frame = Frame::client_event(Event::StartSession, None, Vec::new());
+        assert_eq!(frame.encode(), Err(ProtocolError::MissingField("id")));
+        frame.event = None;
+        frame.id = None;
+        assert_eq!(frame.encode(), Err(ProtocolError::MissingField("event")));
+    }
+}
@@ -3,8 +3,12 @@ import { getCurrentWindow } from '@tauri-apps/api/window';
 import { CollapsedView } from './components/CollapsedView';
 import { ExpandedView } from './components/ExpandedView';
 import { useAppStore } from '@/lib/store';
-import { appendVoiceSessionEntry, startVoiceSession } from '@/lib/tauri';
-import { useAudio } from '@/hooks/useAudio';
+import {
+  appendVoiceSessionEntry,
+  startVoiceSession,
+  syncUiLanguage,
+} from '@/lib/tauri';
+import { useAudio, type LiveSpeech } from '@/hooks/useAudio';
 import { useAgent } from '@/hooks/useAgent';
 
 function App() {
@@ -14,11 +18,25 @@ function App() {
   const lastPromptRef = useRef('');
   const setVoiceSessionId = useAppStore((state) => state.setVoiceSessionId);
   const setOutput = useAppStore((state) => state.setOutput);
-  const { speak } = useAudio();
+  const { speak, startLiveSpeech } = useAudio();
+  // Agent 运行期间边输出边播报的会话，不支持时为 null，结束后整段播报
+  const liveSpeechRef = useRef<LiveSpeech | null>(null);
   const { runAgent } = useAgent({
+    onOutput: (chunk) => {
+      liveSpeechRef.current?.push(chunk);
+    },
+    onError: () => {
+      liveSpeechRef.current?.cancel();
+      liveSpeechRef.current = null;
+    },
     onFinished: (fullText) => {
+      const liveSpeech = liveSpeechRef.current;
+      liveSpeechRef.current = null;
       const cleaned = fullText.trimEnd();
-      if (!cleaned) return;
+      if (!cleaned) {
+        liveSpeech?.cancel();
+        return;
+      }
 
       void appendVoiceSessionEntry({
         role: 'assistant',
@@ -34,7 +52,11 @@ function App() {
         : cleaned;
       setOutput(finalOutput);
 
-      void speak(cleaned);
+      if (liveSpeech) {
+        void liveSpeech.finish();
+      } else {
+        void speak(cleaned);
+      }
     },
   });
 
@@ -44,10 +66,12 @@ function App() {
       if (!cleaned) return;
 
       lastPromptRef.current = cleaned;
+      liveSpeechRef.current?.cancel();
+      liveSpeechRef.current = await startLiveSpeech();
       const prefill = `你：${cleaned}\n\nAgent：`;
       await runAgent(cleaned, prefill);
     },
-    [runAgent]
+    [runAgent, startLiveSpeech]
   );
 
   // 展开时调整窗口大小
@@ -79,6 +103,12 @@ function App() {
     lastOutputRef.current = output;
   }, [isExpanded, output, handleExpand]);
 
+  useEffect(() => {
+    syncUiLanguage().catch((error) => {
+      console.error('Failed to sync UI language:', error);
+    });
+  }, []);
+
   useEffect(() => {
     let isActive = true;
 
@@ -5,9 +5,12 @@ import {
   onAgentOutput,
   onAgentFinished,
   onAgentError,
+  errorMessage,
 } from '@/lib/tauri';
 
 interface UseAgentOptions {
+  /** Agent 每输出一行调用一次，可用于边生成边播报 */
+  onOutput?: (chunk: string) => void;
   onFinished?: (fullText: string) => void;
   onError?: (message: string) => void;
 }
@@ -15,7 +18,7 @@ interface UseAgentOptions {
 export function useAgent(options: UseAgentOptions = {}) {
   const { setStatus, appendOutput, clearOutput, setError, setIsRecording } =
     useAppStore();
-  const { onFinished, onError } = options;
+  const { onOutput, onFinished, onError } = options;
   const finishHandledRef = useRef(false);
 
   // 监听 Agent 事件
@@ -36,6 +39,7 @@ export function useAgent(options: UseAgentOptions = {}) {
     // 监听输出
     registerListener(onAgentOutput((chunk) => {
       appendOutput(chunk);
+      onOutput?.(chunk);
     }));
 
     // 监听完成
@@ -49,19 +53,19 @@ export function useAgent(options: UseAgentOptions = {}) {
     }));
 
     // 监听错误
-    registerListener(onAgentError((message) => {
+    registerListener(onAgentError((error) => {
       finishHandledRef.current = true;
-      console.error('[Agent] error event', message);
-      setError(message);
+      console.error('[Agent] error event', error);
+      setError(error.message);
       setIsRecording(false);
-      onError?.(message);
+      onError?.(error.message);
     }));
 
     return () => {
       isActive = false;
       unlisteners.forEach((unlisten) => unlisten());
     };
-  }, [appendOutput, setStatus, setError, setIsRecording, onFinished, onError]);
+  }, [appendOutput, setStatus, setError, setIsRecording, onOutput, onFinished, onError]);
 
   const runAgent = useCallback(
     async (prompt: string, prefillOutput?: string) => {
@@ -83,13 +87,18 @@ export function useAgent(options: UseAgentOptions = {}) {
           onFinished?.(fullText);
         }
       } catch (error) {
+        // 错误事件已先到达时不再重复通知
+        const handled = finishHandledRef.current;
         finishHandledRef.current = true;
         console.error('[Agent] invoke failed', error);
-        setError((error as Error).message);
+        setError(errorMessage(error));
         setIsRecording(false);
+        if (!handled) {
+          onError?.(errorMessage(error));
+        }
       }
     },
-    [clearOutput, appendOutput, setStatus, setError, setIsRecording, onFinished]
+    [clearOutput, appendOutput, setStatus, setError, setIsRecording, onFinished, onError]
   );
 
   return {
@@ -1,13 +1,38 @@
 import { useRef, useCallback } from 'react';
 import { useAppStore } from '@/lib/store';
 import {
+  cancelTts,
+  closeTtsSession,
+  onTtsStreamCancelled,
   onTtsStreamChunk,
   onTtsStreamError,
   onTtsStreamFinished,
+  errorMessage,
+  openTtsSession,
+  pushTtsSession,
   speakText,
   speakTextStream,
+  ttsOutputFormat,
+  type TtsAudioFormat,
 } from '@/lib/tauri';
 
+/** 边生成边播报的会话句柄 */
+export interface LiveSpeech {
+  /** 追加一段文本（如 Agent 的流式输出） */
+  push: (text: string) => void;
+  /** 文本结束，等待剩余音频合成完毕 */
+  finish: () => Promise<void>;
+  /** 放弃本次播报 */
+  cancel: () => void;
+}
+
+const AUDIO_MIME_TYPES: Record<TtsAudioFormat, string> = {
+  mp3: 'audio/mp3',
+  ogg_opus: 'audio/ogg; codecs=opus',
+  wav: 'audio/wav',
+  pcm: 'audio/L16',
+};
+
 export function useAudio() {
   const audioRef = useRef<HTMLAudioElement | null>(null);
   const audioUrlRef = useRef<string | null>(null);
@@ -92,11 +117,14 @@ export function useAudio() {
   const canStream = useCallback(() => {
     if (typeof window === 'undefined') return false;
     if (typeof MediaSource === 'undefined') return false;
+    // 流式播放基于 MediaSource 拼接 mp3 分块，其他格式走整段播放
+    if (ttsOutputFormat(config) !== 'mp3') return false;
     return MediaSource.isTypeSupported('audio/mpeg');
-  }, []);
+  }, [config]);
 
-  const playStream = useCallback(
-    async (text: string) => {
+  /** 准备 MediaSource 播放并监听后端下发的音频分块 */
+  const beginStream = useCallback(
+    async () => {
       streamErrorRef.current = null;
       streamActiveRef.current = true;
       streamQueueRef.current = [];
@@ -104,7 +132,7 @@ export function useAudio() {
       streamChunkCountRef.current = 0;
       playbackStartedRef.current = false;
 
-      console.log('[TTS] stream start', { length: text.length });
+      console.log('[TTS] stream start');
 
       const mediaSource = new MediaSource();
       mediaSourceRef.current = mediaSource;
@@ -168,7 +196,7 @@ export function useAudio() {
         { once: true }
       );
 
-      const [onChunk, onFinished, onError] = await Promise.all([
+      const [onChunk, onFinished, onError, onCancelled] = await Promise.all([
         onTtsStreamChunk((chunk) => {
           if (!streamActiveRef.current) return;
           if (streamChunkCountRef.current === 0) {
@@ -188,40 +216,102 @@ export function useAudio() {
           streamEndedRef.current = true;
           appendNextChunk();
         }),
-        onTtsStreamError((message) => {
-          console.error('TTS stream error:', message);
-          streamErrorRef.current = new Error(message);
+        onTtsStreamError((error) => {
+          console.error('TTS stream error:', error);
+          streamErrorRef.current = new Error(error.message);
           setStatus('idle');
           cleanupStream();
         }),
+        onTtsStreamCancelled((totalBytes) => {
+          if (!streamActiveRef.current) return;
+          console.log('[TTS] stream cancelled', { totalBytes });
+          cleanupStream();
+        }),
       ]);
 
-      streamUnlistenersRef.current = [onChunk, onFinished, onError];
+      streamUnlistenersRef.current = [onChunk, onFinished, onError, onCancelled];
+    },
+    [appendNextChunk, cleanupStream, setStatus]
+  );
+
+  const playStream = useCallback(
+    async (text: string) => {
+      await beginStream();
 
       console.log('[TTS] stream invoke backend', { length: text.length });
       try {
         if (!streamActiveRef.current) {
           return;
         }
-        await speakTextStream(text, config?.tts.voiceType);
+        // 音色由后端按当前提供方的配置选择
+        await speakTextStream(text);
       } catch (error) {
         streamErrorRef.current =
-          error instanceof Error ? error : new Error('TTS stream failed');
+          error instanceof Error
+            ? error
+            : new Error(errorMessage(error, 'TTS stream failed'));
       }
 
       if (streamErrorRef.current) {
         throw streamErrorRef.current;
       }
     },
-    [appendNextChunk, cleanupStream, setStatus, config]
+    [beginStream]
   );
 
+  /** 边生成边播报：打开增量合成会话；不支持流式播放或提供方不支持会话时返回 null */
+  const startLiveSpeech = useCallback(async (): Promise<LiveSpeech | null> => {
+    // 增量合成会话只支持火山引擎
+    if (!canStream() || (config?.tts.provider ?? 'volcengine') !== 'volcengine') {
+      return null;
+    }
+
+    try {
+      cleanupStream();
+      await beginStream();
+      await openTtsSession();
+    } catch (error) {
+      console.error('[TTS] live session open failed', error);
+      cleanupStream();
+      return null;
+    }
+
+    // 按顺序推送文本，结束会话前等待全部送达
+    let pushChain = Promise.resolve();
+    return {
+      push: (text) => {
+        pushChain = pushChain
+          .then(() => pushTtsSession(text))
+          .catch((error) => {
+            console.error('[TTS] live session push failed', error);
+          });
+      },
+      finish: async () => {
+        setStatus('speaking');
+        await pushChain;
+        try {
+          await closeTtsSession();
+        } catch (error) {
+          console.error('[TTS] live session close failed', error);
+          cleanupStream();
+          setStatus('idle');
+        }
+      },
+      cancel: () => {
+        void cancelTts();
+        cleanupStream();
+      },
+    };
+  }, [canStream, config, cleanupStream, beginStream, setStatus]);
+
   const playOnce = useCallback(
     async (text: string) => {
       console.log('[TTS] playOnce start', { length: text.length });
-      const audioBytes = await speakText(text, config?.tts.voiceType);
+      const audioBytes = await speakText(text);
 
-      const audioBlob = new Blob([audioBytes], { type: 'audio/mp3' });
+      const audioBlob = new Blob([audioBytes], {
+        type: AUDIO_MIME_TYPES[ttsOutputFormat(config)],
+      });
       const audioUrl = URL.createObjectURL(audioBlob);
       audioUrlRef.current = audioUrl;
 
@@ -290,12 +380,14 @@ export function useAudio() {
   );
 
   const stop = useCallback(() => {
+    void cancelTts();
     cleanupStream();
     setStatus('idle');
   }, [cleanupStream, setStatus]);
 
   return {
     speak,
+    startLiveSpeech,
     stop,
   };
 }
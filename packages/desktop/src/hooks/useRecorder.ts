@@ -1,18 +1,39 @@
 import { useRef, useCallback } from 'react';
+import type { UnlistenFn } from '@tauri-apps/api/event';
 import { useAppStore } from '@/lib/store';
-import { prepareSttAudio } from '@/lib/audio';
-import { appendVoiceSessionEntry, transcribeAudio } from '@/lib/tauri';
+import { createPcmEncoder, STREAM_MIME_TYPE } from '@/lib/audio';
+import {
+  appendVoiceSessionEntry,
+  cancelTts,
+  errorMessage,
+  finishSttStream,
+  onSttFinal,
+  onSttPartial,
+  onSttStreamError,
+  onSttUtteranceEnd,
+  pushSttStream,
+  startSttStream,
+} from '@/lib/tauri';
 
 interface UseRecorderOptions {
   onTranscribed?: (text: string) => void;
 }
 
+/** 一次录音占用的采集节点与事件监听 */
+interface Capture {
+  stream: MediaStream;
+  context: AudioContext;
+  source: MediaStreamAudioSourceNode;
+  processor: ScriptProcessorNode;
+  unlisten: UnlistenFn[];
+}
+
 export function useRecorder(options: UseRecorderOptions = {}) {
-  const mediaRecorderRef = useRef<MediaRecorder | null>(null);
-  const chunksRef = useRef<Blob[]>([]);
-  const { setStatus, setError, setIsRecording } = useAppStore();
+  const captureRef = useRef<Capture | null>(null);
+  // 按顺序推送音频分段，结束录音前等待全部送达
+  const pushChainRef = useRef<Promise<void>>(Promise.resolve());
+  const { setStatus, setError, setIsRecording, setOutput } = useAppStore();
   const { onTranscribed } = options;
-  const recorderMimeTypeRef = useRef<string>('');
 
   const getAudioStream = useCallback(async (): Promise<MediaStream> => {
     if (navigator.mediaDevices?.getUserMedia) {
@@ -33,104 +54,130 @@ export function useRecorder(options: UseRecorderOptions = {}) {
     throw new Error('当前环境不支持音频采集接口');
   }, []);
 
+  const releaseCapture = useCallback(() => {
+    const capture = captureRef.current;
+    captureRef.current = null;
+    if (!capture) return;
+
+    capture.processor.onaudioprocess = null;
+    capture.processor.disconnect();
+    capture.source.disconnect();
+    capture.stream.getTracks().forEach((track) => track.stop());
+    capture.unlisten.forEach((unlisten) => unlisten());
+    void capture.context.close();
+  }, []);
+
+  const stopRecording = useCallback(async () => {
+    if (!captureRef.current) return;
+    console.log('[Recorder] stopRecording');
+    releaseCapture();
+    setIsRecording(false);
+
+    try {
+      setStatus('transcribing');
+      await pushChainRef.current;
+      const transcript = await finishSttStream();
+
+      const cleaned = transcript.trim();
+      if (!cleaned) {
+        setStatus('idle');
+        return;
+      }
+
+      void appendVoiceSessionEntry({
+        role: 'user',
+        text: cleaned,
+        source: 'voice',
+      }).catch((error) => {
+        console.error('Failed to append voice session entry:', error);
+      });
+
+      if (onTranscribed) {
+        void onTranscribed(cleaned);
+      } else {
+        setStatus('idle');
+      }
+    } catch (error) {
+      console.error('[Recorder] transcribe failed', error);
+      setError(errorMessage(error));
+    }
+  }, [releaseCapture, setIsRecording, setStatus, setError, onTranscribed]);
+
   const startRecording = useCallback(async () => {
     try {
       console.log('[Recorder] startRecording');
+      // 开口即打断正在播报的回复
+      void cancelTts();
       setStatus('recording');
       setIsRecording(true);
-      if (typeof MediaRecorder === 'undefined') {
-        throw new Error('当前环境不支持 MediaRecorder');
+
+      const AudioContextCtor =
+        window.AudioContext ||
+        (window as typeof window & { webkitAudioContext?: typeof AudioContext })
+          .webkitAudioContext;
+      if (!AudioContextCtor) {
+        throw new Error('当前环境不支持 AudioContext');
       }
 
       const stream = await getAudioStream();
-      const preferredMimeTypes = [
-        'audio/webm;codecs=opus',
-        'audio/ogg;codecs=opus',
-        'audio/webm',
-        'audio/ogg',
-        'audio/mp4',
-        'audio/aac',
-        'audio/mpeg',
-      ];
-      const supportedMimeType = preferredMimeTypes.find((type) =>
-        MediaRecorder.isTypeSupported(type)
-      );
-      const mediaRecorder = supportedMimeType
-        ? new MediaRecorder(stream, { mimeType: supportedMimeType })
-        : new MediaRecorder(stream);
-      recorderMimeTypeRef.current = supportedMimeType ?? '';
-
-      chunksRef.current = [];
-
-      mediaRecorder.ondataavailable = (event) => {
-        if (event.data.size > 0) {
-          chunksRef.current.push(event.data);
-        }
-      };
-
-      mediaRecorder.onstop = async () => {
-        console.log('[Recorder] onstop');
-        setIsRecording(false);
-        // 停止所有音轨
+      // 先建立识别连接，录音开始后的每个分段都能直接送出
+      try {
+        await startSttStream(STREAM_MIME_TYPE);
+      } catch (error) {
         stream.getTracks().forEach((track) => track.stop());
+        throw error;
+      }
 
-        // 合并音频数据
-        const blobType =
-          mediaRecorder.mimeType || recorderMimeTypeRef.current || undefined;
-        const audioBlob = new Blob(chunksRef.current, { type: blobType });
-
-        try {
-          setStatus('transcribing');
-          const mimeType =
-            mediaRecorder.mimeType || recorderMimeTypeRef.current || '';
-          const { audioBytes, mimeType: sttMimeType } = await prepareSttAudio(
-            audioBlob,
-            mimeType
-          );
-          const transcript = await transcribeAudio(audioBytes, sttMimeType);
-
-          const cleaned = transcript.trim();
-          if (!cleaned) {
-            setStatus('idle');
-            return;
-          }
-
-          void appendVoiceSessionEntry({
-            role: 'user',
-            text: cleaned,
-            source: 'voice',
-          }).catch((error) => {
-            console.error('Failed to append voice session entry:', error);
+      // 识别中间结果实时显示，说完一句（PCM 输入的静音检测）自动结束录音
+      const unlisten = await Promise.all([
+        onSttPartial((text) => setOutput(`你：${text}`)),
+        onSttFinal((text) => setOutput(`你：${text}`)),
+        onSttUtteranceEnd(() => {
+          void stopRecording();
+        }),
+        onSttStreamError((error) => {
+          releaseCapture();
+          setIsRecording(false);
+          setError(errorMessage(error));
+        }),
+      ]);
+
+      const context = new AudioContextCtor();
+      const source = context.createMediaStreamSource(stream);
+      const processor = context.createScriptProcessor(4096, 1, 1);
+      const encoder = createPcmEncoder(context.sampleRate);
+      pushChainRef.current = Promise.resolve();
+
+      processor.onaudioprocess = (event) => {
+        const chunk = encoder(event.inputBuffer.getChannelData(0));
+        if (chunk.length === 0) return;
+        pushChainRef.current = pushChainRef.current
+          .then(() => pushSttStream(Array.from(chunk)))
+          .catch((error) => {
+            console.error('[Recorder] push chunk failed', error);
           });
-
-          if (onTranscribed) {
-            void onTranscribed(cleaned);
-          } else {
-            setStatus('idle');
-          }
-        } catch (error) {
-          console.error('[Recorder] transcribe failed', error);
-          setError((error as Error).message);
-        }
       };
+      source.connect(processor);
+      // ScriptProcessor 需连到输出才会被调度，输出缓冲保持静音
+      processor.connect(context.destination);
 
-      mediaRecorderRef.current = mediaRecorder;
-      mediaRecorder.start(100); // 每 100ms 收集一次数据
-      console.log('[Recorder] mediaRecorder started', mediaRecorder.mimeType);
+      captureRef.current = { stream, context, source, processor, unlisten };
+      console.log('[Recorder] streaming started', context.sampleRate);
     } catch (error) {
       console.error('[Recorder] startRecording failed', error);
+      releaseCapture();
       setIsRecording(false);
-      setError((error as Error).message || '无法访问麦克风，请检查权限设置');
-    }
-  }, [setStatus, setIsRecording, setError, getAudioStream, onTranscribed]);
-
-  const stopRecording = useCallback(async () => {
-    if (mediaRecorderRef.current && mediaRecorderRef.current.state !== 'inactive') {
-      console.log('[Recorder] stopRecording');
-      setIsRecording(false);
-      mediaRecorderRef.current.stop();
+      setError(errorMessage(error, '无法访问麦克风，请检查权限设置'));
     }
-  }, [setIsRecording]);
+  }, [
+    setStatus,
+    setIsRecording,
+    setError,
+    setOutput,
+    getAudioStream,
+    releaseCapture,
+    stopRecording,
+  ]);
 
   return {
     startRecording,
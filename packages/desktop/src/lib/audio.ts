@@ -1,5 +1,7 @@
 const TARGET_SAMPLE_RATE = 16000;
 const TARGET_MIME_TYPE = 'audio/wav';
+/** 流式识别上传的格式：16kHz 单声道 16 位小端 PCM；后端按参数转为识别请求声明的格式 */
+export const STREAM_MIME_TYPE = `audio/pcm;rate=${TARGET_SAMPLE_RATE};channels=1`;
 
 function decodeAudioData(
   context: AudioContext,
@@ -120,3 +122,41 @@ export async function prepareSttAudio(
     );
   }
 }
+
+/**
+ * 把麦克风采集的浮点样本逐段转换为 16kHz 16 位 PCM
+ *
+ * 线性插值重采样，跨分段保留插值位置，避免分段边界处的跳变。
+ */
+export function createPcmEncoder(
+  inputSampleRate: number
+): (samples: Float32Array) => Uint8Array {
+  const step = inputSampleRate / TARGET_SAMPLE_RATE;
+  let position = 0;
+  let previous = 0;
+
+  return (samples) => {
+    const output: number[] = [];
+    // position 相对本段开头，-1 表示上一段的最后一个样本
+    while (position < samples.length - 1) {
+      const index = Math.floor(position);
+      const fraction = position - index;
+      const left = index < 0 ? previous : samples[index];
+      const right = samples[index + 1];
+      output.push(left + (right - left) * fraction);
+      position += step;
+    }
+    position -= samples.length;
+    if (samples.length > 0) {
+      previous = samples[samples.length - 1];
+    }
+
+    const bytes = new Uint8Array(output.length * 2);
+    const view = new DataView(bytes.buffer);
+    output.forEach((value, i) => {
+      const sample = Math.max(-1, Math.min(1, value));
+      view.setInt16(i * 2, sample < 0 ? sample * 0x8000 : sample * 0x7fff, true);
+    });
+    return bytes;
+  };
+}
@@ -1,4 +1,5 @@
 import { create } from 'zustand';
+import type { TtsAudioParams, TtsProviderKind } from '@/lib/tauri';
 
 export type AppStatus =
   | 'idle'
@@ -25,8 +26,10 @@ interface AppState {
       ttsResourceId: string;
     };
     tts: {
+      provider?: TtsProviderKind;
       voiceType: string;
       autoSpeak: boolean;
+      audio?: TtsAudioParams;
     };
   } | null;
 
@@ -1,22 +1,143 @@
 import { invoke } from '@tauri-apps/api/core';
 import { listen, UnlistenFn } from '@tauri-apps/api/event';
 
+// ============ 错误模型 ============
+
+export type AppErrorCategory =
+  | 'config'
+  | 'auth'
+  | 'quota'
+  | 'network'
+  | 'protocol'
+  | 'input'
+  | 'process'
+  | 'io'
+  | 'window'
+  | 'state'
+  | 'internal';
+
+/** 命令失败时的返回值，也是各 `*-error` 事件的负载 */
+export interface AppError {
+  code: string;
+  category: AppErrorCategory;
+  message: string;
+  details: string | null;
+  retryable: boolean;
+}
+
+export function isAppError(value: unknown): value is AppError {
+  return (
+    typeof value === 'object' &&
+    value !== null &&
+    typeof (value as AppError).code === 'string' &&
+    typeof (value as AppError).message === 'string'
+  );
+}
+
+/** 把命令抛出的错误转为可展示的文本 */
+export function errorMessage(error: unknown, fallback = '未知错误'): string {
+  if (isAppError(error)) return error.message;
+  if (error instanceof Error) return error.message || fallback;
+  if (typeof error === 'string') return error || fallback;
+  return fallback;
+}
+
 // ============ 配置管理 ============
 
+export type TtsAudioFormat = 'mp3' | 'ogg_opus' | 'pcm' | 'wav';
+
+export interface TtsAudioParams {
+  speedRatio?: number;
+  volumeRatio?: number;
+  pitchRatio?: number;
+  format?: TtsAudioFormat;
+  sampleRate?: number;
+  emotion?: string;
+}
+
+export type SttProviderKind = 'volcengine' | 'openai' | 'command';
+export type TtsProviderKind = 'volcengine' | 'openai' | 'command';
+
 export interface VolcengineConfig {
   appId: string;
   accessToken: string;
   resourceId?: string;
+  enableGzip?: boolean;
   stt: {
+    provider?: SttProviderKind;
     resourceId: string;
+    modelName?: string;
+    enableItn?: boolean;
+    enablePunc?: boolean;
+    enableDdc?: boolean;
+    language?: string;
+    sampleRate?: number;
+    channel?: number;
+    endWindowSize?: number;
+    enableHotwords?: boolean;
+    projectDir?: string;
+    vad?: {
+      enabled?: boolean;
+      marginDb?: number;
+      minDb?: number;
+      minSpeechMs?: number;
+      endSilenceMs?: number;
+      paddingMs?: number;
+    };
+    openai?: {
+      baseUrl?: string;
+      apiKey?: string;
+      model?: string;
+      timestamps?: boolean;
+    };
+    command?: {
+      program?: string;
+      args?: string[];
+      model?: string;
+      ffmpeg?: string;
+      timeoutSecs?: number;
+    };
   };
   tts: {
+    provider?: TtsProviderKind;
     voiceType: string;
     cluster: string;
     resourceId: string;
+    audio?: TtsAudioParams;
+    normalize?: 'off' | 'basic' | 'full';
+    segmenter?: {
+      minChars?: number;
+      maxChars?: number;
+    };
+    cache?: {
+      enabled?: boolean;
+      maxSizeMb?: number;
+      maxAgeDays?: number;
+    };
+    openai?: {
+      baseUrl?: string;
+      apiKey?: string;
+      model?: string;
+      voice?: string;
+    };
+    command?: {
+      program?: string;
+      args?: string[];
+      voice?: string;
+      sampleRate?: number;
+      timeoutSecs?: number;
+    };
   };
 }
 
+/** 语音合成实际输出的音频格式：本地命令固定输出 wav */
+export function ttsOutputFormat(
+  config: { tts: { provider?: TtsProviderKind; audio?: TtsAudioParams } } | null
+): TtsAudioFormat {
+  if (config?.tts.provider === 'command') return 'wav';
+  return config?.tts.audio?.format ?? 'mp3';
+}
+
 export async function getVolcengineConfig(): Promise<VolcengineConfig | null> {
   try {
     return await invoke<VolcengineConfig>('get_volcengine_config');
@@ -31,6 +152,23 @@ export async function saveVolcengineConfig(
   await invoke('save_volcengine_config', { config });
 }
 
+/** 后端消息使用的界面语言 */
+export type UiLanguage = 'zh-CN' | 'en-US';
+
+/** 上报系统语言，返回后端生效的界面语言 */
+export async function syncUiLanguage(
+  systemLanguage: string = navigator.language
+): Promise<UiLanguage> {
+  return await invoke<UiLanguage>('sync_ui_language', { systemLanguage });
+}
+
+/** 保存界面语言，传 'system' 或不传表示跟随系统 */
+export async function saveUiLanguage(
+  language?: UiLanguage | 'system'
+): Promise<UiLanguage> {
+  return await invoke<UiLanguage>('save_ui_language', { language });
+}
+
 // ============ 语音识别 (STT) ============
 
 export async function transcribeAudio(
@@ -40,6 +178,91 @@ export async function transcribeAudio(
   return await invoke<string>('stt_transcribe', { audioBytes, mimeType });
 }
 
+export interface SttWord {
+  text: string;
+  startTime: number;
+  endTime: number;
+  blankDuration: number;
+}
+
+export interface SttUtterance {
+  text: string;
+  startTime: number;
+  endTime: number;
+  definite: boolean;
+  words: SttWord[];
+}
+
+export interface SttTranscript {
+  text: string;
+  utterances: SttUtterance[];
+  durationMs: number | null;
+}
+
+export async function transcribeAudioDetailed(
+  audioBytes: number[],
+  mimeType: string
+): Promise<SttTranscript> {
+  return await invoke<SttTranscript>('stt_transcribe_detailed', {
+    audioBytes,
+    mimeType,
+  });
+}
+
+export async function startSttStream(mimeType: string): Promise<void> {
+  await invoke('stt_stream_start', { mimeType });
+}
+
+export async function pushSttStream(chunk: number[]): Promise<void> {
+  await invoke('stt_stream_push', { chunk });
+}
+
+export async function finishSttStream(): Promise<string> {
+  return await invoke<string>('stt_stream_finish');
+}
+
+export function onSttPartial(
+  callback: (text: string) => void
+): Promise<UnlistenFn> {
+  return listen<{ text: string }>('stt-partial', (event) => {
+    callback(event.payload.text);
+  });
+}
+
+export function onSttFinal(
+  callback: (text: string) => void
+): Promise<UnlistenFn> {
+  return listen<{ text: string }>('stt-final', (event) => {
+    callback(event.payload.text);
+  });
+}
+
+export function onSttStreamError(
+  callback: (error: AppError) => void
+): Promise<UnlistenFn> {
+  return listen<AppError>('stt-stream-error', (event) => {
+    callback(event.payload);
+  });
+}
+
+/** 流式输入（PCM）检测到开口，`atMs` 为相对录音开头的毫秒数 */
+export function onSttSpeechStart(
+  callback: (atMs: number) => void
+): Promise<UnlistenFn> {
+  return listen<{ atMs: number }>('stt-speech-start', (event) => {
+    callback(event.payload.atMs);
+  });
+}
+
+/** 流式输入（PCM）一句话说完，可据此自动停止录音 */
+export function onSttUtteranceEnd(
+  callback: (atMs: number) => void
+): Promise<UnlistenFn> {
+  return listen<{ atMs: number }>('stt-utterance-end', (event) => {
+    callback(event.payload.atMs);
+  });
+}
+
 // ============ Agent 调用 ============
 
 export async function runAgent(prompt: string): Promise<string> {
@@ -63,10 +286,10 @@ export function onAgentFinished(
 }
 
 export function onAgentError(
-  callback: (message: string) => void
+  callback: (error: AppError) => void
 ): Promise<UnlistenFn> {
-  return listen<{ message: string }>('agent-error', (event) => {
-    callback(event.payload.message);
+  return listen<AppError>('agent-error', (event) => {
+    callback(event.payload);
   });
 }
 
@@ -74,17 +297,51 @@ export function onAgentError(
 
 export async function speakText(
   text: string,
-  voiceType?: string
+  voiceType?: string,
+  audio?: TtsAudioParams
 ): Promise<Uint8Array> {
-  const bytes = await invoke<number[]>('tts_speak', { text, voiceType });
+  const bytes = await invoke<number[]>('tts_speak', { text, voiceType, audio });
   return new Uint8Array(bytes);
 }
 
 export async function speakTextStream(
   text: string,
-  voiceType?: string
+  voiceType?: string,
+  audio?: TtsAudioParams
+): Promise<void> {
+  await invoke('tts_speak_stream', { text, voiceType, audio });
+}
+
+export async function openTtsSession(
+  voiceType?: string,
+  audio?: TtsAudioParams
 ): Promise<void> {
-  await invoke('tts_speak_stream', { text, voiceType });
+  await invoke('tts_session_open', { voiceType, audio });
+}
+
+export async function pushTtsSession(text: string): Promise<void> {
+  await invoke('tts_session_push', { text });
+}
+
+export async function closeTtsSession(): Promise<number> {
+  return await invoke<number>('tts_session_close');
+}
+
+export async function cancelTts(): Promise<boolean> {
+  return await invoke<boolean>('tts_cancel');
+}
+
+export async function prewarmTts(): Promise<void> {
+  await invoke('tts_prewarm');
+}
+
+export interface TtsCacheStats {
+  entries: number;
+  bytes: number;
+}
+
+export async function clearTtsCache(): Promise<TtsCacheStats> {
+  return await invoke<TtsCacheStats>('tts_cache_clear');
 }
 
 export function onTtsStreamChunk(
@@ -103,11 +360,63 @@ export function onTtsStreamFinished(
   });
 }
 
+export interface TtsFirstAudio {
+  latencyMs: number;
+  reusedConnection: boolean;
+}
+
+export function onTtsFirstAudio(
+  callback: (info: TtsFirstAudio) => void
+): Promise<UnlistenFn> {
+  return listen<TtsFirstAudio>('tts-first-audio', (event) => {
+    callback(event.payload);
+  });
+}
+
+export function onTtsStreamCancelled(
+  callback: (totalBytes: number) => void
+): Promise<UnlistenFn> {
+  return listen<{ totalBytes: number }>('tts-stream-cancelled', (event) => {
+    callback(event.payload.totalBytes);
+  });
+}
+
+export interface TtsSubtitleWord {
+  text: string;
+  startMs: number;
+  endMs: number;
+}
+
+export interface TtsSentence {
+  phase: 'start' | 'end';
+  index: number;
+  text: string;
+  startByte: number;
+  endByte: number;
+  startMs: number;
+  endMs: number;
+  words: TtsSubtitleWord[];
+}
+
+export function onTtsSentence(
+  callback: (sentence: TtsSentence) => void
+): Promise<UnlistenFn> {
+  return listen<TtsSentence>('tts-sentence', (event) => {
+    callback(event.payload);
+  });
+}
+
+export async function exportTtsSubtitles(
+  format: 'srt' | 'vtt'
+): Promise<string> {
+  return await invoke<string>('tts_export_subtitles', { format });
+}
+
 export function onTtsStreamError(
-  callback: (message: string) => void
+  callback: (error: AppError) => void
 ): Promise<UnlistenFn> {
-  return listen<{ message: string }>('tts-stream-error', (event) => {
-    callback(event.payload.message);
+  return listen<AppError>('tts-stream-error', (event) => {
+    callback(event.payload);
   });
 }
 
@@ -132,6 +441,61 @@ export async function appendVoiceSessionEntry(
   await invoke('voice_session_append', entry);
 }
 
+// ============ 语音耗时统计 ============
+
+/** 被计时的环节，耗时均为毫秒 */
+export type VoiceStage =
+  | 'sttConnect'
+  | 'sttUpload'
+  | 'sttFinal'
+  | 'agentSpawn'
+  | 'agentFirstOutput'
+  | 'agentExit'
+  | 'ttsConnect'
+  | 'ttsFirstAudio'
+  | 'ttsFinish';
+
+/** 一次交互（识别 → Agent → 合成）各环节的耗时 */
+export interface VoiceInteractionMetrics {
+  id: string;
+  startedAt: number;
+  stages: Partial<Record<VoiceStage, number>>;
+}
+
+export interface VoiceStageStats {
+  count: number;
+  p50: number;
+  p90: number;
+  p99: number;
+  max: number;
+}
+
+export interface VoiceStats {
+  interactions: number;
+  stages: Partial<Record<VoiceStage, VoiceStageStats>>;
+}
+
+/** 最近 `limit` 次交互（默认 100）各环节耗时的分位数 */
+export async function getVoiceStats(limit?: number): Promise<VoiceStats> {
+  return await invoke<VoiceStats>('voice_stats', { limit });
+}
+
+/** 一次交互结束时推送的耗时明细 */
+export function onVoiceMetrics(
+  callback: (metrics: VoiceInteractionMetrics) => void
+): Promise<UnlistenFn> {
+  return listen<VoiceInteractionMetrics>('voice-metrics', (event) => {
+    callback(event.payload);
+  });
+}
+
+// ============ 诊断 ============
+
+/** 导出诊断包（最近日志、脱敏配置与版本信息），返回 zip 文件路径 */
+export async function exportDiagnostics(): Promise<string> {
+  return await invoke<string>('diagnostics_export');
+}
+
 // ============ 窗口控制 ============
 
 export async function setWindowSize(